@@ -0,0 +1,30 @@
+#ifndef LOA_SCRAPER_H
+#define LOA_SCRAPER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Scrapes volumes start..=end (end == 0 means no upper bound) and returns them as
+ * a UTF-8 JSON array. Returns NULL on failure; call loa_last_error() for details.
+ * The result must be released with loa_free_string().
+ */
+char *loa_scrape_to_json(uint32_t start, uint32_t end);
+
+/*
+ * Returns the last error message raised on the calling thread, or NULL. The result
+ * must be released with loa_free_string().
+ */
+char *loa_last_error(void);
+
+/* Releases a string returned by this library. Passing NULL is a no-op. */
+void loa_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LOA_SCRAPER_H */
@@ -0,0 +1,100 @@
+use crate::Volume;
+use crate::http::{Client, Response};
+use crate::output::{OutputSink, block_on};
+use crate::volume::{UPSERT_KEY, dedupe};
+use anyhow::{Result, anyhow, bail};
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+pub const API_URL: &str = "https://api.airtable.com/v0";
+
+/// Airtable accepts at most this many records per request.
+const BATCH_SIZE: usize = 10;
+
+/// Airtable allows five requests a second per base.
+const BATCH_DELAY: Duration = Duration::from_millis(250);
+
+/// How long Airtable asks clients to back off after a 429.
+const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
+
+/// An Airtable table that volumes are upserted into, matched on its `row_key` field.
+/// The table needs a field for every column written; `typecast` lets Airtable convert
+/// the values to the fields' types. Writing works with or without a Tokio runtime, of
+/// either flavor, and blocks until every batch is sent.
+#[derive(Debug, Clone)]
+pub struct AirtableSink {
+    pub base: String,
+    pub table: String,
+    pub api_key: String,
+}
+
+impl AirtableSink {
+    fn url(&self) -> Result<String> {
+        let mut url = reqwest::Url::parse(API_URL)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("Bad Airtable API URL"))?
+            .push(&self.base)
+            .push(&self.table);
+        Ok(url.to_string())
+    }
+
+    async fn upsert(&self, volumes: &[Volume]) -> Result<()> {
+        let client = Client::new();
+        let url = self.url()?;
+        let records = volumes
+            .iter()
+            .map(|v| match serde_json::to_value(v)? {
+                Value::Object(mut fields) => {
+                    fields.insert(UPSERT_KEY.to_string(), v.key().into());
+                    Ok(json!({ "fields": fields }))
+                }
+                _ => Err(anyhow!("Volume did not serialize to an object")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, batch) in records.chunks(BATCH_SIZE).enumerate() {
+            if i > 0 {
+                tokio::time::sleep(BATCH_DELAY).await;
+            }
+            let body = json!({
+                "performUpsert": { "fieldsToMergeOn": [UPSERT_KEY] },
+                "typecast": true,
+                "records": batch,
+            });
+            let mut response = self.send(&client, &url, &body).await?;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                tokio::time::sleep(RATE_LIMIT_DELAY).await;
+                response = self.send(&client, &url, &body).await?;
+            }
+            if !response.status().is_success() {
+                bail!(
+                    "Airtable rejected the records ({}): {}",
+                    response.status(),
+                    response.text()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(&self, client: &Client, url: &str, body: &Value) -> Result<Response> {
+        client
+            .request(Method::PATCH, url)
+            .bearer_auth(&self.api_key)
+            .json(body)
+            .send()
+            .await
+    }
+}
+
+impl OutputSink for AirtableSink {
+    fn describe(&self) -> String {
+        format!("Airtable {}/{}", self.base, self.table)
+    }
+
+    fn write(&self, volumes: &[Volume]) -> Result<()> {
+        let volumes = dedupe(volumes);
+        block_on(self.upsert(&volumes))
+    }
+}
@@ -0,0 +1,305 @@
+//! A read-only JSON API over the volumes, for catalog frontends: `/volumes`,
+//! `/volumes/{n}`, and `/authors`, filtered with query parameters, plus `/graphql`
+//! with the graphql feature.
+
+use crate::Volume;
+use crate::output::author_index;
+use crate::ownership::OWNED;
+use crate::scrape::STATUS_PUBLISHED;
+use crate::search::search;
+use anyhow::{Context, Result, anyhow, bail};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode, header};
+use serde::Serialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+/// Page size when a request doesn't pass `limit`.
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Most volumes one request can ask for.
+pub const MAX_LIMIT: usize = 1000;
+
+/// The volumes being served, swapped out whole when a refresh finishes.
+pub type SharedVolumes = Arc<RwLock<Vec<Volume>>>;
+
+/// The query parameters `/volumes` and `/authors` accept. Every filter given must match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    /// Words that must all appear in the author or title, as `search` matches them.
+    pub q: Option<String>,
+    /// Part of the author's name, ignoring case.
+    pub author: Option<String>,
+    /// `loa` for the main series, or another series' name.
+    pub series: Option<String>,
+    /// `published`, `forthcoming`, or `delisted`.
+    pub status: Option<String>,
+    pub owned: Option<bool>,
+    /// Publication year.
+    pub year: Option<String>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl Filter {
+    /// Parses a query string, rejecting parameters it doesn't know so a typo doesn't
+    /// silently return everything.
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut filter = Filter {
+            limit: DEFAULT_LIMIT,
+            ..Default::default()
+        };
+        for (name, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            let value = value.into_owned();
+            let number = |value: &str| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("{} must be a whole number, not {:?}", name, value))
+            };
+            match name.as_ref() {
+                "q" => filter.q = Some(value),
+                "author" => filter.author = Some(value.to_lowercase()),
+                "series" => filter.series = Some(value.to_lowercase()),
+                "status" => filter.status = Some(value.to_lowercase()),
+                "owned" => {
+                    filter.owned = Some(match value.as_str() {
+                        "true" | "yes" | "1" => true,
+                        "false" | "no" | "0" => false,
+                        _ => bail!("owned must be true or false, not {:?}", value),
+                    })
+                }
+                "year" => filter.year = Some(value),
+                "limit" => filter.limit = number(&value)?.min(MAX_LIMIT),
+                "offset" => filter.offset = number(&value)?,
+                _ => bail!(
+                    "Unknown parameter {:?}; filter with q, author, series, status, owned, year, limit, and offset",
+                    name
+                ),
+            }
+        }
+        Ok(filter)
+    }
+
+    fn matches(&self, volume: &Volume) -> bool {
+        let series = match volume.series.as_str() {
+            "" => "loa",
+            series => series,
+        };
+        let status = match volume.status.as_deref() {
+            None | Some("") => STATUS_PUBLISHED,
+            Some(status) => status,
+        };
+        self.author
+            .as_ref()
+            .is_none_or(|author| volume.author.to_lowercase().contains(author))
+            && self.series.as_ref().is_none_or(|s| s == series)
+            && self.status.as_ref().is_none_or(|s| s == status)
+            && self
+                .owned
+                .is_none_or(|owned| owned == (volume.own_volume == OWNED))
+            && self
+                .year
+                .as_ref()
+                .is_none_or(|year| volume.publication_year.as_ref() == Some(year))
+    }
+
+    /// Every volume the filters match, before paging.
+    pub fn apply<'a>(&self, volumes: &'a [Volume]) -> Vec<&'a Volume> {
+        let candidates: Vec<&Volume> = match &self.q {
+            Some(q) => search(volumes, q, false),
+            None => volumes.iter().collect(),
+        };
+        candidates.into_iter().filter(|v| self.matches(v)).collect()
+    }
+}
+
+/// One page of a listing, with the total so a frontend can page through the rest.
+#[derive(Debug, Serialize)]
+struct Page<T> {
+    total: usize,
+    offset: usize,
+    limit: usize,
+    items: Vec<T>,
+}
+
+impl<T> Page<T> {
+    fn of(items: Vec<T>, filter: &Filter) -> Self {
+        Page {
+            total: items.len(),
+            offset: filter.offset,
+            limit: filter.limit,
+            items: items
+                .into_iter()
+                .skip(filter.offset)
+                .take(filter.limit)
+                .collect(),
+        }
+    }
+}
+
+/// A volume by number in the main series (`/volumes/12`) or by its key, as in the
+/// `row_key` column (`/volumes/aps-3`).
+fn find_volume<'a>(volumes: &'a [Volume], id: &str) -> Option<&'a Volume> {
+    let key = match id.parse::<u32>() {
+        Ok(number) => format!("loa-{}", number),
+        Err(_) => id.to_string(),
+    };
+    volumes.iter().find(|v| v.key() == key)
+}
+
+/// The status and JSON body for a request. Kept apart from the server so it can be
+/// exercised without a socket.
+pub fn respond(
+    volumes: &[Volume],
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+) -> (StatusCode, serde_json::Value) {
+    let error = |status: StatusCode, message: String| (status, json!({ "error": message }));
+    if method != Method::GET {
+        return error(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "The API is read-only; use GET".to_string(),
+        );
+    }
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let filter = || Filter::parse(query.unwrap_or_default());
+    let listing = |page: Result<serde_json::Value>| match page {
+        Ok(page) => (StatusCode::OK, page),
+        Err(e) => error(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+
+    match segments.as_slice() {
+        ["volumes"] => listing(filter().and_then(|filter| {
+            let matching = filter.apply(volumes);
+            Ok(serde_json::to_value(Page::of(matching, &filter))?)
+        })),
+        ["volumes", id] => {
+            let id = urlencoding::decode(id).map_or_else(|_| id.to_string(), |id| id.into_owned());
+            match find_volume(volumes, &id) {
+                Some(volume) => (StatusCode::OK, json!(volume)),
+                None => error(StatusCode::NOT_FOUND, format!("No volume {}", id)),
+            }
+        }
+        ["authors"] => listing(filter().and_then(|filter| {
+            let matching: Vec<Volume> = filter.apply(volumes).into_iter().cloned().collect();
+            Ok(serde_json::to_value(Page::of(
+                author_index(&matching),
+                &filter,
+            ))?)
+        })),
+        #[cfg(not(feature = "graphql"))]
+        ["graphql"] => error(
+            StatusCode::NOT_FOUND,
+            "Can't serve GraphQL: built without the graphql feature".to_string(),
+        ),
+        _ => error(
+            StatusCode::NOT_FOUND,
+            format!(
+                "No such endpoint {}; try /volumes, /volumes/{{n}}, or /authors",
+                path
+            ),
+        ),
+    }
+}
+
+/// Lets frontends served from another origin call the API.
+fn cors(response: hyper::http::response::Builder) -> hyper::http::response::Builder {
+    response
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, POST")
+        .header(header::ACCESS_CONTROL_ALLOW_HEADERS, "content-type")
+}
+
+fn json_response(
+    status: StatusCode,
+    body: serde_json::Value,
+) -> hyper::http::Result<Response<Body>> {
+    cors(Response::builder())
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+}
+
+/// Runs a GraphQL request, sent as JSON in a POST body or as `?query=` in a GET.
+#[cfg(feature = "graphql")]
+async fn graphql(
+    schema: &crate::graphql::LoaSchema,
+    request: Request<Body>,
+    volumes: &SharedVolumes,
+) -> (StatusCode, serde_json::Value) {
+    let query = request.uri().query().unwrap_or_default().to_string();
+    let parsed: Result<async_graphql::Request> = match *request.method() {
+        Method::POST => hyper::body::to_bytes(request.into_body())
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|body| Ok(serde_json::from_slice(&body)?)),
+        Method::GET => url::form_urlencoded::parse(query.as_bytes())
+            .find(|(name, _)| name == "query")
+            .map(|(_, query)| async_graphql::Request::new(query))
+            .ok_or_else(|| anyhow!("Pass the GraphQL query as ?query=")),
+        _ => Err(anyhow!("Send GraphQL queries with POST or GET")),
+    };
+    match parsed {
+        Ok(parsed) => {
+            let snapshot = volumes.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let response = crate::graphql::execute(schema, parsed, snapshot).await;
+            (StatusCode::OK, json!(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            json!({ "errors": [{ "message": e.to_string() }] }),
+        ),
+    }
+}
+
+/// Binds `addr` and returns the server, which answers from whatever `volumes` holds
+/// at the time of each request until the process is stopped.
+pub fn serve(
+    addr: SocketAddr,
+    volumes: SharedVolumes,
+) -> Result<impl Future<Output = Result<()>> + Send> {
+    #[cfg(feature = "graphql")]
+    let schema = crate::graphql::schema();
+    let make_service = make_service_fn(move |_| {
+        let volumes = volumes.clone();
+        #[cfg(feature = "graphql")]
+        let schema = schema.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let volumes = volumes.clone();
+                #[cfg(feature = "graphql")]
+                let schema = schema.clone();
+                async move {
+                    if request.method() == Method::OPTIONS {
+                        return cors(Response::builder())
+                            .status(StatusCode::NO_CONTENT)
+                            .body(Body::empty());
+                    }
+                    #[cfg(feature = "graphql")]
+                    if request.uri().path().trim_end_matches('/') == "/graphql" {
+                        let (status, body) = graphql(&schema, request, &volumes).await;
+                        return json_response(status, body);
+                    }
+                    let (status, body) = {
+                        let volumes = volumes.read().unwrap_or_else(|e| e.into_inner());
+                        respond(
+                            &volumes,
+                            request.method(),
+                            request.uri().path(),
+                            request.uri().query(),
+                        )
+                    };
+                    json_response(status, body)
+                }
+            }))
+        }
+    });
+    let server = Server::try_bind(&addr)
+        .with_context(|| format!("Couldn't listen on {}", addr))?
+        .serve(make_service);
+    Ok(async move { Ok(server.await?) })
+}
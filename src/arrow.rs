@@ -0,0 +1,34 @@
+use crate::Volume;
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use arrow_json::ReaderBuilder;
+use arrow_json::reader::infer_json_schema_from_iterator;
+use std::sync::Arc;
+
+/// Converts scraped volumes into a single Arrow `RecordBatch`.
+///
+/// The schema is inferred from the serialized fields, so numeric columns such as
+/// `volume_number` keep their type instead of being flattened to strings. No volumes
+/// give an empty batch with the columns every volume has.
+pub fn to_record_batch(volumes: &[Volume]) -> Result<RecordBatch> {
+    let values = volumes
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = if values.is_empty() {
+        let columns = serde_json::to_value(Volume::default())?;
+        infer_json_schema_from_iterator(std::iter::once(Ok(columns)))?
+    } else {
+        infer_json_schema_from_iterator(values.iter().map(Ok))?
+    };
+    let schema = Arc::new(schema);
+
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(volumes.len().max(1))
+        .build_decoder()?;
+    decoder.serialize(&values)?;
+
+    Ok(decoder
+        .flush()?
+        .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+}
@@ -0,0 +1,39 @@
+use crate::wikidata::AuthorInfo;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Author lookups from earlier runs, used when Wikidata or Wikipedia can't be reached.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorCache {
+    pub authors: BTreeMap<String, AuthorInfo>,
+}
+
+/// Where the CLI keeps the author cache.
+pub fn default_cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("authors.json")
+}
+
+impl AuthorCache {
+    /// Loads the cache, treating a missing or unreadable file as empty since it only
+    /// ever holds data that can be fetched again.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write author cache {}", path.display()))
+    }
+}
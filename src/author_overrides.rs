@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The article chosen for an author whose lookup was ambiguous.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthorOverride {
+    /// Empty when the author has no article, so none is looked up.
+    pub wikipedia_link: String,
+    /// The Wikidata item chosen, when the choice came from Wikidata.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub qid: String,
+}
+
+/// Wikipedia articles picked by hand (with `--interactive`, or by editing the file) for
+/// authors the lookups get wrong or can't decide on. They win over the lookups on
+/// every run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorOverrides {
+    pub authors: BTreeMap<String, AuthorOverride>,
+}
+
+/// Where the CLI keeps the author overrides.
+pub fn default_overrides_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("author-overrides.json")
+}
+
+impl AuthorOverrides {
+    /// Loads the overrides, treating a missing file as none. Unlike the cache, a file
+    /// that doesn't parse is an error: it holds choices that can't be fetched again.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read author overrides {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse author overrides {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write author overrides {}", path.display()))
+    }
+}
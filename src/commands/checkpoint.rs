@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use colored::*;
+use loa_scraper::Volume;
+use loa_scraper::scrape::VolumeData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// Exit status for a run that stopped early but can be continued with `--resume`
+/// (EX_TEMPFAIL from sysexits.h).
+pub const EXIT_RESUMABLE: i32 = 75;
+
+/// Exit status for a second Ctrl-C, which doesn't wait for the checkpoint (128 + SIGINT).
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// A token the first Ctrl-C cancels, so a scrape stops after the request under way and
+/// saves its checkpoint. A second Ctrl-C exits at once.
+pub fn cancel_on_ctrl_c() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!(
+            "\n{} {}",
+            "⏸️".yellow(),
+            "Stopping and saving progress; press Ctrl-C again to quit now".yellow()
+        );
+        cancel.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+    });
+    token
+}
+
+/// Progress of an interrupted scrape: the listings it started from, the volumes built
+/// so far, and which enrichment stages have finished.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub listings: Vec<VolumeData>,
+    pub volumes: Vec<Volume>,
+    pub completed: Vec<String>,
+    /// Volumes already processed by the first unfinished in-place stage.
+    pub progress: usize,
+}
+
+pub fn default_checkpoint_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("checkpoint.json")
+}
+
+impl Checkpoint {
+    pub fn new(listings: Vec<VolumeData>) -> Self {
+        Checkpoint {
+            listings,
+            ..Default::default()
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("No checkpoint to resume at {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse checkpoint {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write checkpoint {}", path.display()))
+    }
+
+    pub fn is_done(&self, stage: &str) -> bool {
+        self.completed.iter().any(|s| s == stage)
+    }
+
+    pub fn finish(&mut self, stage: &str) {
+        self.completed.push(stage.to_string());
+        self.progress = 0;
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::input::read_volumes;
+use loa_scraper::output::{self, OutputFormat, OutputSink, OutputTarget, format_for_path};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// CSV, JSON, protobuf (.pb) or SQLite export to convert
+    pub input: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Output file path, or a postgres:// connection string (default: stdout). Repeat to write several at once, each in the format its extension names (or FORMAT:PATH)"
+    )]
+    pub output: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "Output format (default: from the output's extension, else csv)"
+    )]
+    pub format: Option<OutputFormat>,
+
+    #[command(flatten)]
+    pub html: super::HtmlArgs,
+}
+
+pub fn run(args: ConvertArgs) -> Result<()> {
+    let volumes = read_volumes(&args.input)?;
+
+    let format = args.format.unwrap_or_else(|| match args.output.as_slice() {
+        [path] => format_for_path(path).unwrap_or(OutputFormat::Csv),
+        _ => OutputFormat::Csv,
+    });
+    let targets = OutputTarget::from_args(&args.output, format, args.html.options())?;
+    let sinks: Vec<&dyn OutputSink> = targets.iter().map(|t| t as &dyn OutputSink).collect();
+    output::write_all(&volumes, &sinks)?;
+
+    for output_path in targets.iter().filter_map(|t| t.path.as_ref()) {
+        eprintln!(
+            "{} {} {} volumes from '{}' to '{}'",
+            "💾".green(),
+            "Converted".green().bold(),
+            volumes.len(),
+            args.input.display(),
+            output_path.bright_white()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use super::{DatasetArgs, progress_bar};
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::coverage::{Coverage, LibraryBook, coverage, lookup_book, read_isbn_list};
+use loa_scraper::http::Client;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub struct CoverageArgs {
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "ISBNs of the books you own, one per line (ISBN-10 or ISBN-13)"
+    )]
+    pub isbns: PathBuf,
+
+    #[arg(
+        long,
+        help = "Match by ISBN only, without looking titles up on OpenLibrary"
+    )]
+    pub offline: bool,
+
+    #[arg(long, help = "Print the full report as JSON instead of a table")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+async fn lookup_books(isbns: &[String]) -> Result<Vec<LibraryBook>> {
+    let client = Client::new();
+    let pb = progress_bar(isbns.len());
+    let mut books = Vec::with_capacity(isbns.len());
+    for isbn in isbns {
+        client.check_budget()?;
+        books.push(lookup_book(&client, isbn).await);
+        client.check_refused()?;
+        pb.inc(1);
+    }
+    pb.finish_with_message("Complete!");
+    Ok(books)
+}
+
+fn print_report(report: &Coverage) {
+    println!("{:<22} {}", "Library books", report.library_books);
+    println!("{:<22} {}", "LOA volumes", report.loa_editions.len());
+    println!("{:<22} {}", "Collected in LOA", report.collected);
+    println!("{:<22} {:.0}%", "Coverage", report.percent());
+    if report.unresolved > 0 {
+        println!("{:<22} {}", "Matched by ISBN only", report.unresolved);
+    }
+
+    if !report.loa_editions.is_empty() {
+        println!();
+        println!(
+            "{:<10} {:<15} {}",
+            "Volume".bold(),
+            "ISBN".bold(),
+            "Title".bold()
+        );
+        for edition in &report.loa_editions {
+            println!(
+                "{:<10} {:<15} {}",
+                edition.volume, edition.isbn, edition.title
+            );
+        }
+    }
+
+    if !report.duplicates.is_empty() {
+        println!();
+        println!(
+            "{} {}",
+            "⚠️".yellow(),
+            "These volumes collect books you already own:"
+                .yellow()
+                .bold()
+        );
+        for duplicate in &report.duplicates {
+            println!(
+                "{:<10} {} ({})",
+                duplicate.volume, duplicate.title, duplicate.author
+            );
+            for work in &duplicate.owned_works {
+                println!("{:<10} - {}", "", work);
+            }
+        }
+    }
+}
+
+/// Reads the `--isbns` list, looking each book up on OpenLibrary unless `offline`.
+pub async fn load_books(path: &Path, offline: bool) -> Result<Vec<LibraryBook>> {
+    let (isbns, invalid) = read_isbn_list(path)?;
+    for line in &invalid {
+        eprintln!(
+            "{} {} {}",
+            "⚠️".yellow(),
+            "Skipping invalid ISBN:".yellow().bold(),
+            line
+        );
+    }
+
+    if offline {
+        return Ok(isbns
+            .into_iter()
+            .map(|isbn| LibraryBook {
+                isbn,
+                ..LibraryBook::default()
+            })
+            .collect());
+    }
+    eprintln!(
+        "{} {} {} books on OpenLibrary",
+        "🔍".cyan(),
+        "Looking up".cyan(),
+        isbns.len()
+    );
+    lookup_books(&isbns).await
+}
+
+pub async fn run(args: CoverageArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let books = load_books(&args.isbns, args.offline).await?;
+
+    let report = coverage(&volumes, &books);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::digest::{
+    Digest, DigestState, default_digest_state_path, write_markdown, write_text,
+};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+const SINCE_LAST_RUN: &str = "last-run";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DigestFormat {
+    Markdown,
+    Text,
+}
+
+#[derive(Args, Debug)]
+pub struct DigestArgs {
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = SINCE_LAST_RUN,
+        help = "Cover changes since the previous digest (last-run) or since a date like 2024-05-01"
+    )]
+    pub since: String,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = DigestFormat::Markdown,
+        help = "Output format"
+    )]
+    pub format: DigestFormat,
+
+    #[arg(short, long, help = "Output file path (default: stdout)")]
+    pub output: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Where the last digest time is kept (default: digest.json in the user data directory)"
+    )]
+    pub digest_state: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+pub fn run(args: DigestArgs) -> Result<()> {
+    let state_path = args
+        .digest_state
+        .clone()
+        .unwrap_or_else(default_digest_state_path);
+    let mut state = DigestState::load(&state_path)?;
+    let since_last_run = args.since == SINCE_LAST_RUN;
+    let since = if since_last_run {
+        state.last_run.clone()
+    } else {
+        Some(args.since.clone())
+    };
+
+    let (volumes, _) = args.dataset.load()?;
+    let digest = Digest::from_volumes(&volumes, since.as_deref());
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    match args.format {
+        DigestFormat::Markdown => write_markdown(&digest, &mut out)?,
+        DigestFormat::Text => write_text(&digest, &mut out)?,
+    }
+    out.flush()?;
+
+    // Only the rolling digest advances; a digest for a fixed date is a one-off
+    if since_last_run {
+        state.last_run = Some(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        state.save(&state_path)?;
+    }
+
+    eprintln!(
+        "{} {} {} new, {} forthcoming, {} delisted",
+        "📰".green(),
+        "Digest:".green().bold(),
+        digest.added.len(),
+        digest.forthcoming.len(),
+        digest.delisted.len()
+    );
+    Ok(())
+}
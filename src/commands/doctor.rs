@@ -0,0 +1,269 @@
+use super::checkpoint::{Checkpoint, default_checkpoint_path};
+use anyhow::{Result, bail};
+use clap::Args;
+use colored::*;
+use loa_scraper::author_cache::{AuthorCache, default_cache_path};
+use loa_scraper::constants::{SERIES_METADATA_ENV, SeriesMetadata, default_series_metadata_path};
+use loa_scraper::http::Client;
+use loa_scraper::scrape::{COLLECTION_URL, LISTING_SELECTORS, parse_volumes};
+use loa_scraper::store::{Store, default_store_path};
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const WIKIPEDIA_URL: &str =
+    "https://en.wikipedia.org/w/api.php?action=query&meta=siteinfo&format=json";
+
+/// Days after which the author cache is reported as stale.
+const STALE_CACHE_DAYS: u64 = 90;
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    #[arg(long, help = "Skip the checks that need the network")]
+    pub offline: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Store database (default: store.db in the user data directory)"
+    )]
+    pub store: Option<PathBuf>,
+}
+
+enum Outcome {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Check {
+    name: String,
+    outcome: Outcome,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, outcome: Outcome, detail: impl Into<String>) -> Self {
+        Check {
+            name: name.into(),
+            outcome,
+            detail: detail.into(),
+        }
+    }
+
+    fn print(&self) {
+        let mark = match self.outcome {
+            Outcome::Ok => "ok".green().bold(),
+            Outcome::Warn => "warn".yellow().bold(),
+            Outcome::Fail => "FAIL".red().bold(),
+        };
+        println!("[{:>4}] {:<28} {}", mark, self.name, self.detail);
+    }
+}
+
+/// Fetches a page, reporting how it went; the body comes back only on success.
+async fn fetch(client: &Client, name: &str, url: &str, checks: &mut Vec<Check>) -> Option<String> {
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => {
+            checks.push(Check::new(name, Outcome::Ok, format!("{} reachable", url)));
+            Some(response.text())
+        }
+        Ok(response) => {
+            checks.push(Check::new(
+                name,
+                Outcome::Fail,
+                format!("{} returned {}", url, response.status()),
+            ));
+            None
+        }
+        Err(e) => {
+            checks.push(Check::new(name, Outcome::Fail, format!("{}: {}", url, e)));
+            None
+        }
+    }
+}
+
+fn check_selectors(page: &str, checks: &mut Vec<Check>) {
+    let html = Html::parse_document(page);
+    for (what, css) in LISTING_SELECTORS {
+        let name = format!("selector: {}", what);
+        let count = Selector::parse(css)
+            .map(|selector| html.select(&selector).count())
+            .unwrap_or(0);
+        let outcome = if count > 0 {
+            Outcome::Ok
+        } else {
+            Outcome::Fail
+        };
+        checks.push(Check::new(
+            name,
+            outcome,
+            format!("{} matches {} elements", css, count),
+        ));
+    }
+
+    match parse_volumes(&html) {
+        Ok(listings) if !listings.is_empty() => checks.push(Check::new(
+            "listing parser",
+            Outcome::Ok,
+            format!("{} numbered volumes on the collection page", listings.len()),
+        )),
+        Ok(_) => checks.push(Check::new(
+            "listing parser",
+            Outcome::Fail,
+            "no numbered volumes found on the collection page",
+        )),
+        Err(e) => checks.push(Check::new("listing parser", Outcome::Fail, e.to_string())),
+    }
+}
+
+fn check_config(checks: &mut Vec<Check>) {
+    let (path, from_env) = match std::env::var_os(SERIES_METADATA_ENV) {
+        Some(path) => (PathBuf::from(path), true),
+        None => (default_series_metadata_path(), false),
+    };
+    let check = if !path.exists() {
+        if from_env {
+            Check::new(
+                "series metadata config",
+                Outcome::Fail,
+                format!(
+                    "{} names {}, which doesn't exist",
+                    SERIES_METADATA_ENV,
+                    path.display()
+                ),
+            )
+        } else {
+            Check::new(
+                "series metadata config",
+                Outcome::Ok,
+                format!("none at {}, using defaults", path.display()),
+            )
+        }
+    } else {
+        match SeriesMetadata::load(&path) {
+            Ok(_) => Check::new(
+                "series metadata config",
+                Outcome::Ok,
+                format!("{} is valid", path.display()),
+            ),
+            Err(e) => Check::new("series metadata config", Outcome::Fail, format!("{:#}", e)),
+        }
+    };
+    checks.push(check);
+}
+
+fn age_days(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(SystemTime::now().duration_since(modified).ok()?.as_secs() / 86_400)
+}
+
+fn check_caches(store_path: &Path, checks: &mut Vec<Check>) {
+    let cache_path = default_cache_path();
+    let check = if !cache_path.exists() {
+        Check::new(
+            "author cache",
+            Outcome::Ok,
+            format!("none yet at {}", cache_path.display()),
+        )
+    } else {
+        // AuthorCache::load treats a corrupt file as empty, so parse it here to tell
+        match fs::read_to_string(&cache_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(serde_json::from_str::<AuthorCache>(&text)?))
+        {
+            Err(e) => Check::new(
+                "author cache",
+                Outcome::Warn,
+                format!(
+                    "{} is unreadable and will be rebuilt: {}",
+                    cache_path.display(),
+                    e
+                ),
+            ),
+            Ok(cache) => match age_days(&cache_path) {
+                Some(days) if days > STALE_CACHE_DAYS => Check::new(
+                    "author cache",
+                    Outcome::Warn,
+                    format!(
+                        "{} authors, last written {} days ago",
+                        cache.authors.len(),
+                        days
+                    ),
+                ),
+                _ => Check::new(
+                    "author cache",
+                    Outcome::Ok,
+                    format!("{} authors", cache.authors.len()),
+                ),
+            },
+        }
+    };
+    checks.push(check);
+
+    let checkpoint_path = default_checkpoint_path();
+    if checkpoint_path.exists() {
+        let check = match Checkpoint::load(&checkpoint_path) {
+            Ok(checkpoint) => Check::new(
+                "checkpoint",
+                Outcome::Warn,
+                format!(
+                    "an interrupted scrape of {} listings can be continued with --resume",
+                    checkpoint.listings.len()
+                ),
+            ),
+            Err(e) => Check::new("checkpoint", Outcome::Fail, format!("{:#}", e)),
+        };
+        checks.push(check);
+    }
+
+    let check = if !store_path.exists() {
+        Check::new(
+            "sync store",
+            Outcome::Ok,
+            format!("none yet at {}", store_path.display()),
+        )
+    } else {
+        match Store::open(store_path).and_then(|store| store.volumes()) {
+            Ok(volumes) => Check::new(
+                "sync store",
+                Outcome::Ok,
+                format!("{} volumes in {}", volumes.len(), store_path.display()),
+            ),
+            Err(e) => Check::new("sync store", Outcome::Fail, format!("{:#}", e)),
+        }
+    };
+    checks.push(check);
+}
+
+pub async fn run(args: DoctorArgs) -> Result<()> {
+    let mut checks = Vec::new();
+
+    if !args.offline {
+        let client = Client::new();
+        if let Some(page) = fetch(&client, "loa.org", COLLECTION_URL, &mut checks).await {
+            check_selectors(&page, &mut checks);
+        }
+        fetch(&client, "Wikipedia", WIKIPEDIA_URL, &mut checks).await;
+    }
+    check_config(&mut checks);
+    check_caches(
+        &args.store.clone().unwrap_or_else(default_store_path),
+        &mut checks,
+    );
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| matches!(c.outcome, Outcome::Fail))
+        .count();
+    if failed > 0 {
+        bail!("{} of {} checks failed", failed, checks.len());
+    }
+    eprintln!("{} {}", "🩺".green(), "All checks passed".green().bold());
+    Ok(())
+}
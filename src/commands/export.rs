@@ -0,0 +1,104 @@
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::output::{self, OutputFormat, OutputSink, OutputTarget};
+use loa_scraper::store::{Store, default_store_path};
+use loa_scraper::title_style::{TitleStyle, restyle};
+use std::fs::File;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[arg(
+        short,
+        long,
+        help = "Output file path, or a postgres:// connection string (default: stdout). Repeat to write several at once, each in the format its extension names (or FORMAT:PATH)"
+    )]
+    pub output: Vec<String>,
+
+    #[command(flatten)]
+    pub services: super::ServiceArgs,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Csv,
+        help = "Output format"
+    )]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub html: super::HtmlArgs,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Store database (default: store.db in the user data directory)"
+    )]
+    pub store: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        help = "Strip these comma-separated columns from the output, e.g. own_volume,cover_path"
+    )]
+    pub redact: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TitleStyle::AsScraped,
+        help = "Capitalize titles in this style instead of as LOA wrote them"
+    )]
+    pub title_style: TitleStyle,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Also write each unique author once, with their Wikipedia link and volumes, to this CSV"
+    )]
+    pub authors_output: Option<String>,
+}
+
+pub fn run(args: ExportArgs) -> Result<()> {
+    let store_path = args.store.unwrap_or_else(default_store_path);
+    let mut volumes = Store::open(&store_path)?.volumes()?;
+    super::redact(&mut volumes, &args.redact)?;
+    restyle(&mut volumes, args.title_style);
+
+    let services = args.services.sinks()?;
+    let mut targets = OutputTarget::from_args(&args.output, args.format, args.html.options())?;
+    if args.output.is_empty() && !services.is_empty() {
+        // The service is the destination; don't also dump the volumes to stdout
+        targets.clear();
+    }
+    let mut sinks: Vec<&dyn OutputSink> = targets.iter().map(|t| t as &dyn OutputSink).collect();
+    sinks.extend(services.iter().map(|s| s.as_ref()));
+    output::write_all(&volumes, &sinks)?;
+    if let Some(path) = &args.authors_output {
+        output::write_author_index(&volumes, &mut File::create(path)?)?;
+    }
+
+    for service in &services {
+        eprintln!(
+            "{} {} {} volumes to {}",
+            "☁️".green(),
+            "Synced".green().bold(),
+            volumes.len(),
+            service.describe().bright_white()
+        );
+    }
+    for output_path in targets.iter().filter_map(|t| t.path.as_ref()) {
+        eprintln!(
+            "{} {} {} volumes to '{}'",
+            "💾".green(),
+            "Exported".green().bold(),
+            volumes.len(),
+            output_path.bright_white()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use super::{DatasetArgs, progress_bar};
+use anyhow::Result;
+use chrono::{Datelike, Utc};
+use clap::Args;
+use colored::*;
+use loa_scraper::Volume;
+use loa_scraper::forecast::{CADENCE_YEARS, Forecast};
+use loa_scraper::gaps::gaps;
+use loa_scraper::http::Client;
+use loa_scraper::wikidata::notable_works;
+
+#[derive(Args, Debug)]
+pub struct ForecastArgs {
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 10,
+        help = "Check the top N authors' notable works on Wikidata for ones not yet collected"
+    )]
+    pub lookup: usize,
+
+    #[arg(long, help = "Use the catalog alone, without asking Wikidata")]
+    pub offline: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 15,
+        help = "Number of authors to list"
+    )]
+    pub limit: usize,
+
+    #[arg(long, help = "Print the full forecast as JSON instead of a report")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+/// Adds the gap analysis for the leading authors. Stops at the first failed query,
+/// since Wikidata being down would fail the rest too.
+async fn add_gaps(forecast: &mut Forecast, volumes: &[Volume], count: usize) -> Result<()> {
+    let authors: Vec<String> = forecast
+        .authors
+        .iter()
+        .take(count)
+        .map(|f| f.author.clone())
+        .collect();
+    let client = Client::new();
+    let pb = progress_bar(authors.len());
+    for author in &authors {
+        client.check_budget()?;
+        match notable_works(&client, author).await {
+            Ok(Some(notable)) => forecast.add_gaps(&gaps(volumes, author, &notable)),
+            Ok(None) => {}
+            Err(e) => {
+                pb.finish_and_clear();
+                eprintln!(
+                    "{} {} {}",
+                    "⚠️".yellow(),
+                    "Skipping the gap analysis:".yellow().bold(),
+                    e
+                );
+                return Ok(());
+            }
+        }
+        client.check_refused()?;
+        pb.inc(1);
+    }
+    pb.finish_with_message("Complete!");
+    Ok(())
+}
+
+fn print_forecast(forecast: &Forecast, limit: usize) {
+    println!("{}", forecast.note.italic());
+
+    println!();
+    println!("{}", "Announced".bold());
+    if forecast.announced.is_empty() {
+        println!("  Nothing announced (scrape with --include-forthcoming to see announcements)");
+    }
+    for announced in &forecast.announced {
+        let when = announced
+            .release_date
+            .as_deref()
+            .map(|date| format!(" (expected {})", date))
+            .unwrap_or_default();
+        let by = match announced.author.as_str() {
+            "" => String::new(),
+            author => format!(" by {}", author),
+        };
+        println!(
+            "  {:<8} {}{}{}",
+            announced.volume, announced.title, by, when
+        );
+    }
+
+    if let Some(cadence) = &forecast.cadence {
+        println!();
+        println!("{}", "Pace".bold());
+        let this_year = Utc::now().year();
+        let recent: Vec<String> = (this_year - CADENCE_YEARS..this_year)
+            .rev()
+            .map(|year| {
+                let count = cadence.per_year.get(&year).copied().unwrap_or_default();
+                format!("{}: {}", year, count)
+            })
+            .collect();
+        println!(
+            "  About {:.1} volumes a year lately ({})",
+            cadence.average,
+            recent.join(", ")
+        );
+    }
+
+    println!();
+    println!("{}", "Authors likely to get another volume".bold());
+    for author in forecast.authors.iter().take(limit) {
+        println!("  {} (score {})", author.author.bold(), author.score);
+        for reason in &author.reasons {
+            println!("    - {}", reason);
+        }
+    }
+
+    if !forecast.periods.is_empty() {
+        println!();
+        println!("{}", "Periods LOA has been adding to".bold());
+        for period in &forecast.periods {
+            println!(
+                "  {}s: {} recent of {} volumes",
+                period.decade, period.recent, period.volumes
+            );
+        }
+    }
+}
+
+pub async fn run(args: ForecastArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let mut forecast = Forecast::from_volumes(&volumes, Utc::now().year());
+
+    if !args.offline && args.lookup > 0 && !forecast.authors.is_empty() {
+        eprintln!(
+            "{} {} the top {} authors' notable works on Wikidata",
+            "🔍".cyan(),
+            "Looking up".cyan(),
+            args.lookup.min(forecast.authors.len())
+        );
+        add_gaps(&mut forecast, &volumes, args.lookup).await?;
+    }
+
+    if args.json {
+        forecast.authors.truncate(args.limit);
+        println!("{}", serde_json::to_string_pretty(&forecast)?);
+    } else {
+        print_forecast(&forecast, args.limit);
+    }
+    Ok(())
+}
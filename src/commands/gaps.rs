@@ -0,0 +1,78 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::gaps::{GapReport, gaps};
+use loa_scraper::http::Client;
+use loa_scraper::wikidata::notable_works;
+
+#[derive(Args, Debug)]
+pub struct GapsArgs {
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Author to check, by their English name on Wikidata, e.g. \"Willa Cather\""
+    )]
+    pub author: String,
+
+    #[arg(long, help = "Print the full report as JSON instead of a table")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+fn print_report(report: &GapReport) {
+    println!("{:<22} {} ({})", "Author", report.author, report.qid);
+    println!("{:<22} {}", "LOA volumes", report.author_volumes.len());
+    println!("{:<22} {}", "Notable works", report.works.len());
+    println!("{:<22} {}", "Collected in LOA", report.collected());
+    println!();
+    println!(
+        "{:<6} {:<40} {}",
+        "Year".bold(),
+        "Work".bold(),
+        "Volume".bold()
+    );
+    for work in &report.works {
+        let year = work.year.map(|y| y.to_string()).unwrap_or_default();
+        if work.volumes.is_empty() {
+            println!(
+                "{:<6} {:<40} {}",
+                year,
+                work.title,
+                "not collected".yellow()
+            );
+        } else {
+            println!("{:<6} {:<40} {}", year, work.title, work.volumes.join(", "));
+        }
+    }
+}
+
+pub async fn run(args: GapsArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+
+    eprintln!(
+        "{} {} {}'s notable works on Wikidata",
+        "🔍".cyan(),
+        "Looking up".cyan(),
+        args.author
+    );
+    let Some(notable) = notable_works(&Client::new(), &args.author).await? else {
+        eprintln!(
+            "{} {} {}",
+            "⚠️".yellow(),
+            "Wikidata lists no notable works for".yellow().bold(),
+            args.author
+        );
+        return Ok(());
+    };
+
+    let report = gaps(&volumes, &args.author, &notable);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use anyhow::{Result, anyhow};
+use clap::{Args, ValueEnum};
+use colored::*;
+use indicatif::style::ProgressTracker;
+use indicatif::{HumanDuration, ProgressBar, ProgressState, ProgressStyle};
+use loa_scraper::Volume;
+use loa_scraper::airtable::AirtableSink;
+use loa_scraper::input::read_volumes;
+use loa_scraper::output::{self, OutputSink};
+use loa_scraper::ownership::{OwnedState, default_state_path};
+use loa_scraper::progress::{Eta, ThrottleMonitor};
+use loa_scraper::store::{Store, default_store_path};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+pub mod checkpoint;
+pub mod convert;
+pub mod coverage;
+pub mod digest;
+pub mod doctor;
+pub mod export;
+pub mod forecast;
+pub mod gaps;
+pub mod overlap;
+pub mod own;
+pub mod recommend;
+pub mod regress;
+pub mod sample;
+pub mod scrape;
+pub mod search;
+pub mod serve;
+pub mod sets;
+pub mod stats;
+pub mod story;
+pub mod sync;
+pub mod timeline;
+pub mod tui;
+pub mod update;
+pub mod validate;
+
+/// Applies `--redact`, warning about columns the export doesn't have.
+pub fn redact(volumes: &mut [Volume], columns: &[String]) -> Result<()> {
+    if columns.is_empty() {
+        return Ok(());
+    }
+    for column in output::redact(volumes, columns)? {
+        eprintln!(
+            "{} {} '{}' is not a column of this export",
+            "⚠️".yellow(),
+            "Nothing to redact:".yellow().bold(),
+            column
+        );
+    }
+    Ok(())
+}
+
+pub fn progress_bar(len: usize) -> ProgressBar {
+    let pb = ProgressBar::new(len as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb
+}
+
+/// Feeds the bar's position to an [`Eta`] and shows its estimate as `{eta}`.
+#[derive(Clone)]
+struct EtaTracker(Eta);
+
+impl ProgressTracker for EtaTracker {
+    fn clone_box(&self) -> Box<dyn ProgressTracker> {
+        Box::new(self.clone())
+    }
+
+    fn tick(&mut self, state: &ProgressState, now: Instant) {
+        self.0.record(state.pos() as usize, now);
+    }
+
+    fn reset(&mut self, _state: &ProgressState, _now: Instant) {}
+
+    fn write(&self, _state: &ProgressState, w: &mut dyn Write) {
+        let _ = match self.0.remaining(Instant::now()) {
+            Some(left) => write!(w, "{:#}", HumanDuration(left)),
+            None => write!(w, "?"),
+        };
+    }
+}
+
+/// A progress bar for one phase of a scrape, starting at `start` of `len`. Its ETA
+/// follows the phase's recent pace, and hosts throttling or failing requests are
+/// listed after it.
+pub fn stage_progress_bar(len: usize, start: usize, throttle: &ThrottleMonitor) -> ProgressBar {
+    let eta = Eta::new(len, start, Instant::now());
+    let throttle = throttle.clone();
+    let pb = ProgressBar::new(len as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {throttle:.yellow}",
+            )
+            .unwrap()
+            .progress_chars("#>-")
+            .with_key("eta", EtaTracker(eta))
+            .with_key("throttle", move |_: &ProgressState, w: &mut dyn Write| {
+                let _ = write!(w, "{}", throttle.summary(Instant::now()));
+            }),
+    );
+    // Keeps the countdown and throttle status current while a slow request is pending
+    pb.enable_steady_tick(Duration::from_millis(500));
+    pb
+}
+
+/// Where commands that work on already-scraped data read it from.
+#[derive(Args, Debug)]
+pub struct DatasetArgs {
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "CSV, JSON, or SQLite export to read (default: the sync store)"
+    )]
+    pub input: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Store database (default: store.db in the user data directory)"
+    )]
+    pub store: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Ownership state kept by `own add` (default: owned.json in the user data directory)"
+    )]
+    pub owned_state: Option<PathBuf>,
+}
+
+impl DatasetArgs {
+    /// Reads the volumes with the ownership state already joined in.
+    pub fn load(&self) -> Result<(Vec<Volume>, OwnedState)> {
+        let mut volumes = match &self.input {
+            Some(path) => read_volumes(path)?,
+            None => {
+                Store::open(&self.store.clone().unwrap_or_else(default_store_path))?.volumes()?
+            }
+        };
+        let owned = OwnedState::load(&self.owned_state.clone().unwrap_or_else(default_state_path))?;
+        owned.apply(&mut volumes);
+        Ok((volumes, owned))
+    }
+}
+
+/// Switches for the HTML report, ignored by other formats.
+#[derive(Args, Debug)]
+pub struct HtmlArgs {
+    #[arg(
+        long,
+        help = "Lay the HTML report out for printing: headings repeated on every page, page numbers, no controls"
+    )]
+    pub print_optimized: bool,
+
+    #[arg(
+        long,
+        help = "Embed the covers downloaded with --covers in the HTML report so the single file works offline (the styles and script already are); covers not on disk are left out"
+    )]
+    pub self_contained: bool,
+}
+
+impl HtmlArgs {
+    pub fn options(&self) -> output::OutputOptions {
+        output::OutputOptions {
+            print_optimized: self.print_optimized,
+            self_contained: self.self_contained,
+        }
+    }
+}
+
+/// Services `--sync` can keep up to date in place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SyncService {
+    Sheets,
+}
+
+/// Hosted tables written alongside (or instead of) `--output`.
+#[derive(Args, Debug)]
+pub struct ServiceArgs {
+    #[arg(
+        long,
+        value_name = "BASE_ID",
+        requires = "airtable_table",
+        help = "Also upsert the volumes into this Airtable base, matched on its row_key field"
+    )]
+    pub airtable_base: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "TABLE",
+        requires = "airtable_base",
+        help = "Airtable table name or ID"
+    )]
+    pub airtable_table: Option<String>,
+
+    #[arg(
+        long,
+        env = "AIRTABLE_API_KEY",
+        hide_env_values = true,
+        help = "Airtable personal access token"
+    )]
+    pub airtable_api_key: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Also update the volumes in place in this service, keeping columns you added"
+    )]
+    pub sync: Option<SyncService>,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        help = "Google spreadsheet to write with --sync sheets"
+    )]
+    pub spreadsheet_id: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        default_value = "LOA",
+        help = "Worksheet to write with --sync sheets, added if missing"
+    )]
+    pub worksheet: String,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        env = "GOOGLE_APPLICATION_CREDENTIALS",
+        help = "Google service account key (JSON) with edit access to the spreadsheet"
+    )]
+    pub google_credentials: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "s3://BUCKET/KEY",
+        help = "Also upload the volumes to this S3 object, in the format its extension names, using the standard AWS_* credentials (repeatable)"
+    )]
+    pub upload: Vec<String>,
+}
+
+impl ServiceArgs {
+    pub fn sinks(&self) -> Result<Vec<Box<dyn OutputSink>>> {
+        let mut sinks: Vec<Box<dyn OutputSink>> = Vec::new();
+        if let (Some(base), Some(table)) = (&self.airtable_base, &self.airtable_table) {
+            let api_key = self.airtable_api_key.clone().ok_or_else(|| {
+                anyhow!("--airtable-base needs --airtable-api-key or AIRTABLE_API_KEY")
+            })?;
+            sinks.push(Box::new(AirtableSink {
+                base: base.clone(),
+                table: table.clone(),
+                api_key,
+            }));
+        }
+
+        if self.sync == Some(SyncService::Sheets) {
+            let spreadsheet_id = self
+                .spreadsheet_id
+                .clone()
+                .ok_or_else(|| anyhow!("--sync sheets needs --spreadsheet-id"))?;
+            let credentials = self.google_credentials.clone().ok_or_else(|| {
+                anyhow!(
+                    "--sync sheets needs --google-credentials or GOOGLE_APPLICATION_CREDENTIALS"
+                )
+            })?;
+            #[cfg(feature = "sheets")]
+            sinks.push(Box::new(loa_scraper::sheets::SheetsSink {
+                spreadsheet_id,
+                worksheet: self.worksheet.clone(),
+                credentials,
+            }));
+            #[cfg(not(feature = "sheets"))]
+            return Err(anyhow!(
+                "Can't sync spreadsheet {} (worksheet {}, key {}): built without the sheets feature",
+                spreadsheet_id,
+                self.worksheet,
+                credentials.display()
+            ));
+        }
+
+        #[cfg(feature = "s3")]
+        for url in &self.upload {
+            sinks.push(Box::new(loa_scraper::s3::S3Sink::new(url)?));
+        }
+        #[cfg(not(feature = "s3"))]
+        if let Some(url) = self.upload.first() {
+            return Err(anyhow!(
+                "Can't upload to {}: built without the s3 feature",
+                url
+            ));
+        }
+        Ok(sinks)
+    }
+}
@@ -0,0 +1,84 @@
+use super::DatasetArgs;
+use super::coverage::load_books;
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::coverage::coverage;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct OverlapArgs {
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "ISBNs of the books you own, one per line (ISBN-10 or ISBN-13)"
+    )]
+    pub isbns: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 50.0,
+        help = "Flag volumes with more than this share of their works already owned"
+    )]
+    pub threshold: f64,
+
+    #[arg(long, help = "Print the flagged volumes as JSON instead of a table")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+pub async fn run(args: OverlapArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let books = load_books(&args.isbns, false).await?;
+
+    // Volumes already owned aren't purchase decisions any more
+    let unowned: Vec<_> = volumes
+        .into_iter()
+        .filter(|v| v.own_volume.is_empty())
+        .collect();
+    let mut flagged: Vec<_> = coverage(&unowned, &books)
+        .duplicates
+        .into_iter()
+        .filter(|d| d.owned_percent() > args.threshold)
+        .collect();
+    flagged.sort_by(|a, b| b.owned_percent().total_cmp(&a.owned_percent()));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&flagged)?);
+        return Ok(());
+    }
+    if flagged.is_empty() {
+        eprintln!(
+            "{} {} you own more than {}% of",
+            "✅".green(),
+            "No volumes".green().bold(),
+            args.threshold
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{:<10} {:>6}  {}",
+        "Volume".bold(),
+        "Owned".bold(),
+        "Title".bold()
+    );
+    for volume in &flagged {
+        println!(
+            "{:<10} {:>3}/{:<2}  {} ({})",
+            volume.volume, volume.owned, volume.works, volume.title, volume.author
+        );
+        for work in &volume.owned_works {
+            println!("{:<19} - {}", "", work);
+        }
+    }
+    eprintln!(
+        "{} {} volumes collect mostly works you already own; consider skipping them",
+        "⚠️".yellow(),
+        flagged.len()
+    );
+    Ok(())
+}
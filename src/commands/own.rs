@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use clap::{Args, Subcommand};
+use colored::*;
+use loa_scraper::ownership::{OwnedState, default_state_path};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct OwnArgs {
+    #[command(subcommand)]
+    pub action: OwnAction,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Ownership state file (default: owned.json in the user data directory)"
+    )]
+    pub state: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OwnAction {
+    /// Mark volumes as owned
+    Add {
+        #[arg(required = true, value_name = "VOLUME")]
+        volumes: Vec<u32>,
+
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "When the volumes were acquired (default: today)"
+        )]
+        on: Option<NaiveDate>,
+    },
+    /// Unmark volumes as owned
+    Remove {
+        #[arg(required = true, value_name = "VOLUME")]
+        volumes: Vec<u32>,
+    },
+    /// Print the owned volume numbers
+    List,
+}
+
+pub fn run(args: OwnArgs) -> Result<()> {
+    let path = args.state.unwrap_or_else(default_state_path);
+    let mut state = OwnedState::load(&path)?;
+
+    match args.action {
+        OwnAction::Add { volumes, on } => {
+            let acquired_on = on
+                .unwrap_or_else(|| Local::now().date_naive())
+                .format("%Y-%m-%d")
+                .to_string();
+            let added = volumes
+                .iter()
+                .filter(|&&n| state.add(n, &acquired_on))
+                .count();
+            state.save(&path)?;
+            eprintln!(
+                "{} {} {} volume(s), {} owned in total",
+                "📗".green(),
+                "Added".green().bold(),
+                added,
+                state.owned.len()
+            );
+        }
+        OwnAction::Remove { volumes } => {
+            let removed = volumes.iter().filter(|&&n| state.remove(n)).count();
+            state.save(&path)?;
+            eprintln!(
+                "{} {} {} volume(s), {} owned in total",
+                "📕".red(),
+                "Removed".red().bold(),
+                removed,
+                state.owned.len()
+            );
+        }
+        OwnAction::List => {
+            for number in &state.owned {
+                println!("{}", number);
+            }
+        }
+    }
+
+    Ok(())
+}
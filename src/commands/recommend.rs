@@ -0,0 +1,85 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::recommend::recommend;
+
+/// Which volumes recommendations are based on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Basis {
+    /// Everything you own
+    Owned,
+    /// The volumes you added most recently with `own add`
+    Recent,
+}
+
+#[derive(Args, Debug)]
+pub struct RecommendArgs {
+    #[arg(long, value_enum, default_value_t = Basis::Owned, help = "What to base suggestions on")]
+    pub based_on: Basis,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 10,
+        help = "Number of volumes to suggest"
+    )]
+    pub limit: usize,
+
+    #[arg(long, help = "Print the suggestions as JSON instead of a list")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+/// How many recent acquisitions `--based-on recent` looks at.
+const RECENT: usize = 5;
+
+pub fn run(args: RecommendArgs) -> Result<()> {
+    let (volumes, owned) = args.dataset.load()?;
+    let owned_volumes: Vec<_> = volumes
+        .iter()
+        .filter(|v| !v.own_volume.is_empty())
+        .collect();
+    let basis = match args.based_on {
+        Basis::Owned => owned_volumes,
+        Basis::Recent => {
+            let mut recent: Vec<_> = owned.acquired.iter().collect();
+            recent.sort_by(|a, b| b.1.cmp(a.1));
+            let numbers: Vec<u32> = recent.iter().take(RECENT).map(|(n, _)| **n).collect();
+            owned_volumes
+                .into_iter()
+                .filter(|v| v.series.is_empty() && numbers.contains(&v.volume_number))
+                .collect()
+        }
+    };
+    if basis.is_empty() {
+        eprintln!(
+            "{} {}",
+            "⚠️".yellow(),
+            "Nothing to base suggestions on: mark volumes owned with `own add` first".yellow()
+        );
+        return Ok(());
+    }
+
+    let mut recommendations = recommend(&volumes, &basis);
+    recommendations.truncate(args.limit);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&recommendations)?);
+        return Ok(());
+    }
+
+    for recommendation in &recommendations {
+        println!(
+            "{:<8} {} ({})",
+            recommendation.volume.bold(),
+            recommendation.title,
+            recommendation.author
+        );
+        for reason in &recommendation.reasons {
+            println!("{:<8} - {}", "", reason);
+        }
+    }
+    Ok(())
+}
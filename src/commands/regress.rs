@@ -0,0 +1,169 @@
+use anyhow::{Result, anyhow};
+use clap::Args;
+use colored::*;
+use loa_scraper::scrape::{VolumeData, parse_volumes};
+use scraper::Html;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub struct RegressArgs {
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory of <name>.html snapshots with <name>.json expected output"
+    )]
+    pub fixtures: PathBuf,
+
+    #[arg(
+        long,
+        help = "Overwrite the expected JSON with the current parser's output"
+    )]
+    pub bless: bool,
+}
+
+enum Outcome {
+    Pass(usize),
+    Blessed(usize),
+    MissingExpected,
+    Mismatch(Vec<String>),
+}
+
+fn describe(listing: &VolumeData) -> String {
+    format!(
+        "#{} {:?}",
+        listing.volume_number, listing.original_volume_name
+    )
+}
+
+/// Describes each serialized field that differs between two parses of the same volume.
+fn field_differences(want: &VolumeData, got: &VolumeData) -> Result<Vec<String>> {
+    let want = serde_json::to_value(want)?;
+    let got = serde_json::to_value(got)?;
+    let empty = serde_json::Map::new();
+    let want = want.as_object().unwrap_or(&empty);
+    let got = got.as_object().unwrap_or(&empty);
+
+    Ok(want
+        .iter()
+        .filter(|(field, value)| got.get(*field) != Some(*value))
+        .map(|(field, value)| {
+            format!(
+                "{}: expected {}, got {}",
+                field,
+                value,
+                got.get(field).cloned().unwrap_or_default()
+            )
+        })
+        .collect())
+}
+
+/// Lists what differs between two parses, keyed by volume number.
+fn differences(expected: &[VolumeData], actual: &[VolumeData]) -> Result<Vec<String>> {
+    let mut diffs = Vec::new();
+
+    for want in expected {
+        match actual
+            .iter()
+            .find(|a| a.volume_number == want.volume_number)
+        {
+            None => diffs.push(format!("missing {}", describe(want))),
+            Some(got) => {
+                for diff in field_differences(want, got)? {
+                    diffs.push(format!("#{} {}", want.volume_number, diff));
+                }
+            }
+        }
+    }
+    for got in actual {
+        if !expected
+            .iter()
+            .any(|e| e.volume_number == got.volume_number)
+        {
+            diffs.push(format!("unexpected {}", describe(got)));
+        }
+    }
+
+    Ok(diffs)
+}
+
+fn check_fixture(html_path: &Path, bless: bool) -> Result<Outcome> {
+    let expected_path = html_path.with_extension("json");
+    let html = Html::parse_document(&fs::read_to_string(html_path)?);
+    let actual = parse_volumes(&html)?;
+
+    if bless {
+        fs::write(
+            &expected_path,
+            serde_json::to_string_pretty(&actual)? + "\n",
+        )?;
+        return Ok(Outcome::Blessed(actual.len()));
+    }
+
+    if !expected_path.exists() {
+        return Ok(Outcome::MissingExpected);
+    }
+
+    let expected: Vec<VolumeData> = serde_json::from_str(&fs::read_to_string(&expected_path)?)?;
+    let diffs = differences(&expected, &actual)?;
+    Ok(if diffs.is_empty() {
+        Outcome::Pass(actual.len())
+    } else {
+        Outcome::Mismatch(diffs)
+    })
+}
+
+pub fn run(args: RegressArgs) -> Result<()> {
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(&args.fixtures)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    fixtures.sort();
+
+    if fixtures.is_empty() {
+        return Err(anyhow!(
+            "No .html fixtures found in '{}'",
+            args.fixtures.display()
+        ));
+    }
+
+    let mut failures = 0;
+    for fixture in &fixtures {
+        let name = fixture.file_name().unwrap_or_default().to_string_lossy();
+        match check_fixture(fixture, args.bless)? {
+            Outcome::Pass(count) => {
+                eprintln!("{} {} ({} volumes)", "✅".green(), name, count)
+            }
+            Outcome::Blessed(count) => {
+                eprintln!("{} {} blessed ({} volumes)", "📝".cyan(), name, count)
+            }
+            Outcome::MissingExpected => {
+                failures += 1;
+                eprintln!(
+                    "{} {} has no expected .json (run with --bless to create it)",
+                    "⚠️".yellow(),
+                    name
+                );
+            }
+            Outcome::Mismatch(diffs) => {
+                failures += 1;
+                eprintln!("{} {} ({} differences)", "❌".red(), name, diffs.len());
+                for diff in diffs.iter().take(10) {
+                    eprintln!("    {}", diff);
+                }
+                if diffs.len() > 10 {
+                    eprintln!("    ... and {} more", diffs.len() - 10);
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} of {} fixtures do not match the current parser",
+            failures,
+            fixtures.len()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::output::{self, OutputFormat, PERSONAL_COLUMNS};
+
+#[derive(Args, Debug)]
+pub struct SampleArgs {
+    #[arg(long, default_value_t = 20, help = "Number of volumes to keep")]
+    pub rows: usize,
+
+    #[arg(
+        long,
+        help = "Scrub personal columns (ownership, local paths, sync timestamps)"
+    )]
+    pub anonymize: bool,
+
+    #[arg(short, long, help = "Output file path (default: stdout)")]
+    pub output: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Csv,
+        help = "Output format"
+    )]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+/// Picks `rows` volumes spread evenly across the catalog, so the sample covers early
+/// and recent volumes alike and comes out the same on every run.
+fn spread<T: Clone>(items: &[T], rows: usize) -> Vec<T> {
+    if rows >= items.len() {
+        return items.to_vec();
+    }
+    (0..rows)
+        .map(|i| items[i * items.len() / rows].clone())
+        .collect()
+}
+
+pub fn run(args: SampleArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let mut sample = spread(&volumes, args.rows);
+
+    if args.anonymize {
+        let columns: Vec<String> = PERSONAL_COLUMNS.iter().map(|c| c.to_string()).collect();
+        output::redact(&mut sample, &columns)?;
+    }
+
+    output::write_volumes(&sample, args.format, args.output.as_deref())?;
+
+    if let Some(output_path) = &args.output {
+        eprintln!(
+            "{} {} {} of {} volumes to '{}'",
+            "💾".green(),
+            "Sampled".green().bold(),
+            sample.len(),
+            volumes.len(),
+            output_path.bright_white()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1120 @@
+use super::checkpoint::{Checkpoint, cancel_on_ctrl_c, default_checkpoint_path};
+use super::stage_progress_bar;
+use anyhow::{Result, anyhow, bail};
+use clap::{Args, ValueEnum};
+use colored::*;
+use indicatif::HumanDuration;
+use loa_scraper::Volume;
+use loa_scraper::author_cache::{AuthorCache, default_cache_path};
+use loa_scraper::author_overrides::{AuthorOverrides, default_overrides_path};
+use loa_scraper::covers::{cover_file, download_covers};
+use loa_scraper::details::enrich_details;
+use loa_scraper::enrich::{EnrichOptions, Enrichment, enrich};
+use loa_scraper::http::{BudgetExhausted, Cancelled, Client};
+use loa_scraper::output::{self, OutputFormat, OutputSink, OutputTarget};
+use loa_scraper::ownership::{OwnedState, default_state_path, mark_owned, read_shelf_export};
+use loa_scraper::progress::ThrottleMonitor;
+use loa_scraper::rate::{DEFAULT_BURST, DEFAULT_REQUESTS_PER_SECOND, RateLimit};
+use loa_scraper::report::{ScrapeReport, ScrapeWarning};
+use loa_scraper::scrape::{
+    AuthorCandidate, AuthorChoice, STATUS_CACHED, STATUS_FORTHCOMING, STATUS_UNAVAILABLE,
+    ScrapeOptions, Series, VolumeData, absolute_url, enrich_volumes_with_cache,
+    list_volumes_with_report,
+};
+use loa_scraper::title_style::{TitleStyle, restyle};
+use loa_scraper::webhook::WebhookEmitter;
+use loa_scraper::wikidata::CHUNK_SIZE;
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+const STAGE_AUTHORS: &str = "authors";
+const STAGE_DETAILS: &str = "details";
+const STAGE_COVERS: &str = "covers";
+
+/// What to scrape and how to enrich it; shared by the default command and `sync`.
+#[derive(Args, Debug)]
+pub struct CollectArgs {
+    #[arg(short, long, help = "Starting volume number (default: 1)")]
+    pub start: Option<u32>,
+
+    #[arg(short, long, help = "Ending volume number (default: last available)")]
+    pub end: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Fetch each volume's detail page for extra fields (ISBN, price, availability)"
+    )]
+    pub details: bool,
+
+    #[arg(
+        long,
+        help = "Also list announced volumes, with status and release_date columns"
+    )]
+    pub include_forthcoming: bool,
+
+    #[arg(
+        long,
+        help = "Also list special publications outside the numbered series, with a blank volume_number and a publication_type column"
+    )]
+    pub include_unnumbered: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Series::Loa,
+        help = "Which catalog to scrape; aps and all add a series column (--start/--end apply within each series)"
+    )]
+    pub series: Series,
+
+    #[arg(
+        long,
+        help = "Include the collection-page markup each row was parsed from (raw_listing_html)"
+    )]
+    pub debug_raw: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SOURCE",
+        help = "Look each volume up in an external catalog (repeatable)"
+    )]
+    pub enrich: Vec<Enrichment>,
+
+    #[arg(
+        long,
+        env = "WORLDCAT_KEY",
+        hide_env_values = true,
+        help = "OCLC WSKey client ID for --enrich worldcat"
+    )]
+    pub worldcat_key: Option<String>,
+
+    #[arg(
+        long,
+        env = "WORLDCAT_SECRET",
+        hide_env_values = true,
+        help = "OCLC WSKey secret for --enrich worldcat"
+    )]
+    pub worldcat_secret: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "POST each volume as JSON to URL as soon as it's fully processed (retried on failure)"
+    )]
+    pub emit_webhook: Option<String>,
+
+    #[arg(long, value_name = "DIR", help = "Download cover images into DIR")]
+    pub covers: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Ownership state kept by `own add` (default: owned.json in the user data directory)"
+    )]
+    pub owned_state: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Mark volumes owned from a Goodreads or LibraryThing export"
+    )]
+    pub owned_from: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Ask which article is meant when an author's Wikipedia lookup is ambiguous, remembering the answers in the author overrides"
+    )]
+    pub interactive: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Wikipedia articles chosen for ambiguous authors (default: author-overrides.json in the user config directory)"
+    )]
+    pub author_overrides: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        help = "Abort if fewer than N volumes are found (default N: the count from the previous run)"
+    )]
+    pub expect_at_least: Option<Option<usize>>,
+
+    #[arg(
+        long,
+        help = "Abort instead of continuing when parse results look suspicious"
+    )]
+    pub strict: bool,
+
+    #[arg(
+        long,
+        help = "Check GitHub for a newer release before scraping (parsing can break when LOA redesigns its site)"
+    )]
+    pub check_update: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Stop after N HTTP requests across all hosts, saving a checkpoint to --resume from"
+    )]
+    pub max_requests: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = DEFAULT_REQUESTS_PER_SECOND,
+        help = "Send at most N requests per second to each host, on average"
+    )]
+    pub requests_per_second: f64,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = DEFAULT_BURST,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Let up to N requests go to a host at once after it's been left alone a while"
+    )]
+    pub burst: u32,
+
+    #[arg(
+        long,
+        help = "Fetch only the listings (or read them from the checkpoint with --resume), then list what the run would fetch and estimate its requests and duration, without fetching it or writing output"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Pace requests to each host by how it's coping: start slow, speed up to a few at once while responses stay quick and clean, back off at any sign of throttling"
+    )]
+    pub adaptive_rate: bool,
+
+    #[arg(
+        long,
+        help = "Continue an interrupted run from its checkpoint (pass the same options)"
+    )]
+    pub resume: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Checkpoint file (default: checkpoint.json in the user cache directory)"
+    )]
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl CollectArgs {
+    pub fn options(&self) -> ScrapeOptions {
+        ScrapeOptions {
+            start: self.start.unwrap_or(1),
+            end: self.end,
+            include_forthcoming: self.include_forthcoming,
+            include_unnumbered: self.include_unnumbered,
+            series: self.series,
+        }
+    }
+
+    pub fn rate_limit(&self) -> RateLimit {
+        RateLimit {
+            requests_per_second: self.requests_per_second,
+            burst: self.burst,
+        }
+    }
+
+    pub fn enrich_options(&self) -> EnrichOptions {
+        EnrichOptions {
+            worldcat_key: self.worldcat_key.clone(),
+            worldcat_secret: self.worldcat_secret.clone(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ScrapeArgs {
+    #[command(flatten)]
+    pub collect: CollectArgs,
+
+    #[arg(
+        short,
+        long,
+        help = "Output file path, or a postgres:// connection string (default: stdout). Repeat to write several at once, each in the format its extension names (or FORMAT:PATH)"
+    )]
+    pub output: Vec<String>,
+
+    #[command(flatten)]
+    pub services: super::ServiceArgs,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Csv,
+        help = "Output format"
+    )]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub html: super::HtmlArgs,
+
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        help = "Strip these comma-separated columns from the output, e.g. own_volume,cover_path"
+    )]
+    pub redact: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TitleStyle::AsScraped,
+        help = "Capitalize titles in this style instead of as LOA wrote them"
+    )]
+    pub title_style: TitleStyle,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Also write each unique author once, with their Wikipedia link and volumes, to this CSV"
+    )]
+    pub authors_output: Option<String>,
+}
+
+/// Fetches and checks the listings in range, returning none if the range is empty.
+async fn fetch_listings(
+    client: &Client,
+    args: &CollectArgs,
+    previous_count: Option<usize>,
+) -> Result<Vec<VolumeData>> {
+    let options = args.options();
+    let start_volume = options.start;
+
+    if args.check_update {
+        super::update::nudge(client).await;
+    }
+
+    eprintln!("{} {}", "📡".yellow(), "Fetching listings...".yellow());
+    let mut report = ScrapeReport::new();
+    let listings = list_volumes_with_report(client, &options, previous_count, &mut report).await?;
+
+    // Missing authors and covers are normal for anthologies; only lost rows matter here
+    for warning in &report.warnings {
+        if let ScrapeWarning::SkippedRow { .. } = warning {
+            eprintln!(
+                "{} {} {}",
+                "⚠️".yellow(),
+                "Suspicious parse result:".red().bold(),
+                warning.to_string().yellow()
+            );
+        }
+    }
+
+    let volume_range = if let Some(end) = args.end {
+        format!("{}-{}", start_volume, end)
+    } else {
+        format!("{}+", start_volume)
+    };
+    let forthcoming = listings
+        .iter()
+        .filter(|l| l.status == STATUS_FORTHCOMING)
+        .count();
+    let unnumbered = listings.iter().filter(|l| l.volume_number == 0).count();
+    let found = listings.len() - forthcoming - unnumbered;
+
+    eprintln!(
+        "{} {} volumes {} (volumes {})",
+        "✅".green(),
+        "Found".green().bold(),
+        found.to_string().bright_white().bold(),
+        volume_range.cyan()
+    );
+
+    let warnings: Vec<&ScrapeWarning> = report
+        .warnings
+        .iter()
+        .filter(|w| matches!(w, ScrapeWarning::Suspicious(_)))
+        .collect();
+    for warning in &warnings {
+        eprintln!(
+            "{} {} {}",
+            "⚠️".yellow(),
+            "Suspicious parse result:".red().bold(),
+            warning.to_string().yellow()
+        );
+    }
+    if !warnings.is_empty() {
+        eprintln!(
+            "   If LOA's site has changed, a newer release may parse it: run `loa-scraper update --check`"
+        );
+    }
+    if args.strict && !warnings.is_empty() {
+        return Err(anyhow!(
+            "Aborting before enrichment: {} suspicious parse result(s) with --strict",
+            warnings.len()
+        ));
+    }
+
+    if let Some(expected) = args.expect_at_least {
+        let expected = match expected {
+            Some(n) => n,
+            None => previous_count.ok_or_else(|| {
+                anyhow!(
+                    "--expect-at-least without a value needs a previous run to count: an existing CSV or SQLite --output file, or the sync store"
+                )
+            })?,
+        };
+        if found < expected {
+            return Err(anyhow!(
+                "Found only {} volumes, expected at least {}; not overwriting the previous output",
+                found,
+                expected
+            ));
+        }
+    }
+
+    if args.include_forthcoming && options.series.includes_loa() {
+        eprintln!(
+            "{} {} forthcoming volumes",
+            "✅".green(),
+            forthcoming.to_string().bright_white().bold()
+        );
+    }
+    if args.include_unnumbered {
+        eprintln!(
+            "{} {} unnumbered publications",
+            "✅".green(),
+            unnumbered.to_string().bright_white().bold()
+        );
+    }
+
+    Ok(listings)
+}
+
+fn enrich_stage(enrichment: Enrichment) -> String {
+    let name = enrichment
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default();
+    format!("enrich:{}", name)
+}
+
+/// Asks on the terminal which of `candidates` an ambiguous author is.
+fn prompt_for_author(author: &str, candidates: &[AuthorCandidate]) -> AuthorChoice {
+    eprintln!(
+        "\n{} {} {}",
+        "❓".cyan(),
+        "Which article is".cyan().bold(),
+        author.bright_white().bold()
+    );
+    for (i, candidate) in candidates.iter().enumerate() {
+        let description = match candidate.description.as_str() {
+            "" => String::new(),
+            description => format!(" — {}", description),
+        };
+        eprintln!(
+            "  {}. {}{}",
+            (i + 1).to_string().bright_white().bold(),
+            candidate.title,
+            description.dimmed()
+        );
+        if !candidate.info.wikipedia_link.is_empty() {
+            eprintln!("     {}", candidate.info.wikipedia_link.dimmed());
+        }
+    }
+    eprintln!("  {}. None of these", "n".bright_white().bold());
+
+    loop {
+        eprint!(
+            "Pick 1-{}, n, or press Enter to skip (keeps choice 1 and asks next time): ",
+            candidates.len()
+        );
+        let mut line = String::new();
+        // End of input means nobody is there to answer
+        if io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
+            return AuthorChoice::Skip;
+        }
+        match line.trim() {
+            "" => return AuthorChoice::Skip,
+            "n" | "N" => return AuthorChoice::NoArticle,
+            answer => {
+                if let Ok(n) = answer.parse::<usize>()
+                    && (1..=candidates.len()).contains(&n)
+                {
+                    return AuthorChoice::Pick(n - 1);
+                }
+            }
+        }
+        eprintln!("{}", "Enter a number from the list, n, or nothing".yellow());
+    }
+}
+
+/// How long each request is taken to last when estimating a dry run's duration.
+const ASSUMED_LATENCY: Duration = Duration::from_millis(400);
+
+/// One stage of a dry run: what it would fetch and how many requests that takes.
+struct PlannedStage {
+    name: String,
+    summary: String,
+    /// What the stage fetches, one line each: an author, a URL, or a volume.
+    targets: Vec<String>,
+    /// The fewest and most requests it could make.
+    requests: (usize, usize),
+}
+
+/// A request count like `12 requests`, or `12–20 requests` when it's a range.
+fn request_range((least, most): (usize, usize)) -> String {
+    if least == most {
+        format!("{} requests", least)
+    } else {
+        format!("{}–{} requests", least, most)
+    }
+}
+
+/// Roughly how long `requests` requests take one at a time under `limit`: each waits
+/// for its response, or for its token when that's slower.
+fn time_for(requests: usize, limit: RateLimit) -> Duration {
+    let interval = Duration::from_secs_f64(1.0 / limit.requests_per_second);
+    ASSUMED_LATENCY.max(interval) * requests as u32
+}
+
+/// The stages a run would still do with these listings, mirroring `run_stages`.
+fn plan_stages(args: &CollectArgs, checkpoint: &Checkpoint) -> Result<Vec<PlannedStage>> {
+    let listings = &checkpoint.listings;
+    let mut stages = Vec::new();
+
+    if !checkpoint.is_done(STAGE_AUTHORS) {
+        let mut authors: Vec<&str> = listings[checkpoint.volumes.len()..]
+            .iter()
+            .map(|listing| listing.author.as_str())
+            .filter(|author| !author.is_empty())
+            .collect();
+        authors.sort();
+        authors.dedup();
+        let overrides_path = args
+            .author_overrides
+            .clone()
+            .unwrap_or_else(default_overrides_path);
+        let overrides = AuthorOverrides::load(&overrides_path)?;
+        let chosen = authors
+            .iter()
+            .filter(|author| overrides.authors.contains_key(**author))
+            .count();
+        let queries = authors.len().div_ceil(CHUNK_SIZE);
+        // A Wikipedia search for each author not chosen by hand, and a VIAF lookup for
+        // each, when Wikidata doesn't already know them
+        let lookups = authors.len() * 2 - chosen;
+        let chosen = match chosen {
+            0 => String::new(),
+            n => format!(" ({} chosen by hand)", n),
+        };
+        stages.push(PlannedStage {
+            name: STAGE_AUTHORS.to_string(),
+            summary: format!(
+                "{} authors{}: {} Wikidata quer{}, then up to {} Wikipedia and VIAF lookups",
+                authors.len(),
+                chosen,
+                queries,
+                if queries == 1 { "y" } else { "ies" },
+                lookups
+            ),
+            targets: authors.iter().map(|author| author.to_string()).collect(),
+            requests: (queries, queries + lookups),
+        });
+    }
+
+    // The first unfinished in-place stage picks up at the checkpoint's progress
+    let mut start = checkpoint.progress;
+    let mut remaining = |stage: &str| {
+        if checkpoint.is_done(stage) {
+            return None;
+        }
+        let rest = &listings[start.min(listings.len())..];
+        start = 0;
+        Some(rest)
+    };
+
+    if args.details
+        && let Some(rest) = remaining(STAGE_DETAILS)
+    {
+        let pages: Vec<String> = rest
+            .iter()
+            .filter(|listing| !listing.loa_detail_link.is_empty())
+            .map(|listing| absolute_url(&listing.loa_detail_link))
+            .collect();
+        stages.push(PlannedStage {
+            name: STAGE_DETAILS.to_string(),
+            summary: format!(
+                "{} detail pages, plus store listings without prices and Wayback lookups for retired pages",
+                pages.len()
+            ),
+            requests: (pages.len(), pages.len() * 2),
+            targets: pages,
+        });
+    }
+
+    for (i, &enrichment) in args.enrich.iter().enumerate() {
+        let stage = enrich_stage(enrichment);
+        let Some(rest) = remaining(&stage) else {
+            continue;
+        };
+        // Requests per volume, and any made once for the whole stage
+        let ((least, most), once) = match enrichment {
+            Enrichment::Loc => ((1, 1), 0),
+            // By ISBN, then by title when that finds nothing
+            Enrichment::Openlibrary => ((1, 2), 0),
+            Enrichment::Ratings if args.enrich[..i].contains(&Enrichment::Openlibrary) => {
+                ((1, 1), 0)
+            }
+            Enrichment::Ratings => ((1, 3), 0),
+            // Plus the access token
+            Enrichment::Worldcat => ((1, 2), 1),
+        };
+        stages.push(PlannedStage {
+            summary: format!("{} volumes to look up", rest.len()),
+            targets: rest
+                .iter()
+                .map(|listing| format!("{} {}", listing.volume_number, listing.title))
+                .collect(),
+            requests: (rest.len() * least + once, rest.len() * most + once),
+            name: stage,
+        });
+    }
+
+    if let Some(covers_dir) = &args.covers
+        && let Some(rest) = remaining(STAGE_COVERS)
+    {
+        // Covers already on disk are reused
+        let covers: Vec<String> = rest
+            .iter()
+            .filter(|listing| {
+                let volume = Volume {
+                    volume_number: listing.volume_number,
+                    series: listing.series.clone(),
+                    loa_detail_link: listing.loa_detail_link.clone(),
+                    cover_image_link: listing.cover_image_link.clone(),
+                    ..Default::default()
+                };
+                !listing.cover_image_link.is_empty() && !cover_file(covers_dir, &volume).exists()
+            })
+            .map(|listing| listing.cover_image_link.clone())
+            .collect();
+        stages.push(PlannedStage {
+            name: STAGE_COVERS.to_string(),
+            summary: format!(
+                "{} covers not yet in '{}'",
+                covers.len(),
+                covers_dir.display()
+            ),
+            requests: (covers.len(), covers.len()),
+            targets: covers,
+        });
+    }
+
+    Ok(stages)
+}
+
+/// Prints what a run would fetch after the listings: each request's target on stdout,
+/// and a summary per stage with an estimate of the requests and time on stderr.
+fn print_plan(args: &CollectArgs, checkpoint: &Checkpoint) -> Result<()> {
+    let stages = plan_stages(args, checkpoint)?;
+
+    eprintln!(
+        "{} {} nothing beyond the listings is fetched, and no output is written",
+        "🧪".cyan(),
+        "Dry run:".cyan().bold()
+    );
+    let mut stdout = io::stdout().lock();
+    for stage in &stages {
+        for target in &stage.targets {
+            writeln!(stdout, "{}\t{}", stage.name, target)?;
+        }
+    }
+    stdout.flush()?;
+
+    let width = stages
+        .iter()
+        .map(|stage| stage.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut requests = (0, 0);
+    let mut time = (Duration::ZERO, Duration::ZERO);
+    for stage in &stages {
+        let (least, most) = stage.requests;
+        eprintln!(
+            "   {:width$}  {} {}",
+            stage.name.bright_white().bold(),
+            stage.summary,
+            format!("({})", request_range(stage.requests)).dimmed(),
+            width = width
+        );
+        requests = (requests.0 + least, requests.1 + most);
+        time.0 += time_for(least, args.rate_limit());
+        time.1 += time_for(most, args.rate_limit());
+    }
+    if stages.is_empty() {
+        eprintln!("   Every stage is already done");
+        return Ok(());
+    }
+
+    let pace = if args.adaptive_rate {
+        " (--adaptive-rate is usually faster)"
+    } else {
+        ""
+    };
+    eprintln!(
+        "{} {} {}, about {:#}–{:#} one at a time at up to {} per second per host{}",
+        "⏱️".cyan(),
+        "Estimate:".cyan().bold(),
+        request_range(requests).bright_white().bold(),
+        HumanDuration(time.0),
+        HumanDuration(time.1),
+        args.requests_per_second,
+        pace
+    );
+    if let Some(max) = args.max_requests
+        && max < requests.1
+    {
+        let when = if max < requests.0 { "will" } else { "may" };
+        eprintln!(
+            "{} --max-requests {} {} stop the run partway; continue it with --resume",
+            "⚠️".yellow(),
+            max,
+            when
+        );
+    }
+    Ok(())
+}
+
+/// Runs the enrichment stages the checkpoint hasn't finished yet, recording progress in
+/// it as each volume completes so an interrupted run can pick up where it stopped.
+async fn run_stages(
+    client: &Client,
+    args: &CollectArgs,
+    checkpoint: &mut Checkpoint,
+    webhook: Option<&WebhookEmitter>,
+    throttle: &ThrottleMonitor,
+) -> Result<()> {
+    // A volume is ready for the webhook once the last stage of the run has processed it
+    let last_stage = if args.covers.is_some() {
+        STAGE_COVERS.to_string()
+    } else if let Some(&enrichment) = args.enrich.last() {
+        enrich_stage(enrichment)
+    } else if args.details {
+        STAGE_DETAILS.to_string()
+    } else {
+        STAGE_AUTHORS.to_string()
+    };
+    let emit = |stage: &str, volume: &Volume| {
+        if let Some(webhook) = webhook
+            && stage == last_stage
+        {
+            webhook.emit(volume);
+        }
+    };
+
+    if !checkpoint.is_done(STAGE_AUTHORS) {
+        let start = checkpoint.volumes.len();
+        let pb = stage_progress_bar(checkpoint.listings.len(), start, throttle);
+        pb.inc(start as u64);
+
+        eprintln!(
+            "{} {}",
+            "🔗".magenta(),
+            "Processing volumes and finding Wikipedia links...".magenta()
+        );
+
+        let cache_path = default_cache_path();
+        let mut cache = AuthorCache::load(&cache_path);
+        let overrides_path = args
+            .author_overrides
+            .clone()
+            .unwrap_or_else(default_overrides_path);
+        let mut overrides = AuthorOverrides::load(&overrides_path)?;
+        let chosen_before = overrides.authors.len();
+        let mut report = ScrapeReport::new();
+        let mut done = Vec::new();
+        let result = enrich_volumes_with_cache(
+            client,
+            &checkpoint.listings[start..],
+            &mut cache,
+            &mut overrides,
+            &mut report,
+            |author, candidates| {
+                if args.interactive {
+                    pb.suspend(|| prompt_for_author(author, candidates))
+                } else {
+                    AuthorChoice::Skip
+                }
+            },
+            |volume| {
+                pb.set_message(format!(
+                    "Volume {}: {}",
+                    volume.volume_number,
+                    volume.title.chars().take(40).collect::<String>()
+                ));
+                pb.inc(1);
+                emit(STAGE_AUTHORS, volume);
+                done.push(volume.clone());
+            },
+        )
+        .await;
+        cache.save(&cache_path)?;
+        let chosen = overrides.authors.len() - chosen_before;
+        if chosen > 0 {
+            overrides.save(&overrides_path)?;
+            eprintln!(
+                "{} {} {} author(s) in '{}'",
+                "📝".cyan(),
+                "Remembered your choice for".cyan().bold(),
+                chosen,
+                overrides_path.display()
+            );
+        }
+
+        let guessed = report
+            .warnings
+            .iter()
+            .filter(|w| matches!(w, ScrapeWarning::UncertainAuthor { .. }))
+            .count();
+        if guessed > 0 && !args.interactive {
+            eprintln!(
+                "{} {} {} author link(s) are best guesses among several; pass --interactive to choose",
+                "⚠️".yellow(),
+                "Ambiguous authors:".yellow().bold(),
+                guessed
+            );
+        }
+
+        match result {
+            // The finished list includes authors that succeeded on retry
+            Ok(volumes) => checkpoint.volumes.extend(volumes),
+            Err(e) => {
+                checkpoint.volumes.extend(done);
+                return Err(e);
+            }
+        }
+        pb.finish_with_message("Complete!");
+
+        let degraded = |status: &str| {
+            checkpoint
+                .volumes
+                .iter()
+                .filter(|v| v.enrichment_status.as_deref() == Some(status))
+                .count()
+        };
+        let (cached, unavailable) = (degraded(STATUS_CACHED), degraded(STATUS_UNAVAILABLE));
+        if cached + unavailable > 0 {
+            eprintln!(
+                "{} {} {} volumes use cached author data, {} have none (see enrichment_status)",
+                "⚠️".yellow(),
+                "Wikipedia/Wikidata unreachable:".yellow().bold(),
+                cached,
+                unavailable
+            );
+        }
+
+        if args.debug_raw {
+            // enrich_volumes yields one volume per listing, in listing order
+            for (volume, listing) in checkpoint.volumes.iter_mut().zip(&checkpoint.listings) {
+                volume.raw_listing_html = Some(listing.raw_listing_html.clone());
+            }
+        }
+
+        checkpoint.finish(STAGE_AUTHORS);
+    }
+
+    if args.details && !checkpoint.is_done(STAGE_DETAILS) {
+        eprintln!(
+            "{} {}",
+            "📖".magenta(),
+            "Fetching volume detail pages...".magenta()
+        );
+
+        let start = checkpoint.progress;
+        let pb = stage_progress_bar(checkpoint.volumes.len(), start, throttle);
+        pb.inc(start as u64);
+        let mut done = 0;
+        let result = enrich_details(client, &mut checkpoint.volumes[start..], |volume| {
+            pb.inc(1);
+            emit(STAGE_DETAILS, volume);
+            done += 1;
+        })
+        .await;
+        checkpoint.progress = start + done;
+        result?;
+        pb.finish_with_message("Complete!");
+        checkpoint.finish(STAGE_DETAILS);
+    }
+
+    let options = args.enrich_options();
+    for &enrichment in &args.enrich {
+        let name = enrichment
+            .to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default();
+        let stage = enrich_stage(enrichment);
+        if checkpoint.is_done(&stage) {
+            continue;
+        }
+
+        eprintln!(
+            "{} {} {}...",
+            "🔎".magenta(),
+            "Looking volumes up in".magenta(),
+            name.magenta()
+        );
+
+        let start = checkpoint.progress;
+        let pb = stage_progress_bar(checkpoint.volumes.len(), start, throttle);
+        pb.inc(start as u64);
+        let mut done = 0;
+        let result = enrich(
+            client,
+            enrichment,
+            &options,
+            &mut checkpoint.volumes[start..],
+            |volume| {
+                pb.inc(1);
+                emit(&stage, volume);
+                done += 1;
+            },
+        )
+        .await;
+        checkpoint.progress = start + done;
+        result?;
+        pb.finish_with_message("Complete!");
+        checkpoint.finish(&stage);
+    }
+
+    if let Some(covers_dir) = &args.covers
+        && !checkpoint.is_done(STAGE_COVERS)
+    {
+        eprintln!(
+            "{} {} '{}'",
+            "🖼️".magenta(),
+            "Downloading cover images to".magenta(),
+            covers_dir.display()
+        );
+
+        let start = checkpoint.progress;
+        let pb = stage_progress_bar(checkpoint.volumes.len(), start, throttle);
+        pb.inc(start as u64);
+        let mut done = 0;
+        let result = download_covers(
+            client,
+            &mut checkpoint.volumes[start..],
+            covers_dir,
+            |volume| {
+                pb.inc(1);
+                emit(STAGE_COVERS, volume);
+                done += 1;
+            },
+        )
+        .await;
+        checkpoint.progress = start + done;
+        result?;
+        pb.finish_with_message("Complete!");
+        checkpoint.finish(STAGE_COVERS);
+    }
+
+    Ok(())
+}
+
+/// Fetches, checks, and enriches the requested volumes, returning none if the range is
+/// empty. `previous_count` is how many volumes the last run produced, when known. When
+/// `cancel` is cancelled the run stops, saving a checkpoint to resume from.
+pub async fn collect(
+    args: &CollectArgs,
+    previous_count: Option<usize>,
+    cancel: CancellationToken,
+) -> Result<Vec<Volume>> {
+    if args.enrich.contains(&Enrichment::Worldcat)
+        && (args.worldcat_key.is_none() || args.worldcat_secret.is_none())
+    {
+        bail!("--enrich worldcat needs a WSKey: pass --worldcat-key and --worldcat-secret");
+    }
+    if !(args.requests_per_second.is_finite() && args.requests_per_second > 0.0) {
+        bail!("--requests-per-second must be more than 0");
+    }
+    if args.interactive && !io::stdin().is_terminal() {
+        bail!("--interactive asks questions on the terminal, but stdin isn't one");
+    }
+
+    eprintln!(
+        "{} {}",
+        "🔍".cyan(),
+        "Scraping Library of America volumes".bright_blue().bold()
+    );
+
+    let throttle = ThrottleMonitor::new();
+    let mut client = Client::new()
+        .with_max_requests(args.max_requests)
+        .with_rate_limit(args.rate_limit())
+        .with_middleware(throttle.clone())
+        .with_cancellation(cancel);
+    if args.adaptive_rate {
+        client = client.with_adaptive_rate();
+    }
+    let checkpoint_path = args
+        .checkpoint
+        .clone()
+        .unwrap_or_else(default_checkpoint_path);
+
+    let mut checkpoint = if args.resume {
+        let checkpoint = Checkpoint::load(&checkpoint_path)?;
+        eprintln!(
+            "{} {} {} volumes, {} stage(s) already done",
+            "⏯️".cyan(),
+            "Resuming from checkpoint:".cyan().bold(),
+            checkpoint.listings.len(),
+            checkpoint.completed.len()
+        );
+        checkpoint
+    } else {
+        // Nothing to checkpoint yet, so this isn't a resumable stop
+        let listings = fetch_listings(&client, args, previous_count)
+            .await
+            .map_err(|e| match e.downcast::<BudgetExhausted>() {
+                Ok(budget) => anyhow!("{}; the collection page alone needs one request", budget),
+                Err(e) => e,
+            })?;
+        if listings.is_empty() {
+            eprintln!(
+                "{} {}",
+                "⚠️".yellow(),
+                "No volumes found in specified range".yellow()
+            );
+            return Ok(Vec::new());
+        }
+        Checkpoint::new(listings)
+    };
+
+    if args.dry_run {
+        print_plan(args, &checkpoint)?;
+        return Ok(Vec::new());
+    }
+
+    let webhook = args
+        .emit_webhook
+        .as_deref()
+        .map(WebhookEmitter::start)
+        .transpose()?;
+    let result = run_stages(&client, args, &mut checkpoint, webhook.as_ref(), &throttle).await;
+    if let Some(webhook) = webhook {
+        let report = webhook.finish().await?;
+        if report.failed > 0 {
+            eprintln!(
+                "{} {} {} of {} volumes weren't delivered to the webhook: {}",
+                "⚠️".yellow(),
+                "Webhook:".yellow().bold(),
+                report.failed,
+                report.sent + report.failed,
+                report.last_error.unwrap_or_default()
+            );
+        }
+    }
+    if let Err(e) = result {
+        if e.is::<BudgetExhausted>() {
+            checkpoint.save(&checkpoint_path)?;
+            eprintln!(
+                "{} {} after {} requests; progress saved to '{}'. Re-run with --resume to continue.",
+                "⏸️".yellow(),
+                "Request limit reached".yellow().bold(),
+                client.requests_made(),
+                checkpoint_path.display()
+            );
+        } else if e.is::<Cancelled>() {
+            checkpoint.save(&checkpoint_path)?;
+            eprintln!(
+                "{} {} after {} requests; progress saved to '{}'. Re-run with --resume to continue.",
+                "⏸️".yellow(),
+                "Interrupted".yellow().bold(),
+                client.requests_made(),
+                checkpoint_path.display()
+            );
+        }
+        return Err(e);
+    }
+    if checkpoint_path.exists() {
+        fs::remove_file(&checkpoint_path)?;
+    }
+
+    let mut volumes = checkpoint.volumes;
+    let state_path = args.owned_state.clone().unwrap_or_else(default_state_path);
+    OwnedState::load(&state_path)?.apply(&mut volumes);
+
+    if let Some(export_path) = &args.owned_from {
+        let shelf = read_shelf_export(export_path)?;
+        let report = mark_owned(&mut volumes, &shelf);
+
+        eprintln!(
+            "{} {} {} by ISBN, {} by title",
+            "📗".green(),
+            "Marked owned volumes:".green().bold(),
+            report.by_isbn.to_string().bright_white().bold(),
+            report.by_title.to_string().bright_white().bold()
+        );
+        for ambiguous in &report.ambiguous {
+            let candidates: Vec<String> =
+                ambiguous.candidates.iter().map(|n| n.to_string()).collect();
+            eprintln!(
+                "{} {} '{}' could be volume {}",
+                "⚠️".yellow(),
+                "Ambiguous match, review by hand:".yellow().bold(),
+                ambiguous.title,
+                candidates.join(" or ")
+            );
+        }
+    }
+
+    Ok(volumes)
+}
+
+pub async fn run(args: ScrapeArgs) -> Result<()> {
+    let services = args.services.sinks()?;
+    let mut targets = OutputTarget::from_args(&args.output, args.format, args.html.options())?;
+    if args.output.is_empty() && !services.is_empty() {
+        // The service is the destination; don't also dump the volumes to stdout
+        targets.clear();
+    }
+    let previous_count = targets.iter().find_map(|target| {
+        let path = target.path.as_deref()?;
+        output::existing_volume_count(path, target.format, &args.collect.options())
+    });
+    let mut volumes = collect(&args.collect, previous_count, cancel_on_ctrl_c()).await?;
+    if volumes.is_empty() {
+        return Ok(());
+    }
+    super::redact(&mut volumes, &args.redact)?;
+    restyle(&mut volumes, args.title_style);
+
+    let mut sinks: Vec<&dyn OutputSink> = targets.iter().map(|t| t as &dyn OutputSink).collect();
+    sinks.extend(services.iter().map(|s| s.as_ref()));
+    output::write_all(&volumes, &sinks)?;
+    if let Some(path) = &args.authors_output {
+        output::write_author_index(&volumes, &mut File::create(path)?)?;
+    }
+
+    for service in &services {
+        eprintln!(
+            "{} {} {} volumes to {}",
+            "☁️".green(),
+            "Synced".green().bold(),
+            volumes.len(),
+            service.describe().bright_white()
+        );
+    }
+    for output_path in targets.iter().filter_map(|t| t.path.as_ref()) {
+        eprintln!(
+            "{} {} '{}'",
+            "💾".green(),
+            "Output file created successfully:".green().bold(),
+            output_path.bright_white()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::search::search;
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Words to look for in authors and titles
+    pub query: String,
+
+    #[arg(long, help = "Also match words with small typos")]
+    pub fuzzy: bool,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+pub fn run(args: SearchArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let matches = search(&volumes, &args.query, args.fuzzy);
+
+    if matches.is_empty() {
+        eprintln!(
+            "{} {} '{}'",
+            "🔍".cyan(),
+            "No volumes match".yellow(),
+            args.query
+        );
+        return Ok(());
+    }
+
+    for volume in &matches {
+        let status = if volume.own_volume.is_empty() {
+            "missing".red()
+        } else {
+            "owned".green()
+        };
+        println!(
+            "{:>4}  {:<8} {}",
+            volume.volume_number.to_string().bright_white().bold(),
+            status,
+            volume.original_volume_name
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use super::scrape::{CollectArgs, collect};
+use anyhow::Result;
+use chrono::Utc;
+use clap::Args;
+use colored::*;
+use loa_scraper::Volume;
+use loa_scraper::api::{self, SharedVolumes};
+use loa_scraper::ownership::{OwnedState, default_state_path};
+use loa_scraper::store::{Store, default_store_path};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    #[arg(long, default_value_t = 8080, help = "Port to listen on")]
+    pub port: u16,
+
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        default_value = "127.0.0.1",
+        help = "Address to listen on; 0.0.0.0 to serve other machines too"
+    )]
+    pub bind: IpAddr,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Store database (default: store.db in the user data directory)"
+    )]
+    pub store: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "HOURS",
+        help = "Scrape into the store every HOURS hours while serving, like `sync`"
+    )]
+    pub refresh_hours: Option<u64>,
+
+    #[command(flatten)]
+    pub collect: CollectArgs,
+}
+
+/// The store's volumes with the ownership state joined in, as `DatasetArgs` reads them.
+fn load(store_path: &Path, collect: &CollectArgs) -> Result<Vec<Volume>> {
+    let mut volumes = Store::open(store_path)?.volumes()?;
+    let owned = OwnedState::load(
+        &collect
+            .owned_state
+            .clone()
+            .unwrap_or_else(default_state_path),
+    )?;
+    owned.apply(&mut volumes);
+    Ok(volumes)
+}
+
+/// Scrapes into the store and swaps the refreshed volumes in.
+async fn refresh(args: &ServeArgs, store_path: &Path, volumes: &SharedVolumes) -> Result<()> {
+    let mut store = Store::open(store_path)?;
+    let previous_count = Some(store.count()?).filter(|&n| n > 0);
+    // Ctrl-C stops the server as usual rather than just the refresh
+    let scraped = collect(&args.collect, previous_count, CancellationToken::new()).await?;
+    if !scraped.is_empty() {
+        let seen_at = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let report = store.sync(&scraped, &args.collect.options(), &seen_at)?;
+        eprintln!(
+            "{} {} {} added, {} changed, {} unchanged",
+            "💾".green(),
+            "Refreshed store:".green().bold(),
+            report.added.len().to_string().bright_white().bold(),
+            report.changed.len().to_string().bright_white().bold(),
+            report.unchanged
+        );
+    }
+    let refreshed = load(store_path, &args.collect)?;
+    *volumes.write().unwrap_or_else(|e| e.into_inner()) = refreshed;
+    Ok(())
+}
+
+pub async fn run(args: ServeArgs) -> Result<()> {
+    let store_path = args.store.clone().unwrap_or_else(default_store_path);
+    let volumes: SharedVolumes = Arc::new(RwLock::new(load(&store_path, &args.collect)?));
+    let addr = SocketAddr::new(args.bind, args.port);
+
+    let mut server = tokio::spawn(api::serve(addr, volumes.clone())?);
+    eprintln!(
+        "{} {} {} volumes on {}",
+        "🌐".green(),
+        "Serving".green().bold(),
+        volumes.read().unwrap_or_else(|e| e.into_inner()).len(),
+        format!("http://{}", addr).bright_white()
+    );
+    #[cfg(feature = "graphql")]
+    eprintln!("   GET /volumes, /volumes/{{n}}, /authors; POST /graphql; Ctrl-C to stop");
+    #[cfg(not(feature = "graphql"))]
+    eprintln!("   GET /volumes, /volumes/{{n}}, /authors; Ctrl-C to stop");
+
+    let Some(hours) = args.refresh_hours.filter(|&h| h > 0) else {
+        return server.await?;
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs(hours * 60 * 60));
+    // The first tick is immediate; the store was just loaded
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            result = &mut server => return result?,
+            _ = interval.tick() => {}
+        }
+        // A failed refresh keeps serving the last good volumes
+        if let Err(e) = refresh(&args, &store_path, &volumes).await {
+            eprintln!(
+                "{} {} {:#}",
+                "⚠️".yellow(),
+                "Refresh failed, still serving the previous volumes:"
+                    .yellow()
+                    .bold(),
+                e
+            );
+        }
+    }
+}
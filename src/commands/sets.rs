@@ -0,0 +1,131 @@
+use super::progress_bar;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::http::Client;
+use loa_scraper::output::write_csv_records;
+use loa_scraper::ownership::{OwnedState, default_state_path};
+use loa_scraper::sets::{BoxedSet, scrape_boxed_sets};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SetsFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct SetsArgs {
+    #[arg(short, long, help = "Output file path (default: stdout)")]
+    pub output: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = SetsFormat::Csv,
+        help = "Output format"
+    )]
+    pub format: SetsFormat,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Ownership state kept by `own add` (default: owned.json in the user data directory)"
+    )]
+    pub owned_state: Option<PathBuf>,
+}
+
+/// One set, with what it would add to the collection.
+#[derive(Debug, Serialize)]
+struct SetRow {
+    title: String,
+    price_usd: String,
+    member_price_usd: String,
+    volumes: String,
+    volume_count: usize,
+    /// Volumes in the set not yet owned, and what each costs at the list price.
+    missing_volumes: String,
+    missing_count: usize,
+    price_per_missing: String,
+    loa_detail_link: String,
+}
+
+fn numbers(volumes: &[u32]) -> String {
+    volumes
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn row(set: &BoxedSet, owned: &OwnedState) -> SetRow {
+    let missing: Vec<u32> = set
+        .volume_numbers
+        .iter()
+        .copied()
+        .filter(|n| !owned.owned.contains(n))
+        .collect();
+    let price_per_missing = match set.price_usd.parse::<f64>() {
+        Ok(price) if !missing.is_empty() => format!("{:.2}", price / missing.len() as f64),
+        _ => String::new(),
+    };
+
+    SetRow {
+        title: set.title.clone(),
+        price_usd: set.price_usd.clone(),
+        member_price_usd: set.member_price_usd.clone(),
+        volumes: numbers(&set.volume_numbers),
+        volume_count: set.volume_numbers.len(),
+        missing_volumes: numbers(&missing),
+        missing_count: missing.len(),
+        price_per_missing,
+        loa_detail_link: set.loa_detail_link.clone(),
+    }
+}
+
+pub async fn run(args: SetsArgs) -> Result<()> {
+    let owned = OwnedState::load(&args.owned_state.clone().unwrap_or_else(default_state_path))?;
+    let client = Client::new();
+
+    eprintln!("{} {}", "📦".cyan(), "Fetching boxed sets...".cyan());
+    let pb = progress_bar(0);
+    let sets = scrape_boxed_sets(&client, |_| {
+        pb.inc_length(1);
+        pb.inc(1);
+    })
+    .await?;
+    pb.finish_with_message("Complete!");
+
+    // Cheapest way to fill a gap first; sets with nothing new or no price last
+    let mut rows: Vec<SetRow> = sets.iter().map(|set| row(set, &owned)).collect();
+    rows.sort_by(|a, b| {
+        let cost = |row: &SetRow| row.price_per_missing.parse::<f64>().unwrap_or(f64::MAX);
+        cost(a).total_cmp(&cost(b))
+    });
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    match args.format {
+        SetsFormat::Csv => write_csv_records(&rows, &mut out)?,
+        SetsFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+
+    eprintln!(
+        "{} {} {} boxed sets, {} with volumes you don't own",
+        "✅".green(),
+        "Found".green().bold(),
+        rows.len(),
+        rows.iter().filter(|r| r.missing_count > 0).count()
+    );
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use loa_scraper::stats::{Stats, fill_gaps, sparkline};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write a shields.io endpoint badge JSON showing collection progress"
+    )]
+    pub badge: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 10,
+        help = "Number of authors to list"
+    )]
+    pub top: usize,
+
+    #[arg(long, help = "Print the full summary as JSON instead of a table")]
+    pub json: bool,
+}
+
+fn print_table(stats: &Stats, top: usize) {
+    println!("{:<22} {}", "Volumes", stats.total);
+    println!(
+        "{:<22} {} ({:.0}%)",
+        "Owned",
+        stats.owned,
+        stats.owned_percent()
+    );
+    println!("{:<22} {}", "Missing", stats.missing);
+    println!(
+        "{:<22} {}",
+        "No Wikipedia match",
+        stats.without_wikipedia.len()
+    );
+    print_series("Volumes per decade", &fill_gaps(&stats.per_decade, 10), "s");
+    print_series(
+        "Acquisitions per year",
+        &fill_gaps(&stats.acquired_per_year, 1),
+        "",
+    );
+
+    if !stats.per_decade.is_empty() {
+        println!();
+        println!("{:<10} {:>7}", "Decade".bold(), "Volumes".bold());
+        for (decade, count) in &stats.per_decade {
+            println!("{:<10} {:>7}", format!("{}s", decade), count);
+        }
+    }
+
+    if !stats.per_author.is_empty() && top > 0 {
+        println!();
+        println!(
+            "{:<40} {:>7} {:>7}",
+            "Author".bold(),
+            "Volumes".bold(),
+            "Owned".bold()
+        );
+        for author in stats.per_author.iter().take(top) {
+            println!(
+                "{:<40} {:>7} {:>7}",
+                author.author, author.volumes, author.owned
+            );
+        }
+        if stats.per_author.len() > top {
+            println!("... and {} more authors", stats.per_author.len() - top);
+        }
+    }
+}
+
+/// Prints a one-line sparkline such as `1980s ▂▅█▃ 2010s (peak 120 in 1990s)`.
+fn print_series(label: &str, buckets: &[(i32, usize)], suffix: &str) {
+    let (Some((first, _)), Some((last, _))) = (buckets.first(), buckets.last()) else {
+        return;
+    };
+    let Some((peak_bucket, peak)) = buckets.iter().max_by_key(|(_, count)| *count) else {
+        return;
+    };
+    let counts: Vec<usize> = buckets.iter().map(|(_, count)| *count).collect();
+
+    println!(
+        "{:<22} {}{} {} {}{}  (peak {} in {}{})",
+        label,
+        first,
+        suffix,
+        sparkline(&counts).cyan(),
+        last,
+        suffix,
+        peak,
+        peak_bucket,
+        suffix
+    );
+}
+
+pub fn run(args: StatsArgs) -> Result<()> {
+    let (volumes, owned) = args.dataset.load()?;
+
+    let stats = Stats::from_volumes(&volumes, &owned);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        print_table(&stats, args.top);
+    }
+
+    if let Some(badge_path) = &args.badge {
+        fs::write(
+            badge_path,
+            serde_json::to_string_pretty(&stats.badge())? + "\n",
+        )?;
+        eprintln!(
+            "{} {} '{}'",
+            "💾".green(),
+            "Badge written to".green().bold(),
+            badge_path.display().to_string().bright_white()
+        );
+    }
+
+    Ok(())
+}
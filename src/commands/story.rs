@@ -0,0 +1,112 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::http::Client;
+use loa_scraper::output::write_csv_records;
+use loa_scraper::story::{Story, scrape_stories, source_volume};
+use loa_scraper::volume::Volume;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StoryFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct StoryArgs {
+    #[arg(short, long, help = "Output file path (default: stdout)")]
+    pub output: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = StoryFormat::Csv,
+        help = "Output format"
+    )]
+    pub format: StoryFormat,
+
+    #[arg(
+        long,
+        default_value_t = 10,
+        help = "How many archive pages to read, newest first"
+    )]
+    pub pages: usize,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+/// One story, with the dataset volume it was matched to.
+#[derive(Debug, Serialize)]
+struct StoryRow {
+    title: String,
+    author: String,
+    source_volume: String,
+    /// Blank when the source isn't a volume in the dataset.
+    volume_number: String,
+    series: String,
+    own_volume: String,
+    link: String,
+    source_link: String,
+}
+
+fn row(story: Story, volume: Option<&Volume>) -> StoryRow {
+    StoryRow {
+        volume_number: volume
+            .filter(|v| v.volume_number > 0)
+            .map(|v| v.volume_number.to_string())
+            .unwrap_or_default(),
+        series: volume.map(|v| v.series.clone()).unwrap_or_default(),
+        own_volume: volume.map(|v| v.own_volume.clone()).unwrap_or_default(),
+        title: story.title,
+        author: story.author,
+        source_volume: story.source_volume,
+        link: story.link,
+        source_link: story.source_link,
+    }
+}
+
+pub async fn run(args: StoryArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+    let client = Client::new();
+
+    eprintln!("{} {}", "📖".cyan(), "Fetching Story of the Week...".cyan());
+    let stories = scrape_stories(&client, args.pages).await?;
+
+    let mut matched = 0;
+    let rows: Vec<StoryRow> = stories
+        .into_iter()
+        .map(|story| {
+            let volume = source_volume(&story, &volumes);
+            matched += usize::from(volume.is_some());
+            row(story, volume)
+        })
+        .collect();
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    match args.format {
+        StoryFormat::Csv => write_csv_records(&rows, &mut out)?,
+        StoryFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+
+    eprintln!(
+        "{} {} {} stories, {} matched to a volume",
+        "✅".green(),
+        "Found".green().bold(),
+        rows.len(),
+        matched
+    );
+    Ok(())
+}
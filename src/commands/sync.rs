@@ -0,0 +1,360 @@
+use super::checkpoint::cancel_on_ctrl_c;
+use super::scrape::{CollectArgs, collect};
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::Volume;
+use loa_scraper::http::Client;
+use loa_scraper::notify::{discord_messages, post_messages, slack_messages};
+use loa_scraper::sanity::comparable_count;
+use loa_scraper::store::{Store, SyncReport, default_store_path};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SmtpSecurity {
+    Starttls,
+    Tls,
+    None,
+}
+
+#[derive(Args, Debug)]
+pub struct EmailArgs {
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Mail a summary of the changes, with the synced volumes as CSV attached, to this address"
+    )]
+    pub email_report: Option<String>,
+
+    #[arg(long, value_name = "HOST", help = "SMTP server for --email-report")]
+    pub smtp_host: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "SMTP port (default: 587 for starttls, 465 for tls, 25 for none)"
+    )]
+    pub smtp_port: Option<u16>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SmtpSecurity::Starttls,
+        help = "How the SMTP connection is encrypted"
+    )]
+    pub smtp_security: SmtpSecurity,
+
+    #[arg(
+        long,
+        value_name = "USER",
+        env = "SMTP_USERNAME",
+        help = "SMTP username"
+    )]
+    pub smtp_username: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PASSWORD",
+        env = "SMTP_PASSWORD",
+        hide_env_values = true,
+        help = "SMTP password"
+    )]
+    pub smtp_password: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Sender of --email-report (default: the SMTP username)"
+    )]
+    pub email_from: Option<String>,
+}
+
+#[cfg(feature = "email")]
+type Mailer = loa_scraper::email::Mailer;
+#[cfg(not(feature = "email"))]
+type Mailer = std::convert::Infallible;
+
+impl EmailArgs {
+    /// The configured mailer, checked before scraping so a bad setup fails fast.
+    fn mailer(&self) -> Result<Option<Mailer>> {
+        let Some(to) = &self.email_report else {
+            return Ok(None);
+        };
+        let host = self
+            .smtp_host
+            .clone()
+            .ok_or_else(|| anyhow!("--email-report needs --smtp-host"))?;
+        let from = self
+            .email_from
+            .clone()
+            .or_else(|| self.smtp_username.clone())
+            .ok_or_else(|| anyhow!("--email-report needs --email-from or --smtp-username"))?;
+        let credentials = match (&self.smtp_username, &self.smtp_password) {
+            (Some(username), Some(password)) => Some((username.clone(), password.clone())),
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "--smtp-username and --smtp-password (or SMTP_USERNAME and SMTP_PASSWORD) go together"
+                ));
+            }
+        };
+
+        #[cfg(feature = "email")]
+        {
+            use loa_scraper::email::Security;
+            let security = match self.smtp_security {
+                SmtpSecurity::Starttls => Security::StartTls,
+                SmtpSecurity::Tls => Security::Tls,
+                SmtpSecurity::None => Security::None,
+            };
+            Ok(Some(Mailer {
+                port: self.smtp_port.unwrap_or(security.default_port()),
+                host,
+                security,
+                credentials,
+                from,
+                to: to.clone(),
+            }))
+        }
+        #[cfg(not(feature = "email"))]
+        {
+            let port = self
+                .smtp_port
+                .map(|port| format!(":{}", port))
+                .unwrap_or_default();
+            let user = credentials.map(|(username, _)| format!(" as {}", username));
+            Err(anyhow!(
+                "Can't mail the report to {} from {} through {}{}{}: built without the email feature",
+                to,
+                from,
+                host,
+                port,
+                user.unwrap_or_default()
+            ))
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct SyncArgs {
+    #[command(flatten)]
+    pub collect: CollectArgs,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Store database (default: store.db in the user data directory)"
+    )]
+    pub store: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        env = "SLACK_WEBHOOK_URL",
+        hide_env_values = true,
+        help = "Announce new volumes in a Slack channel through this incoming webhook"
+    )]
+    pub slack_webhook: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        env = "DISCORD_WEBHOOK_URL",
+        hide_env_values = true,
+        help = "Announce new volumes in a Discord channel through this webhook"
+    )]
+    pub discord_webhook: Option<String>,
+
+    #[arg(
+        long,
+        help = "Pop a desktop notification for new volumes and price changes"
+    )]
+    pub desktop_notify: bool,
+
+    #[command(flatten)]
+    pub email: EmailArgs,
+}
+
+/// Posts the new volumes to the configured chat webhooks. A failed post is reported
+/// but doesn't undo the sync.
+async fn announce(args: &SyncArgs, added: &[Volume]) {
+    let client = Client::new();
+    let chats = [
+        ("Slack", &args.slack_webhook, slack_messages(added)),
+        ("Discord", &args.discord_webhook, discord_messages(added)),
+    ];
+    for (name, url, messages) in chats {
+        let Some(url) = url else {
+            continue;
+        };
+        match post_messages(&client, url, &messages).await {
+            Ok(()) => eprintln!(
+                "{} {} {} new volumes to {}",
+                "📣".green(),
+                "Announced".green().bold(),
+                added.len(),
+                name
+            ),
+            Err(e) => eprintln!(
+                "{} {} {}",
+                "⚠️".yellow(),
+                format!("Couldn't post to {}:", name).yellow().bold(),
+                e
+            ),
+        }
+    }
+}
+
+/// The subject and body of the `--email-report` message for one sync.
+#[cfg(feature = "email")]
+fn email_report(
+    report: &SyncReport,
+    volumes: &[Volume],
+    seen_at: &str,
+) -> Result<(String, String)> {
+    use loa_scraper::digest::{self, Digest};
+    let subject = format!(
+        "LOA sync: {} added, {} changed, {} delisted",
+        report.added.len(),
+        report.changed.len(),
+        report.delisted.len()
+    );
+    let mut body = Vec::new();
+    digest::write_text(&Digest::from_sync(volumes, seen_at), &mut body)?;
+    let mut body = String::from_utf8(body)?;
+    if !report.changed.is_empty() {
+        body.push_str(&format!("\nChanged: {}\n", keys(&report.changed)));
+    }
+    body.push_str(&format!(
+        "\n{} volumes in the store, {} unchanged this run.\n",
+        volumes.len(),
+        report.unchanged
+    ));
+    Ok((subject, body))
+}
+
+#[cfg(feature = "email")]
+async fn send_email(
+    mailer: &Mailer,
+    report: &SyncReport,
+    volumes: &[Volume],
+    seen_at: &str,
+) -> Result<()> {
+    let (subject, body) = email_report(report, volumes, seen_at)?;
+    let mut csv = Vec::new();
+    loa_scraper::output::write_csv_records(volumes, &mut csv)?;
+    let filename = format!("loa-volumes-{}.csv", seen_at.get(..10).unwrap_or(seen_at));
+    mailer.send(&subject, &body, &filename, csv).await
+}
+
+#[cfg(not(feature = "email"))]
+async fn send_email(
+    mailer: &Mailer,
+    _report: &SyncReport,
+    _volumes: &[Volume],
+    _seen_at: &str,
+) -> Result<()> {
+    match *mailer {}
+}
+
+fn keys(volumes: &[String]) -> String {
+    volumes.join(", ")
+}
+
+pub async fn run(args: SyncArgs) -> Result<()> {
+    let store_path = args.store.clone().unwrap_or_else(default_store_path);
+    let mut store = Store::open(&store_path)?;
+    let mailer = args.email.mailer()?;
+    #[cfg(not(feature = "desktop"))]
+    if args.desktop_notify {
+        return Err(anyhow!(
+            "Can't show desktop notifications: built without the desktop feature"
+        ));
+    }
+
+    let previous_count =
+        Some(comparable_count(&store.volumes()?, &args.collect.options())).filter(|&n| n > 0);
+    let volumes = collect(&args.collect, previous_count, cancel_on_ctrl_c()).await?;
+    if volumes.is_empty() {
+        return Ok(());
+    }
+
+    let seen_at = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let report = store.sync(&volumes, &args.collect.options(), &seen_at)?;
+
+    eprintln!(
+        "{} {} {} added, {} changed, {} unchanged",
+        "💾".green(),
+        "Synced store:".green().bold(),
+        report.added.len().to_string().bright_white().bold(),
+        report.changed.len().to_string().bright_white().bold(),
+        report.unchanged
+    );
+    if !report.added.is_empty() {
+        eprintln!("{} New volumes: {}", "🆕".green(), keys(&report.added));
+    }
+    if !report.changed.is_empty() {
+        eprintln!("{} Changed volumes: {}", "✏️".cyan(), keys(&report.changed));
+    }
+    for change in &report.repriced {
+        eprintln!(
+            "{} Price of {}: ${} → ${}",
+            "💲".cyan(),
+            change.volume.key(),
+            change.old_price,
+            change.new_price
+        );
+    }
+    let chat = args.slack_webhook.is_some() || args.discord_webhook.is_some();
+    let added: Vec<Volume> = if !report.added.is_empty() && (chat || args.desktop_notify) {
+        store
+            .volumes()?
+            .into_iter()
+            .filter(|v| v.first_seen.as_deref() == Some(seen_at.as_str()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if chat && !added.is_empty() {
+        announce(&args, &added).await;
+    }
+    #[cfg(feature = "desktop")]
+    if args.desktop_notify {
+        let toasts = loa_scraper::notify::desktop_toasts(&added, &report.repriced);
+        if let Err(e) = loa_scraper::notify::show_toasts(&toasts) {
+            eprintln!(
+                "{} {} {}",
+                "⚠️".yellow(),
+                "Couldn't show desktop notifications:".yellow().bold(),
+                e
+            );
+        }
+    }
+    if !report.missing.is_empty() {
+        eprintln!(
+            "{} {} {}",
+            "⚠️".yellow(),
+            "No longer listed, kept as delisted:".yellow().bold(),
+            keys(&report.missing)
+        );
+    }
+    if let Some(mailer) = &mailer {
+        match send_email(mailer, &report, &store.volumes()?, &seen_at).await {
+            Ok(()) => eprintln!(
+                "{} {} report to {}",
+                "📧".green(),
+                "Mailed".green().bold(),
+                args.email.email_report.as_deref().unwrap_or_default()
+            ),
+            Err(e) => eprintln!(
+                "{} {} {:#}",
+                "⚠️".yellow(),
+                "Couldn't mail the report:".yellow().bold(),
+                e
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::*;
+use loa_scraper::output::{timeline_entries, write_timeline_html};
+use std::fs::File;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TimelineFormat {
+    Html,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct TimelineArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = TimelineFormat::Html,
+        help = "Output format: a self-contained interactive page, or the timeline's entries as JSON"
+    )]
+    pub format: TimelineFormat,
+
+    #[arg(short, long, help = "Output file path (default: stdout)")]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+pub fn run(args: TimelineArgs) -> Result<()> {
+    let (volumes, _) = args.dataset.load()?;
+
+    let undated = volumes.iter().filter(|v| v.period().is_none()).count();
+    if undated > 0 {
+        eprintln!(
+            "{} {} volumes have no years in their title or author dates and are left off the timeline",
+            "⚠️".yellow(),
+            undated
+        );
+    }
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    match args.format {
+        TimelineFormat::Html => write_timeline_html(&volumes, &mut out)?,
+        TimelineFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &timeline_entries(&volumes))?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+
+    if let Some(path) = &args.output {
+        eprintln!(
+            "{} {} timeline of {} volumes to '{}'",
+            "💾".green(),
+            "Wrote".green().bold(),
+            volumes.len() - undated,
+            path.bright_white()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,386 @@
+use super::DatasetArgs;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn run(_args: TuiArgs) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Can't start the TUI: built without the tui feature"
+    ))
+}
+
+#[cfg(feature = "tui")]
+pub fn run(args: TuiArgs) -> Result<()> {
+    use loa_scraper::ownership::default_state_path;
+
+    let (volumes, owned) = args.dataset.load()?;
+    let state_path = args
+        .dataset
+        .owned_state
+        .clone()
+        .unwrap_or_else(default_state_path);
+    let mut app = app::App::new(volumes, owned, state_path);
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(feature = "tui")]
+mod app {
+    use anyhow::Result;
+    use chrono::Local;
+    use loa_scraper::Volume;
+    use loa_scraper::ownership::{OWNED, OwnedState};
+    use loa_scraper::scrape::absolute_url;
+    use loa_scraper::search::search;
+    use ratatui::DefaultTerminal;
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color, Modifier, Style, Stylize};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState};
+    use ratatui::{Frame, symbols};
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    /// Which volumes the list shows, cycled with `o`.
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum OwnedFilter {
+        All,
+        Owned,
+        Missing,
+    }
+
+    impl OwnedFilter {
+        fn next(self) -> Self {
+            match self {
+                OwnedFilter::All => OwnedFilter::Owned,
+                OwnedFilter::Owned => OwnedFilter::Missing,
+                OwnedFilter::Missing => OwnedFilter::All,
+            }
+        }
+
+        fn matches(self, volume: &Volume) -> bool {
+            match self {
+                OwnedFilter::All => true,
+                OwnedFilter::Owned => volume.own_volume == OWNED,
+                OwnedFilter::Missing => volume.own_volume != OWNED,
+            }
+        }
+
+        fn label(self) -> &'static str {
+            match self {
+                OwnedFilter::All => "all",
+                OwnedFilter::Owned => "owned",
+                OwnedFilter::Missing => "missing",
+            }
+        }
+    }
+
+    pub struct App {
+        volumes: Vec<Volume>,
+        owned: OwnedState,
+        state_path: PathBuf,
+        query: String,
+        /// Whether keys go to the search box rather than the list.
+        searching: bool,
+        filter: OwnedFilter,
+        /// Indices into `volumes` of the rows shown, in order.
+        visible: Vec<usize>,
+        table: TableState,
+        status: String,
+    }
+
+    impl App {
+        pub fn new(volumes: Vec<Volume>, owned: OwnedState, state_path: PathBuf) -> Self {
+            let mut app = App {
+                volumes,
+                owned,
+                state_path,
+                query: String::new(),
+                searching: false,
+                filter: OwnedFilter::All,
+                visible: Vec::new(),
+                table: TableState::default(),
+                status: String::new(),
+            };
+            app.refilter();
+            app
+        }
+
+        pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+            loop {
+                terminal.draw(|frame| self.draw(frame))?;
+                let Event::Key(key) = event::read()? else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    return Ok(());
+                }
+                if self.searching {
+                    self.search_key(key.code);
+                } else if !self.list_key(key.code)? {
+                    return Ok(());
+                }
+            }
+        }
+
+        fn search_key(&mut self, code: KeyCode) {
+            match code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                    self.refilter();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.refilter();
+                }
+                _ => {}
+            }
+        }
+
+        /// Handles a key in the list; false when it's time to quit.
+        fn list_key(&mut self, code: KeyCode) -> Result<bool> {
+            self.status.clear();
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::PageDown => self.table.scroll_down_by(10),
+                KeyCode::PageUp => self.table.scroll_up_by(10),
+                KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+                KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+                KeyCode::Char('/') => self.searching = true,
+                KeyCode::Char('o') => {
+                    self.filter = self.filter.next();
+                    self.refilter();
+                }
+                KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle_owned()?,
+                KeyCode::Char('l') | KeyCode::Enter => {
+                    if let Some(volume) = self.selected() {
+                        let url = absolute_url(&volume.loa_detail_link);
+                        self.open(&url);
+                    }
+                }
+                KeyCode::Char('w') => {
+                    if let Some(volume) = self.selected() {
+                        match volume.author_wikipedia_link.clone() {
+                            url if url.is_empty() => {
+                                self.status = format!("No Wikipedia link for {}", volume.author)
+                            }
+                            url => self.open(&url),
+                        }
+                    }
+                }
+                _ => {}
+            }
+            Ok(true)
+        }
+
+        fn selected(&self) -> Option<&Volume> {
+            let row = self.table.selected()?;
+            self.visible.get(row).map(|&i| &self.volumes[i])
+        }
+
+        /// Re-applies the search and the owned filter, keeping the selection on the same
+        /// volume when it's still shown.
+        fn refilter(&mut self) {
+            let current = self
+                .table
+                .selected()
+                .and_then(|row| self.visible.get(row).copied());
+            let matching: Option<HashSet<String>> = (!self.query.trim().is_empty()).then(|| {
+                search(&self.volumes, &self.query, false)
+                    .iter()
+                    .map(|v| v.key())
+                    .collect()
+            });
+            self.visible = (0..self.volumes.len())
+                .filter(|&i| {
+                    let volume = &self.volumes[i];
+                    self.filter.matches(volume)
+                        && matching
+                            .as_ref()
+                            .is_none_or(|keys| keys.contains(&volume.key()))
+                })
+                .collect();
+            let row = current
+                .and_then(|i| self.visible.iter().position(|&v| v == i))
+                .unwrap_or(0);
+            self.table.select((!self.visible.is_empty()).then_some(row));
+        }
+
+        /// Marks the selected volume owned or not, saving the ownership state straight
+        /// away as `own add` and `own remove` do.
+        fn toggle_owned(&mut self) -> Result<()> {
+            let Some(&i) = self.table.selected().and_then(|row| self.visible.get(row)) else {
+                return Ok(());
+            };
+            let volume = &mut self.volumes[i];
+            if !volume.series.is_empty() || volume.volume_number == 0 {
+                self.status = "Only numbered volumes of the main series can be marked owned".into();
+                return Ok(());
+            }
+            let number = volume.volume_number;
+            if volume.own_volume == OWNED {
+                self.owned.remove(number);
+                volume.own_volume.clear();
+                self.status = format!("Volume {} marked missing", number);
+            } else {
+                let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+                self.owned.add(number, &today);
+                volume.own_volume = OWNED.to_string();
+                self.status = format!("Volume {} marked owned", number);
+            }
+            self.owned.save(&self.state_path)?;
+            self.refilter();
+            Ok(())
+        }
+
+        fn open(&mut self, url: &str) {
+            self.status = match open_in_browser(url) {
+                Ok(()) => format!("Opened {}", url),
+                Err(e) => format!("Couldn't open {}: {}", url, e),
+            };
+        }
+
+        fn draw(&mut self, frame: &mut Frame) {
+            let [search_area, list_area, detail_area, help_area] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(6),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+
+            let search_style = if self.searching {
+                Style::new().fg(Color::Yellow)
+            } else {
+                Style::new()
+            };
+            let search_line = Line::from(vec![
+                Span::styled("Search: ", search_style.bold()),
+                Span::styled(&self.query, search_style),
+                Span::raw(if self.searching { "▏" } else { "" }),
+                Span::raw(format!(
+                    "   showing {} ({} of {})",
+                    self.filter.label(),
+                    self.visible.len(),
+                    self.volumes.len()
+                ))
+                .dark_gray(),
+            ]);
+            frame.render_widget(Paragraph::new(search_line), search_area);
+
+            let rows = self.visible.iter().map(|&i| {
+                let volume = &self.volumes[i];
+                let owned = if volume.own_volume == OWNED {
+                    Span::raw("owned").green()
+                } else {
+                    Span::raw("missing").red()
+                };
+                let number = match (volume.series.as_str(), volume.volume_number) {
+                    (_, 0) => String::new(),
+                    ("", n) => n.to_string(),
+                    (series, n) => format!("{} {}", series.to_uppercase(), n),
+                };
+                Row::new(vec![
+                    Line::from(number).right_aligned(),
+                    Line::from(owned),
+                    Line::from(volume.author.as_str()),
+                    Line::from(volume.title.as_str()),
+                ])
+            });
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(7),
+                    Constraint::Length(7),
+                    Constraint::Percentage(30),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(Row::new(["#", "Owned", "Author", "Title"]).bold())
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::new().borders(Borders::TOP | Borders::BOTTOM));
+            frame.render_stateful_widget(table, list_area, &mut self.table);
+
+            let detail = match self.selected() {
+                Some(volume) => {
+                    let field = |name: &'static str, value: Option<&str>| {
+                        Line::from(vec![
+                            Span::raw(format!("{:<10}", name)).dark_gray(),
+                            Span::raw(value.unwrap_or_default().to_string()),
+                        ])
+                    };
+                    vec![
+                        Line::from(volume.original_volume_name.as_str()).bold(),
+                        field("Published", volume.publication_year.as_deref()),
+                        field("ISBN", volume.isbn.as_deref()),
+                        field("LOA", Some(&absolute_url(&volume.loa_detail_link))),
+                        field("Wikipedia", Some(&volume.author_wikipedia_link)),
+                    ]
+                }
+                None => vec![Line::from("No volumes match").dark_gray()],
+            };
+            frame.render_widget(
+                Paragraph::new(detail).block(
+                    Block::new()
+                        .borders(Borders::BOTTOM)
+                        .border_set(symbols::border::PLAIN),
+                ),
+                detail_area,
+            );
+
+            let help = if !self.status.is_empty() {
+                Line::from(self.status.as_str()).yellow()
+            } else if self.searching {
+                Line::from("Type to search · Enter keep · Esc clear").dark_gray()
+            } else {
+                Line::from(
+                    "↑↓ move · / search · o all/owned/missing · space toggle owned · l LOA page · w Wikipedia · q quit",
+                )
+                .dark_gray()
+            };
+            frame.render_widget(Paragraph::new(help), help_area);
+        }
+    }
+
+    /// Opens `url` with the platform's default handler, without waiting for it.
+    fn open_in_browser(url: &str) -> std::io::Result<()> {
+        let mut command = if cfg!(target_os = "macos") {
+            Command::new("open")
+        } else if cfg!(target_os = "windows") {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        } else {
+            Command::new("xdg-open")
+        };
+        command
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map(|_| ())
+    }
+}
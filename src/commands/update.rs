@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::*;
+use loa_scraper::http::Client;
+use loa_scraper::update::{CURRENT_VERSION, download, latest_release};
+use std::fs;
+
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+    #[arg(long, help = "Only report whether a newer release exists")]
+    pub check: bool,
+}
+
+/// Warns when a newer release is out, for `--check-update`. Never fails the run.
+pub async fn nudge(client: &Client) {
+    match latest_release(client).await {
+        Ok(release) if release.is_newer() => eprintln!(
+            "{} {} {} is available (running {}); run `loa-scraper update` or see {}",
+            "⬆️".yellow(),
+            "Update:".yellow().bold(),
+            release.version(),
+            CURRENT_VERSION,
+            release.html_url
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!(
+            "{} {} {}",
+            "⚠️".yellow(),
+            "Couldn't check for updates:".yellow().bold(),
+            e
+        ),
+    }
+}
+
+pub async fn run(args: UpdateArgs) -> Result<()> {
+    let client = Client::new();
+    let release = latest_release(&client).await?;
+    if !release.is_newer() {
+        eprintln!(
+            "{} {} {} is the latest release",
+            "✅".green(),
+            "Up to date:".green().bold(),
+            CURRENT_VERSION
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "{} {} {} → {}",
+        "⬆️".cyan(),
+        "New release:".cyan().bold(),
+        CURRENT_VERSION,
+        release.version()
+    );
+    if args.check {
+        eprintln!("   {}", release.html_url);
+        return Ok(());
+    }
+
+    let Some(asset) = release.platform_asset() else {
+        eprintln!(
+            "{} {} download it from {} or run `cargo install loa-scraper`",
+            "⚠️".yellow(),
+            "No prebuilt binary for this platform:".yellow().bold(),
+            release.html_url
+        );
+        return Ok(());
+    };
+
+    let bytes = download(&client, asset).await?;
+    let exe = std::env::current_exe()?;
+    let staged = exe.with_extension("new");
+    fs::write(&staged, &bytes).with_context(|| format!("Failed to write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows can't overwrite a running executable but can rename it out of the way
+    let old = exe.with_extension("old");
+    if cfg!(windows) {
+        fs::rename(&exe, &old)?;
+    }
+    fs::rename(&staged, &exe).with_context(|| format!("Failed to replace {}", exe.display()))?;
+
+    eprintln!(
+        "{} {} {} to {}",
+        "✅".green(),
+        "Updated".green().bold(),
+        exe.display(),
+        release.version()
+    );
+    Ok(())
+}
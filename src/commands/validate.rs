@@ -0,0 +1,62 @@
+use super::progress_bar;
+use anyhow::{Result, anyhow};
+use clap::Args;
+use colored::*;
+use loa_scraper::input::read_volumes;
+use loa_scraper::links::{LinkStatus, check_volume_links, link_check_client};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// CSV or SQLite export whose links to check
+    pub file: PathBuf,
+}
+
+pub async fn run(args: ValidateArgs) -> Result<()> {
+    let volumes = read_volumes(&args.file)?;
+    let client = link_check_client()?;
+
+    eprintln!(
+        "{} {} {} volumes",
+        "🔗".magenta(),
+        "Checking links for".magenta(),
+        volumes.len()
+    );
+
+    let pb = progress_bar(volumes.len());
+    let checks = check_volume_links(&client, &volumes, |_| pb.inc(1)).await;
+    pb.finish_with_message("Complete!");
+
+    let mut dead = 0;
+    let mut redirected = 0;
+    for check in checks.iter().filter(|c| !c.status.is_ok()) {
+        let label = match &check.status {
+            LinkStatus::Redirected { .. } => {
+                redirected += 1;
+                "redirect".yellow()
+            }
+            _ => {
+                dead += 1;
+                "dead".red()
+            }
+        };
+        println!(
+            "#{:<4} {:<8} {:<22} {} ({})",
+            check.volume_number, label, check.field, check.url, check.status
+        );
+    }
+
+    eprintln!(
+        "{} {} links checked: {} ok, {} redirected, {} dead",
+        "✅".green(),
+        checks.len(),
+        checks.len() - dead - redirected,
+        redirected,
+        dead
+    );
+
+    if dead > 0 {
+        return Err(anyhow!("{} dead links in '{}'", dead, args.file.display()));
+    }
+    Ok(())
+}
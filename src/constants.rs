@@ -0,0 +1,120 @@
+use crate::Volume;
+use crate::scrape::SERIES_APS;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const PUBLISHER: &str = "Library of America";
+pub const PUBLICATION_PLACE: &str = "New York";
+pub const SERIES: &str = "Library of America";
+pub const APS_SERIES: &str = "American Poets Project";
+
+/// Environment variable naming a series metadata file to use instead of the default.
+pub const SERIES_METADATA_ENV: &str = "LOA_SERIES_METADATA";
+
+/// Where series metadata overrides are read from when `LOA_SERIES_METADATA` isn't set.
+pub fn default_series_metadata_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("series.json")
+}
+
+/// Publisher and series details the bibliographic exports (MARC, CSL, RIS, JSON-LD,
+/// Turtle, ...) all fill in, so they describe each volume the same way. A config file
+/// can override any of them, e.g. to match a library's authority records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeriesMetadata {
+    pub publisher: String,
+    pub publication_place: String,
+    pub series_title: String,
+    pub aps_series_title: String,
+    /// ISSNs for the two series; none are recorded unless configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aps_issn: Option<String>,
+}
+
+impl Default for SeriesMetadata {
+    fn default() -> Self {
+        SeriesMetadata {
+            publisher: PUBLISHER.to_string(),
+            publication_place: PUBLICATION_PLACE.to_string(),
+            series_title: SERIES.to_string(),
+            aps_series_title: APS_SERIES.to_string(),
+            issn: None,
+            aps_issn: None,
+        }
+    }
+}
+
+impl SeriesMetadata {
+    /// Loads overrides, treating a missing file as none. Fields the file leaves out
+    /// keep their defaults.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read series metadata {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse series metadata {}", path.display()))
+    }
+
+    /// The series title a volume was published in.
+    pub fn series_title(&self, volume: &Volume) -> &str {
+        if volume.series == SERIES_APS {
+            &self.aps_series_title
+        } else {
+            &self.series_title
+        }
+    }
+
+    /// The ISSN of a volume's series, if one is configured.
+    pub fn issn(&self, volume: &Volume) -> Option<&str> {
+        if volume.series == SERIES_APS {
+            self.aps_issn.as_deref()
+        } else {
+            self.issn.as_deref()
+        }
+    }
+}
+
+static METADATA: OnceLock<SeriesMetadata> = OnceLock::new();
+
+/// The series metadata exports use: whatever was installed with `set_metadata`, or the
+/// defaults.
+pub fn metadata() -> &'static SeriesMetadata {
+    METADATA.get_or_init(SeriesMetadata::default)
+}
+
+/// Installs the metadata for the rest of the process. Fails once exports have already
+/// used it.
+pub fn set_metadata(metadata: SeriesMetadata) -> Result<()> {
+    METADATA
+        .set(metadata)
+        .map_err(|_| anyhow!("Series metadata was already in use"))
+}
+
+/// Installs overrides from `LOA_SERIES_METADATA`, or the default config path.
+pub fn load_configured_metadata() -> Result<()> {
+    let metadata = match std::env::var_os(SERIES_METADATA_ENV) {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !path.exists() {
+                return Err(anyhow!(
+                    "{} names {}, which doesn't exist",
+                    SERIES_METADATA_ENV,
+                    path.display()
+                ));
+            }
+            SeriesMetadata::load(&path)?
+        }
+        None => SeriesMetadata::load(&default_series_metadata_path())?,
+    };
+    set_metadata(metadata)
+}
@@ -0,0 +1,269 @@
+//! Links a list of ISBNs from a home library to the LOA catalog: which books are LOA
+//! volumes, and which LOA volumes collect works the library already has in other
+//! editions.
+
+use crate::Volume;
+use crate::details::is_valid_isbn13;
+use crate::enrich::USER_AGENT;
+use crate::http::Client;
+use crate::volume::normalize_title;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+const SEARCH_URL: &str = "https://openlibrary.org/search.json";
+
+/// A book from the library list, with what OpenLibrary knows about it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LibraryBook {
+    pub isbn: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+/// A library book that is itself an LOA volume.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoaEdition {
+    pub isbn: String,
+    pub volume: String,
+    pub title: String,
+}
+
+/// An LOA volume collecting works the library already has in other editions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicate {
+    pub volume: String,
+    pub title: String,
+    pub author: String,
+    /// How many works the volume's title lists, and how many of them are owned.
+    pub works: usize,
+    pub owned: usize,
+    /// "Typee (9780143039440)" for each library book the volume contains.
+    pub owned_works: Vec<String>,
+}
+
+impl Duplicate {
+    /// Share of the volume's works already owned in some edition.
+    pub fn owned_percent(&self) -> f64 {
+        if self.works == 0 {
+            return 0.0;
+        }
+        (self.owned * 100) as f64 / self.works as f64
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Coverage {
+    pub library_books: usize,
+    /// Books OpenLibrary had no title for, which can only match by ISBN.
+    pub unresolved: usize,
+    pub loa_editions: Vec<LoaEdition>,
+    /// Books (by ISBN) whose work some LOA volume collects.
+    pub collected: usize,
+    pub duplicates: Vec<Duplicate>,
+}
+
+impl Coverage {
+    /// Share of the library that is an LOA volume or is collected in one.
+    pub fn percent(&self) -> f64 {
+        if self.library_books == 0 {
+            return 0.0;
+        }
+        (self.loa_editions.len() + self.collected) as f64 * 100.0 / self.library_books as f64
+    }
+}
+
+/// Reads ISBN-10s or ISBN-13s, one per line, as ISBN-13s. Blank lines and `#`
+/// comments are skipped; returns the lines that aren't valid ISBNs separately.
+pub fn read_isbn_list(path: &Path) -> Result<(Vec<String>, Vec<String>)> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut isbns = Vec::new();
+    let mut invalid = Vec::new();
+    let mut seen = HashSet::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        match to_isbn13(line) {
+            Some(isbn) if seen.insert(isbn.clone()) => isbns.push(isbn),
+            Some(_) => {}
+            None => invalid.push(line.to_string()),
+        }
+    }
+    Ok((isbns, invalid))
+}
+
+/// Normalizes an ISBN-10 or ISBN-13, with or without hyphens, to ISBN-13.
+pub fn to_isbn13(text: &str) -> Option<String> {
+    let chars: Vec<u8> = text
+        .bytes()
+        .filter(|b| b.is_ascii_digit() || *b == b'X' || *b == b'x')
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+
+    match chars.len() {
+        13 if !chars.contains(&b'X') && is_valid_isbn13(&chars) => String::from_utf8(chars).ok(),
+        // Only an ISBN-10's check digit can be X
+        10 if !chars[..9].contains(&b'X') => {
+            let sum: u32 = chars
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let value = if *c == b'X' { 10 } else { (c - b'0') as u32 };
+                    value * (10 - i as u32)
+                })
+                .sum();
+            if !sum.is_multiple_of(11) {
+                return None;
+            }
+            let mut digits = b"978".to_vec();
+            digits.extend_from_slice(&chars[..9]);
+            let weighted: u32 = digits
+                .iter()
+                .enumerate()
+                .map(|(i, d)| (d - b'0') as u32 * if i % 2 == 0 { 1 } else { 3 })
+                .sum();
+            digits.push(b'0' + ((10 - weighted % 10) % 10) as u8);
+            String::from_utf8(digits).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Looks a library book up on OpenLibrary. Lookup failures leave the title empty.
+pub async fn lookup_book(client: &Client, isbn: &str) -> LibraryBook {
+    let mut book = LibraryBook {
+        isbn: isbn.to_string(),
+        ..LibraryBook::default()
+    };
+    let query = format!("isbn:{}", isbn);
+    let Ok(response) = client
+        .get(SEARCH_URL)
+        .query(&[
+            ("q", query.as_str()),
+            ("fields", "title,author_name"),
+            ("limit", "1"),
+        ])
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+    else {
+        return book;
+    };
+    let Ok(body) = response.json::<Value>() else {
+        return book;
+    };
+    let doc = body.pointer("/docs/0");
+    book.title = doc
+        .and_then(|d| d.get("title"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    book.author = doc
+        .and_then(|d| d.pointer("/author_name/0"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    book
+}
+
+/// The works a volume's title lists, e.g. "Typee, Omoo, Mardi" or "The Sun Also Rises
+/// & Other Writings", compared without case, punctuation, or a leading article.
+/// Catch-alls like "Other Writings" aren't counted as works.
+pub fn contained_works(volume: &Volume) -> Vec<String> {
+    volume
+        .title
+        .split([',', ';', ':', '/', '&'])
+        .map(work_key)
+        .filter(|work| !work.is_empty() && !work.starts_with("other "))
+        .collect()
+}
+
+pub(crate) fn work_key(title: &str) -> String {
+    let normalized = normalize_title(title);
+    ["the ", "a ", "an "]
+        .iter()
+        .find_map(|article| normalized.strip_prefix(article))
+        .unwrap_or(&normalized)
+        .to_string()
+}
+
+/// The family name, which is all a book's author and a volume's author reliably share.
+fn surname(author: &str) -> String {
+    normalize_title(author)
+        .rsplit(' ')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The work of `volume` that `book` is an edition of: one with the book's title
+/// (without a subtitle) and, when OpenLibrary knows the book's author, the same author.
+fn collected_work(volume: &Volume, book: &LibraryBook) -> Option<String> {
+    let title = work_key(book.title.as_deref()?.split(':').next().unwrap_or_default());
+    let same_author = book
+        .author
+        .as_deref()
+        .is_none_or(|author| normalize_title(&volume.author).contains(&surname(author)));
+    (same_author && contained_works(volume).contains(&title)).then_some(title)
+}
+
+pub fn coverage(volumes: &[Volume], books: &[LibraryBook]) -> Coverage {
+    let mut report = Coverage {
+        library_books: books.len(),
+        unresolved: books.iter().filter(|b| b.title.is_none()).count(),
+        ..Coverage::default()
+    };
+
+    let mut collected = HashSet::new();
+    for book in books {
+        if let Some(volume) = volumes
+            .iter()
+            .find(|v| v.isbn.as_deref() == Some(book.isbn.as_str()))
+        {
+            report.loa_editions.push(LoaEdition {
+                isbn: book.isbn.clone(),
+                volume: volume.key(),
+                title: volume.title.clone(),
+            });
+        }
+    }
+    let loa_isbns: HashSet<&str> = report
+        .loa_editions
+        .iter()
+        .map(|e| e.isbn.as_str())
+        .collect();
+
+    for volume in volumes {
+        let mut owned = HashSet::new();
+        let mut owned_works = Vec::new();
+        for book in books
+            .iter()
+            .filter(|b| !loa_isbns.contains(b.isbn.as_str()))
+        {
+            if let Some(work) = collected_work(volume, book) {
+                owned.insert(work);
+                collected.insert(book.isbn.as_str());
+                owned_works.push(format!(
+                    "{} ({})",
+                    book.title.as_deref().unwrap_or_default(),
+                    book.isbn
+                ));
+            }
+        }
+        if !owned_works.is_empty() {
+            report.duplicates.push(Duplicate {
+                volume: volume.key(),
+                title: volume.title.clone(),
+                author: volume.author.clone(),
+                works: contained_works(volume).len(),
+                owned: owned.len(),
+                owned_works,
+            });
+        }
+    }
+    report.collected = collected.len();
+    report
+}
@@ -0,0 +1,114 @@
+use crate::Volume;
+use crate::http::Client;
+use crate::output::record_id;
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::path::{Path, PathBuf};
+
+fn cover_extension(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    match path.rsplit_once('.') {
+        Some((_, ext))
+            if matches!(
+                ext.to_ascii_lowercase().as_str(),
+                "jpg" | "jpeg" | "png" | "gif" | "webp"
+            ) =>
+        {
+            ext
+        }
+        _ => "jpg",
+    }
+}
+
+/// Where `download_covers` keeps a volume's cover in `dir`, with the extension taken
+/// from the image link: `<volume_number>.<ext>` for the numbered collection, as covers
+/// have always been named, and the volume's record ID (`aps-1`,
+/// `loa-poems-of-emily-dickinson`) for other series and unnumbered publications.
+pub fn cover_file(dir: &Path, volume: &Volume) -> PathBuf {
+    let name = if volume.series.is_empty() && volume.volume_number > 0 {
+        format!("{:03}", volume.volume_number)
+    } else {
+        record_id(volume)
+    };
+    dir.join(format!(
+        "{}.{}",
+        name,
+        cover_extension(&volume.cover_image_link)
+    ))
+}
+
+/// Downloads each volume's cover into `dir` as named by [`cover_file`] and records the
+/// local path in `cover_path`. Files that already exist are reused rather than
+/// fetched again, so repeated runs only download new covers.
+pub async fn download_covers(
+    client: &Client,
+    volumes: &mut [Volume],
+    dir: &Path,
+    mut on_volume: impl FnMut(&Volume),
+) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    for volume in volumes.iter_mut() {
+        if volume.cover_image_link.is_empty() {
+            volume.cover_path = Some(String::new());
+            on_volume(volume);
+            continue;
+        }
+
+        let path = cover_file(dir, volume);
+
+        if !path.exists() {
+            // A missing cover shouldn't abort the run; the row just gets an empty path
+            client.check_budget()?;
+            if let Ok(response) = client.get(&volume.cover_image_link).send().await
+                && response.status().is_success()
+            {
+                tokio::fs::write(&path, response.bytes()).await?;
+            }
+            client.check_refused()?;
+        }
+
+        volume.cover_path = Some(if path.exists() {
+            path.to_string_lossy().into_owned()
+        } else {
+            String::new()
+        });
+        on_volume(volume);
+    }
+
+    Ok(())
+}
+
+fn image_type(extension: &str) -> &'static str {
+    match extension.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+/// Replaces each volume's `cover_image_link` with a `data:` URI holding the cover
+/// already downloaded to its `cover_path`, so a page showing it needs nothing from the
+/// network. Nothing is fetched: a cover that isn't on disk is dropped rather than left
+/// pointing at the web.
+pub fn inline_covers(volumes: &mut [Volume]) {
+    for volume in volumes.iter_mut() {
+        let link = &volume.cover_image_link;
+        if link.is_empty() || link.starts_with("data:") {
+            continue;
+        }
+        let content_type = image_type(cover_extension(link));
+
+        let local = volume
+            .cover_path
+            .as_deref()
+            .filter(|path| !path.is_empty())
+            .and_then(|path| std::fs::read(path).ok());
+        volume.cover_image_link = match local {
+            Some(bytes) => format!("data:{};base64,{}", content_type, BASE64.encode(bytes)),
+            None => String::new(),
+        };
+    }
+}
@@ -0,0 +1,334 @@
+use crate::Volume;
+use crate::http::Client;
+use crate::rate::for_each_volume;
+use crate::scrape::absolute_url;
+use crate::wayback::closest_snapshot;
+use anyhow::Result;
+use reqwest::StatusCode;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// `in_print` values; empty when the page didn't say.
+pub const IN_PRINT: &str = "yes";
+pub const NOT_IN_PRINT: &str = "no";
+
+/// Fields that only appear on a volume's own page, not in the collection listing.
+#[derive(Debug, Clone, Default)]
+pub struct VolumeDetails {
+    pub isbn: Option<String>,
+    pub publication_year: Option<String>,
+    pub price_usd: Option<String>,
+    pub member_price_usd: Option<String>,
+    pub availability: Option<String>,
+    /// Whether LOA still sells the volume, when the page says either way.
+    pub in_print: Option<bool>,
+    /// The page's "Buy" link, followed when the price isn't on the detail page itself.
+    pub store_link: Option<String>,
+}
+
+pub(crate) fn is_valid_isbn13(digits: &[u8]) -> bool {
+    if digits.len() != 13 || !(digits.starts_with(b"978") || digits.starts_with(b"979")) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (d - b'0') as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Finds the first checksum-valid ISBN-13 in `text`, allowing hyphens or spaces
+/// between digit groups as printed on the LOA site.
+pub fn find_isbn13(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_digit() || (start > 0 && bytes[start - 1].is_ascii_digit()) {
+            start += 1;
+            continue;
+        }
+
+        let mut digits = Vec::with_capacity(13);
+        let mut i = start;
+        while i < bytes.len() && digits.len() < 13 {
+            match bytes[i] {
+                b'0'..=b'9' => digits.push(bytes[i]),
+                b'-' | b' ' if !digits.is_empty() => {}
+                _ => break,
+            }
+            i += 1;
+        }
+
+        let followed_by_digit = i < bytes.len() && bytes[i].is_ascii_digit();
+        if !followed_by_digit && is_valid_isbn13(&digits) {
+            return Some(String::from_utf8(digits).unwrap_or_default());
+        }
+        start += 1;
+    }
+
+    None
+}
+
+/// Finds the year in a "Published: March 1982" style line, looking a short way past
+/// the label so unrelated years elsewhere on the page aren't picked up.
+pub fn find_publication_year(text: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid in `text`; full lowercasing doesn't
+    let lower = text.to_ascii_lowercase();
+    ["published", "publication date", "pub date"]
+        .iter()
+        .flat_map(|label| lower.match_indices(label).map(|(pos, _)| pos + label.len()))
+        .find_map(|after| {
+            let window: Vec<char> = text[after..].chars().take(40).collect();
+            window.windows(4).enumerate().find_map(|(i, w)| {
+                let before_ok = i == 0 || !window[i - 1].is_ascii_digit();
+                let after_ok = window.get(i + 4).is_none_or(|c| !c.is_ascii_digit());
+                let year: String = w.iter().collect();
+                (before_ok
+                    && after_ok
+                    && w.iter().all(|c| c.is_ascii_digit())
+                    && (year.starts_with("19") || year.starts_with("20")))
+                .then_some(year)
+            })
+        })
+}
+
+/// Finds the dollar amount in a "Member Price: $36.00" style line. Other labels skip
+/// member prices, so "price" finds the list price.
+pub fn find_labeled_price(text: &str, label: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid in `text`; full lowercasing doesn't
+    let lower = text.to_ascii_lowercase();
+    lower.match_indices(label).find_map(|(pos, _)| {
+        if !label.starts_with("member") && lower[..pos].ends_with("member ") {
+            return None;
+        }
+        let window: String = text[pos + label.len()..].chars().take(20).collect();
+        let amount = window.split('$').nth(1)?;
+        let amount: String = amount
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+            .filter(|c| *c != ',')
+            .collect();
+        amount
+            .parse::<f64>()
+            .ok()
+            .map(|price| format!("{:.2}", price))
+    })
+}
+
+/// Turns a schema.org availability (`https://schema.org/InStock`) into `in stock`.
+fn availability_words(value: &str) -> String {
+    let name = value.rsplit('/').next().unwrap_or(value);
+    let mut words = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            words.push(' ');
+        }
+        words.extend(c.to_lowercase());
+    }
+    words
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads price and availability from schema.org Product markup: JSON-LD first, then
+/// microdata.
+fn find_offer(html: &Html) -> (Option<String>, Option<String>) {
+    let (mut price, mut availability) = (None, None);
+
+    if let Ok(selector) = Selector::parse("script[type=\"application/ld+json\"]") {
+        for script in html.select(&selector) {
+            let Ok(json) = serde_json::from_str::<Value>(&script.text().collect::<String>()) else {
+                continue;
+            };
+            let offers = json.get("offers");
+            let offer = match offers {
+                Some(Value::Array(list)) => list.first(),
+                other => other,
+            };
+            if let Some(offer) = offer {
+                price = price.or_else(|| offer.get("price").and_then(json_text));
+                availability = availability.or_else(|| {
+                    offer
+                        .get("availability")
+                        .and_then(json_text)
+                        .map(|a| availability_words(&a))
+                });
+            }
+        }
+    }
+
+    let itemprop = |name: &str| {
+        let selector = Selector::parse(&format!("[itemprop=\"{}\"]", name)).ok()?;
+        let element = html.select(&selector).next()?;
+        let value = element.value();
+        value
+            .attr("content")
+            .or(value.attr("href"))
+            .map(str::to_string)
+            .or_else(|| Some(element.text().collect::<String>().trim().to_string()))
+            .filter(|v| !v.is_empty())
+    };
+    price = price.or_else(|| itemprop("price"));
+    availability =
+        availability.or_else(|| itemprop("availability").map(|a| availability_words(&a)));
+
+    let price = price.and_then(|p| {
+        p.trim_start_matches('$')
+            .parse::<f64>()
+            .ok()
+            .map(|p| format!("{:.2}", p))
+    });
+    (price, availability)
+}
+
+fn find_store_link(html: &Html) -> Option<String> {
+    let selector = Selector::parse("a[href]").ok()?;
+    html.select(&selector).find_map(|link| {
+        let href = link.value().attr("href")?;
+        let text = link.text().collect::<String>().to_lowercase();
+        (href.contains("/store/") || text.contains("buy") || text.contains("add to cart"))
+            .then(|| absolute_url(href))
+    })
+}
+
+/// Reads an out-of-print notice or stock status as whether LOA still sells the volume.
+/// "Out of stock" alone means a reprint is due, so it still counts as in print.
+fn find_in_print(lower_text: &str, availability: Option<&str>, has_price: bool) -> Option<bool> {
+    const OUT_OF_PRINT: &[&str] = &[
+        "out of print",
+        "discontinued",
+        "no longer available",
+        "no longer in print",
+        "no longer sold",
+    ];
+    if availability.is_some_and(|a| OUT_OF_PRINT.iter().any(|p| a.contains(p)))
+        || OUT_OF_PRINT.iter().any(|p| lower_text.contains(p))
+    {
+        return Some(false);
+    }
+    (availability.is_some() || has_price).then_some(true)
+}
+
+pub fn parse_details(html: &Html) -> VolumeDetails {
+    let text = html.root_element().text().collect::<Vec<_>>().join(" ");
+    let (offer_price, availability) = find_offer(html);
+    let lower = text.to_lowercase();
+    let availability = availability.or_else(|| {
+        ["out of stock", "out of print", "in stock", "pre-order"]
+            .into_iter()
+            .find(|phrase| lower.contains(phrase))
+            .map(str::to_string)
+    });
+
+    let price_usd = offer_price
+        .or_else(|| find_labeled_price(&text, "list price"))
+        .or_else(|| find_labeled_price(&text, "price"));
+
+    VolumeDetails {
+        isbn: find_isbn13(&text),
+        publication_year: find_publication_year(&text),
+        in_print: find_in_print(&lower, availability.as_deref(), price_usd.is_some()),
+        price_usd,
+        member_price_usd: find_labeled_price(&text, "member price"),
+        availability,
+        store_link: find_store_link(html),
+    }
+}
+
+async fn fetch_page(client: &Client, url: &str) -> Option<(StatusCode, String)> {
+    let response = client.get(url).send().await.ok()?;
+    let status = response.status();
+    Some((status, response.text()))
+}
+
+/// Fetches each volume's detail page and fills in the fields it provides, calling
+/// `on_volume` as each one completes. Retired pages (404/410) are looked up on the
+/// Wayback Machine, recording the snapshot in `archived_detail_link` and reading the
+/// fields from it instead. Pages that fail to load leave the fields empty.
+pub async fn enrich_details(
+    client: &Client,
+    volumes: &mut [Volume],
+    on_volume: impl FnMut(&Volume),
+) -> Result<()> {
+    for_each_volume(
+        client,
+        volumes,
+        async |volume| {
+            fetch_details(client, volume).await;
+            Ok(())
+        },
+        on_volume,
+    )
+    .await
+}
+
+async fn fetch_details(client: &Client, volume: &mut Volume) {
+    let mut details = VolumeDetails::default();
+    let mut archived_link = String::new();
+    let url = absolute_url(&volume.loa_detail_link);
+
+    if !url.is_empty() {
+        match fetch_page(client, &url).await {
+            Some((status, body)) if status.is_success() => {
+                details = parse_details(&Html::parse_document(&body));
+
+                // Prices may live on a separate store listing
+                if details.price_usd.is_none()
+                    && let Some(store) = details.store_link.clone()
+                    && store != url
+                    && let Some((status, body)) = fetch_page(client, &store).await
+                    && status.is_success()
+                {
+                    let listing = parse_details(&Html::parse_document(&body));
+                    details.price_usd = listing.price_usd;
+                    details.member_price_usd =
+                        details.member_price_usd.or(listing.member_price_usd);
+                    details.availability = listing.availability.or(details.availability);
+                    details.in_print = match (details.in_print, listing.in_print) {
+                        (Some(false), _) | (_, Some(false)) => Some(false),
+                        (here, there) => here.or(there),
+                    };
+                }
+            }
+            Some((StatusCode::NOT_FOUND | StatusCode::GONE, _)) => {
+                if let Some(snapshot) = closest_snapshot(client, &url).await {
+                    if let Some((status, body)) = fetch_page(client, &snapshot).await
+                        && status.is_success()
+                    {
+                        // An old snapshot's price and stock say nothing about today
+                        details = VolumeDetails {
+                            price_usd: None,
+                            member_price_usd: None,
+                            availability: None,
+                            ..parse_details(&Html::parse_document(&body))
+                        };
+                    }
+                    archived_link = snapshot;
+                }
+                // A retired page means LOA no longer sells the volume directly
+                details.in_print = Some(false);
+            }
+            _ => {}
+        }
+    }
+
+    volume.isbn = Some(details.isbn.unwrap_or_default());
+    volume.publication_year = Some(details.publication_year.unwrap_or_default());
+    volume.archived_detail_link = Some(archived_link);
+    volume.price_usd = Some(details.price_usd.unwrap_or_default());
+    volume.member_price_usd = Some(details.member_price_usd.unwrap_or_default());
+    volume.availability = Some(details.availability.unwrap_or_default());
+    volume.in_print = Some(match details.in_print {
+        Some(true) => IN_PRINT.to_string(),
+        Some(false) => NOT_IN_PRINT.to_string(),
+        None => String::new(),
+    });
+}
@@ -0,0 +1,195 @@
+use crate::Volume;
+use crate::scrape::{SERIES_APS, STATUS_DELISTED, STATUS_FORTHCOMING};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where `digest --since last-run` remembers when it last ran.
+pub fn default_digest_state_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("digest.json")
+}
+
+/// When the last digest was written, so the next one picks up where it left off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DigestState {
+    pub last_run: Option<String>,
+}
+
+impl DigestState {
+    /// Loads the state file, treating a missing file as never having run.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read digest state {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse digest state {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write digest state {}", path.display()))
+    }
+}
+
+/// Catalog changes worth announcing, from the `sync` store's first-seen and delisted
+/// timestamps.
+#[derive(Debug, Clone, Default)]
+pub struct Digest {
+    /// Start of the period covered (an RFC 3339 timestamp or date), or `None` for all
+    /// time.
+    pub since: Option<String>,
+    pub added: Vec<Volume>,
+    pub forthcoming: Vec<Volume>,
+    pub delisted: Vec<Volume>,
+}
+
+impl Digest {
+    pub fn from_volumes(volumes: &[Volume], since: Option<&str>) -> Self {
+        // Timestamps are all RFC 3339 in UTC, so they compare as strings; a bare date
+        // sorts before every time on that day
+        let after = |timestamp: Option<&str>| {
+            timestamp.is_some_and(|t| !t.is_empty() && since.is_none_or(|since| t > since))
+        };
+        Digest {
+            since: since.map(str::to_string),
+            ..Self::matching(volumes, after)
+        }
+    }
+
+    /// The changes made by the one `sync` that stamped volumes with `seen_at`.
+    pub fn from_sync(volumes: &[Volume], seen_at: &str) -> Self {
+        Self::matching(volumes, |timestamp| timestamp == Some(seen_at))
+    }
+
+    fn matching(volumes: &[Volume], included: impl Fn(Option<&str>) -> bool) -> Self {
+        let mut digest = Digest::default();
+        for volume in volumes {
+            if status(volume) == STATUS_DELISTED {
+                if included(volume.delisted_on.as_deref()) {
+                    digest.delisted.push(volume.clone());
+                }
+            } else if included(volume.first_seen.as_deref()) {
+                if status(volume) == STATUS_FORTHCOMING {
+                    digest.forthcoming.push(volume.clone());
+                } else {
+                    digest.added.push(volume.clone());
+                }
+            }
+        }
+        digest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.forthcoming.is_empty() && self.delisted.is_empty()
+    }
+
+    fn sections(&self) -> [(&'static str, &[Volume]); 3] {
+        [
+            ("New in the collection", &self.added),
+            ("Coming soon", &self.forthcoming),
+            ("No longer listed", &self.delisted),
+        ]
+    }
+
+    fn heading(&self) -> String {
+        match &self.since {
+            Some(since) => format!(
+                "Library of America: what's new since {}",
+                since.get(..10).unwrap_or(since)
+            ),
+            None => "Library of America: what's new".to_string(),
+        }
+    }
+}
+
+fn status(volume: &Volume) -> &str {
+    volume.status.as_deref().unwrap_or_default()
+}
+
+/// "#342", "APS #3", or nothing for unnumbered publications.
+pub(crate) fn number_label(volume: &Volume) -> Option<String> {
+    match (volume.volume_number, volume.series.as_str()) {
+        (0, _) => None,
+        (n, SERIES_APS) => Some(format!("APS #{}", n)),
+        (n, _) => Some(format!("#{}", n)),
+    }
+}
+
+pub(crate) fn release(volume: &Volume) -> Option<&str> {
+    volume.release_date.as_deref().filter(|d| !d.is_empty())
+}
+
+pub fn write_markdown(digest: &Digest, out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "## {}", digest.heading())?;
+    if digest.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "No catalog changes.")?;
+        return Ok(());
+    }
+
+    for (title, volumes) in digest.sections() {
+        if volumes.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(out, "### {}", title)?;
+        writeln!(out)?;
+        for volume in volumes {
+            let mut line = String::from("-");
+            if let Some(number) = number_label(volume) {
+                line.push_str(&format!(" **{}**", number));
+            }
+            line.push_str(&format!(" *{}*", volume.title));
+            if !volume.author.is_empty() {
+                line.push_str(&format!(" by {}", volume.author));
+            }
+            if let Some(date) = release(volume) {
+                line.push_str(&format!(" (expected {})", date));
+            }
+            writeln!(out, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_text(digest: &Digest, out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "{}", digest.heading())?;
+    if digest.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "No catalog changes.")?;
+        return Ok(());
+    }
+
+    for (title, volumes) in digest.sections() {
+        if volumes.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}:", title)?;
+        for volume in volumes {
+            let mut line = String::from("  ");
+            if let Some(number) = number_label(volume) {
+                line.push_str(&format!("{} ", number));
+            }
+            line.push_str(&volume.title);
+            if !volume.author.is_empty() {
+                line.push_str(&format!(" by {}", volume.author));
+            }
+            if let Some(date) = release(volume) {
+                line.push_str(&format!(" (expected {})", date));
+            }
+            writeln!(out, "{}", line)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::Volume;
+use crate::volume::{UPSERT_KEY, upsert_rows};
+use ::duckdb::types::Value;
+use ::duckdb::{Connection, params_from_iter};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+fn to_sql_value(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::BigInt(i),
+            None => Value::Double(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn sql_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Bool(_) => "BOOLEAN",
+        serde_json::Value::Number(n) if n.is_i64() => "BIGINT",
+        serde_json::Value::Number(_) => "DOUBLE",
+        _ => "VARCHAR",
+    }
+}
+
+/// Writes volumes to the `volumes` table of a DuckDB file, creating it if needed and
+/// otherwise upserting on `row_key` like `--format sqlite`. Columns keep their types
+/// (`volume_number` is a BIGINT), and columns newer versions of `Volume` add are added
+/// to an existing table.
+pub fn write_duckdb(volumes: &[Volume], path: &str) -> Result<()> {
+    let mut conn = Connection::open(path)
+        .with_context(|| format!("Failed to open DuckDB database {}", path))?;
+    let (rows, sample) = upsert_rows(volumes)?;
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS volumes (\"{}\" VARCHAR PRIMARY KEY, volume_number BIGINT)",
+        UPSERT_KEY
+    ))?;
+    let existing: HashSet<String> = tx
+        .prepare("SELECT column_name FROM information_schema.columns WHERE table_name = 'volumes'")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for (column, value) in &sample {
+        if !existing.contains(column) {
+            tx.execute_batch(&format!(
+                "ALTER TABLE volumes ADD COLUMN \"{}\" {}",
+                column,
+                sql_type(value)
+            ))?;
+        }
+    }
+
+    for row in &rows {
+        let columns: Vec<&String> = row.keys().collect();
+        let sql = format!(
+            "INSERT INTO volumes ({}) VALUES ({}) ON CONFLICT (\"{}\") DO UPDATE SET {}",
+            columns
+                .iter()
+                .map(|c| format!("\"{}\"", c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", "),
+            UPSERT_KEY,
+            columns
+                .iter()
+                .filter(|c| c.as_str() != UPSERT_KEY)
+                .map(|c| format!("\"{0}\" = excluded.\"{0}\"", c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        tx.prepare_cached(&sql)?
+            .execute(params_from_iter(row.values().map(to_sql_value)))?;
+    }
+    tx.commit()?;
+    Ok(())
+}
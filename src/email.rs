@@ -0,0 +1,87 @@
+use anyhow::{Context, Result, anyhow};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// How the connection to the SMTP server is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Security {
+    /// Plain connection upgraded with STARTTLS, usually on port 587
+    StartTls,
+    /// TLS from the start, usually on port 465
+    Tls,
+    /// No encryption, for a relay on the same machine
+    None,
+}
+
+impl Security {
+    pub fn default_port(self) -> u16 {
+        match self {
+            Security::StartTls => 587,
+            Security::Tls => 465,
+            Security::None => 25,
+        }
+    }
+}
+
+/// An SMTP server and the addresses a report is mailed between.
+#[derive(Debug, Clone)]
+pub struct Mailer {
+    pub host: String,
+    pub port: u16,
+    pub security: Security,
+    /// Username and password, for servers that require signing in
+    pub credentials: Option<(String, String)>,
+    pub from: String,
+    pub to: String,
+}
+
+impl Mailer {
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let builder = match self.security {
+            Security::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+            Security::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            Security::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        };
+        let builder = builder.port(self.port);
+        Ok(match &self.credentials {
+            Some((username, password)) => builder
+                .credentials(Credentials::new(username.clone(), password.clone()))
+                .build(),
+            None => builder.build(),
+        })
+    }
+
+    /// Sends a plain-text message with a CSV file attached.
+    pub async fn send(
+        &self,
+        subject: &str,
+        body: &str,
+        filename: &str,
+        csv: Vec<u8>,
+    ) -> Result<()> {
+        let mailbox = |address: &str| -> Result<Mailbox> {
+            address
+                .parse()
+                .map_err(|e| anyhow!("Bad email address {}: {}", address, e))
+        };
+        let message = Message::builder()
+            .from(mailbox(&self.from)?)
+            .to(mailbox(&self.to)?)
+            .subject(subject)
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(body.to_string()))
+                    .singlepart(
+                        Attachment::new(filename.to_string())
+                            .body(csv, ContentType::parse("text/csv")?),
+                    ),
+            )?;
+        self.transport()?
+            .send(message)
+            .await
+            .with_context(|| format!("Failed to send the report through {}", self.host))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use super::USER_AGENT;
+use crate::Volume;
+use crate::constants::PUBLISHER;
+use crate::http::Client;
+use serde_json::Value;
+
+const SEARCH_URL: &str = "https://www.loc.gov/books/";
+const PERMALINK_BASE: &str = "https://lccn.loc.gov";
+
+/// Returns the LCCN of the first catalog record matching the free-text `query`.
+async fn first_lccn(client: &Client, query: &str) -> Option<String> {
+    let response = client
+        .get(SEARCH_URL)
+        .query(&[("q", query), ("fo", "json"), ("c", "1")])
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let results: Value = response.json().ok()?;
+    results
+        .pointer("/results/0/number_lccn/0")
+        .and_then(Value::as_str)
+        .map(|lccn| lccn.replace(' ', ""))
+        .filter(|lccn| !lccn.is_empty())
+}
+
+/// Looks the volume up by ISBN when `--details` found one, otherwise by title and
+/// author within LOA's publications.
+pub(super) async fn enrich_volume(client: &Client, volume: &mut Volume) {
+    let isbn = volume.isbn.as_deref().unwrap_or("");
+    let mut lccn = None;
+    if !isbn.is_empty() {
+        lccn = first_lccn(client, isbn).await;
+    }
+    if lccn.is_none() {
+        let query = format!("{} {} {}", volume.title, volume.author, PUBLISHER);
+        lccn = first_lccn(client, query.trim()).await;
+    }
+
+    let lccn = lccn.unwrap_or_default();
+    volume.loc_permalink = Some(if lccn.is_empty() {
+        String::new()
+    } else {
+        format!("{}/{}", PERMALINK_BASE, lccn)
+    });
+    volume.lccn = Some(lccn);
+}
@@ -0,0 +1,68 @@
+//! Optional per-volume lookups against external catalogs, selected with `--enrich`.
+
+use crate::Volume;
+use crate::http::Client;
+use crate::rate::for_each_volume;
+use anyhow::Result;
+use clap::ValueEnum;
+
+mod loc;
+mod openlibrary;
+mod worldcat;
+
+pub(crate) const USER_AGENT: &str = "LOA-Scraper/1.0 (https://github.com/example/loa-scraper)";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Enrichment {
+    /// OpenLibrary work and edition IDs and a cover URL
+    Openlibrary,
+    /// Library of Congress control number and catalog permalink
+    Loc,
+    /// Average rating and ratings count from OpenLibrary
+    Ratings,
+    /// OCLC number from the WorldCat Search API (needs a WSKey)
+    Worldcat,
+}
+
+/// Credentials for enrichments whose APIs require them.
+#[derive(Debug, Clone, Default)]
+pub struct EnrichOptions {
+    /// OCLC WSKey client ID and secret, for `worldcat`.
+    pub worldcat_key: Option<String>,
+    pub worldcat_secret: Option<String>,
+}
+
+/// Runs one enrichment over every volume, calling `on_volume` as each completes.
+/// Lookups that fail leave that volume's fields empty.
+pub async fn enrich(
+    client: &Client,
+    enrichment: Enrichment,
+    options: &EnrichOptions,
+    volumes: &mut [Volume],
+    on_volume: impl FnMut(&Volume),
+) -> Result<()> {
+    let worldcat = match enrichment {
+        Enrichment::Worldcat => Some(worldcat::Session::start(client, options).await?),
+        Enrichment::Loc | Enrichment::Openlibrary | Enrichment::Ratings => None,
+    };
+
+    for_each_volume(
+        client,
+        volumes,
+        async |volume| {
+            match enrichment {
+                Enrichment::Loc => loc::enrich_volume(client, volume).await,
+                Enrichment::Openlibrary => openlibrary::enrich_volume(client, volume).await,
+                Enrichment::Ratings => openlibrary::enrich_ratings(client, volume).await,
+                Enrichment::Worldcat => {
+                    if let Some(session) = &worldcat {
+                        session.enrich_volume(client, volume).await;
+                    }
+                }
+            }
+            Ok(())
+        },
+        on_volume,
+    )
+    .await
+}
@@ -0,0 +1,135 @@
+use super::USER_AGENT;
+use crate::Volume;
+use crate::constants::PUBLISHER;
+use crate::http::Client;
+use serde_json::Value;
+
+const BASE_URL: &str = "https://openlibrary.org";
+
+#[derive(Debug, Default)]
+struct OpenLibraryIds {
+    work: String,
+    edition: String,
+    cover_url: String,
+}
+
+async fn get_json(client: &Client, url: &str, query: &[(&str, &str)]) -> Option<Value> {
+    let response = client
+        .get(url)
+        .query(query)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().ok()
+}
+
+/// Strips the `/works/` or `/books/` prefix from an OpenLibrary key.
+fn key_id(key: &str) -> String {
+    key.rsplit('/').next().unwrap_or("").to_string()
+}
+
+fn cover_url(cover_id: Option<i64>) -> String {
+    cover_id
+        .filter(|id| *id > 0)
+        .map(|id| format!("https://covers.openlibrary.org/b/id/{}-L.jpg", id))
+        .unwrap_or_default()
+}
+
+async fn by_isbn(client: &Client, isbn: &str) -> Option<OpenLibraryIds> {
+    let edition = get_json(client, &format!("{}/isbn/{}.json", BASE_URL, isbn), &[]).await?;
+    Some(OpenLibraryIds {
+        work: edition
+            .pointer("/works/0/key")
+            .and_then(Value::as_str)
+            .map(key_id)
+            .unwrap_or_default(),
+        edition: edition
+            .get("key")
+            .and_then(Value::as_str)
+            .map(key_id)
+            .unwrap_or_default(),
+        cover_url: cover_url(edition.pointer("/covers/0").and_then(Value::as_i64)),
+    })
+}
+
+async fn by_title(client: &Client, title: &str, author: &str) -> Option<OpenLibraryIds> {
+    let mut query = vec![("title", title), ("publisher", PUBLISHER), ("limit", "1")];
+    if !author.is_empty() {
+        query.push(("author", author));
+    }
+    let results = get_json(client, &format!("{}/search.json", BASE_URL), &query).await?;
+    let doc = results.pointer("/docs/0")?;
+
+    Some(OpenLibraryIds {
+        work: doc
+            .get("key")
+            .and_then(Value::as_str)
+            .map(key_id)
+            .unwrap_or_default(),
+        edition: doc
+            .get("cover_edition_key")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+        cover_url: cover_url(doc.get("cover_i").and_then(Value::as_i64)),
+    })
+}
+
+/// Looks the volume up by ISBN when `--details` found one, otherwise by title and
+/// author within LOA's publications.
+async fn lookup(client: &Client, volume: &Volume) -> Option<OpenLibraryIds> {
+    let isbn = volume.isbn.as_deref().unwrap_or("");
+    if !isbn.is_empty()
+        && let Some(ids) = by_isbn(client, isbn).await
+    {
+        return Some(ids);
+    }
+    by_title(client, &volume.title, &volume.author).await
+}
+
+pub(super) async fn enrich_volume(client: &Client, volume: &mut Volume) {
+    let ids = lookup(client, volume).await.unwrap_or_default();
+    volume.openlibrary_work_id = Some(ids.work);
+    volume.openlibrary_edition_id = Some(ids.edition);
+    volume.openlibrary_cover_url = Some(ids.cover_url);
+}
+
+/// Fills in the work's average rating and ratings count, reusing the work ID from
+/// `--enrich openlibrary` when that ran first.
+pub(super) async fn enrich_ratings(client: &Client, volume: &mut Volume) {
+    let mut work = volume.openlibrary_work_id.clone().unwrap_or_default();
+    if work.is_empty() {
+        work = lookup(client, volume).await.unwrap_or_default().work;
+    }
+
+    let mut average = String::new();
+    let mut count = String::new();
+    if !work.is_empty()
+        && let Some(ratings) = get_json(
+            client,
+            &format!("{}/works/{}/ratings.json", BASE_URL, work),
+            &[],
+        )
+        .await
+    {
+        let count_value = ratings
+            .pointer("/summary/count")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if count_value > 0 {
+            count = count_value.to_string();
+            average = ratings
+                .pointer("/summary/average")
+                .and_then(Value::as_f64)
+                .map(|avg| format!("{:.2}", avg))
+                .unwrap_or_default();
+        }
+    }
+
+    volume.rating_average = Some(average);
+    volume.ratings_count = Some(count);
+}
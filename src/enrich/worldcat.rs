@@ -0,0 +1,90 @@
+use super::{EnrichOptions, USER_AGENT};
+use crate::Volume;
+use crate::constants::PUBLISHER;
+use crate::http::Client;
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::Method;
+use serde_json::Value;
+
+const TOKEN_URL: &str = "https://oauth.oclc.org/token";
+const SEARCH_URL: &str = "https://americas.discovery.api.oclc.org/worldcat/search/v2/bibs";
+
+/// An OAuth access token for the WorldCat Search API, obtained once per run from the
+/// WSKey and secret.
+pub(super) struct Session {
+    token: String,
+}
+
+impl Session {
+    pub(super) async fn start(client: &Client, options: &EnrichOptions) -> Result<Self> {
+        let (Some(key), Some(secret)) = (&options.worldcat_key, &options.worldcat_secret) else {
+            bail!("--enrich worldcat needs a WSKey: pass --worldcat-key and --worldcat-secret");
+        };
+
+        let response = client
+            .request(Method::POST, TOKEN_URL)
+            .query(&[("grant_type", "client_credentials"), ("scope", "wcapi")])
+            .basic_auth(key, secret)
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("WorldCat rejected the WSKey ({})", response.status());
+        }
+        let body: Value = response.json().context("Bad WorldCat token response")?;
+        let token = body
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("WorldCat token response has no access_token"))?;
+
+        Ok(Session {
+            token: token.to_string(),
+        })
+    }
+
+    /// Returns the OCLC number of the first record matching the CQL-style `query`.
+    async fn first_oclc_number(&self, client: &Client, query: &str) -> Option<String> {
+        let response = client
+            .get(SEARCH_URL)
+            .query(&[("q", query), ("limit", "1")])
+            .bearer_auth(&self.token)
+            .header("Accept", "application/json")
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let results: Value = response.json().ok()?;
+        results
+            .pointer("/bibRecords/0/identifier/oclcNumber")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    /// Looks the volume up by ISBN when `--details` found one, otherwise by title and
+    /// author within LOA's publications.
+    pub(super) async fn enrich_volume(&self, client: &Client, volume: &mut Volume) {
+        let isbn = volume.isbn.as_deref().unwrap_or("");
+        let mut oclc = None;
+        if !isbn.is_empty() {
+            oclc = self
+                .first_oclc_number(client, &format!("bn:{}", isbn))
+                .await;
+        }
+        if oclc.is_none() {
+            let mut query = format!(
+                "ti:\"{}\" AND pb:\"{}\"",
+                volume.title.replace('"', ""),
+                PUBLISHER
+            );
+            if !volume.author.is_empty() {
+                query.push_str(&format!(" AND au:\"{}\"", volume.author.replace('"', "")));
+            }
+            oclc = self.first_oclc_number(client, &query).await;
+        }
+
+        volume.oclc_number = Some(oclc.unwrap_or_default());
+    }
+}
@@ -0,0 +1,66 @@
+//! C ABI for embedding the scraper in non-Rust applications. See
+//! `include/loa_scraper.h` for the matching declarations.
+
+use crate::http::Client;
+use crate::scrape::{ScrapeOptions, scrape};
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::ptr;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn scrape_json(start: u32, end: u32) -> anyhow::Result<String> {
+    let options = ScrapeOptions {
+        start: start.max(1),
+        end: (end > 0).then_some(end),
+        ..Default::default()
+    };
+    let runtime = tokio::runtime::Runtime::new()?;
+    let volumes = runtime.block_on(scrape(&Client::new(), &options))?;
+    Ok(serde_json::to_string(&volumes)?)
+}
+
+/// Scrapes volumes `start..=end` (`end == 0` means no upper bound) and returns them
+/// as a JSON array. Returns NULL on failure; see `loa_last_error`. The result must
+/// be released with `loa_free_string`.
+#[unsafe(no_mangle)]
+pub extern "C" fn loa_scrape_to_json(start: u32, end: u32) -> *mut c_char {
+    match scrape_json(start, end).and_then(|json| Ok(CString::new(json)?)) {
+        Ok(json) => json.into_raw(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a copy of the last error message on this thread, or NULL if there was
+/// none. The result must be released with `loa_free_string`.
+#[unsafe(no_mangle)]
+pub extern "C" fn loa_last_error() -> *mut c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(ptr::null_mut(), |message| message.clone().into_raw())
+    })
+}
+
+/// Releases a string returned by this library. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer previously returned by this library that has not
+/// already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn loa_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
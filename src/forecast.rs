@@ -0,0 +1,273 @@
+//! Speculation about future volumes: what's announced, how fast LOA publishes, and
+//! which authors and periods past patterns suggest come next. Everything but the
+//! announced volumes is a heuristic guess.
+
+use crate::Volume;
+use crate::digest::release;
+use crate::gaps::GapReport;
+use crate::output::author_identity;
+use crate::scrape::{STATUS_DELISTED, STATUS_FORTHCOMING};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+/// Complete years the publishing pace is averaged over.
+pub const CADENCE_YEARS: i32 = 5;
+
+/// How recent (in years) an author's last volume or a period's additions must be to
+/// count as active.
+const RECENT_YEARS: i32 = 10;
+
+/// Years of an author's writing after the last one their volumes cover that suggest
+/// another volume could follow.
+const UNCOVERED_YEARS: i32 = 5;
+
+/// How many uncollected notable works count towards an author's score.
+const MAX_GAP_SCORE: usize = 3;
+
+/// Works named in a gap reason before the rest are summed up.
+const GAP_WORKS_SHOWN: usize = 3;
+
+pub const DISCLAIMER: &str =
+    "Heuristic guesses from past publishing patterns, not announcements from LOA.";
+
+/// A volume LOA has announced but not yet published.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Announced {
+    pub volume: String,
+    pub title: String,
+    pub author: String,
+    pub release_date: Option<String>,
+}
+
+/// Volumes published per year, from the volumes whose publication year is known.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cadence {
+    pub per_year: BTreeMap<i32, usize>,
+    /// Average over the last complete years.
+    pub average: f64,
+}
+
+/// An author a future volume might be devoted to, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthorForecast {
+    pub author: String,
+    pub volumes: usize,
+    pub score: usize,
+    pub reasons: Vec<String>,
+}
+
+/// A decade of writing, by how many volumes cover it and how many of those are recent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodForecast {
+    pub decade: i32,
+    pub volumes: usize,
+    pub recent: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Forecast {
+    pub note: &'static str,
+    pub announced: Vec<Announced>,
+    pub cadence: Option<Cadence>,
+    pub authors: Vec<AuthorForecast>,
+    pub periods: Vec<PeriodForecast>,
+}
+
+fn year_of(text: Option<&str>) -> Option<i32> {
+    text?.get(..4)?.parse().ok()
+}
+
+fn status(volume: &Volume) -> &str {
+    volume.status.as_deref().unwrap_or_default()
+}
+
+fn cadence(volumes: &[Volume], this_year: i32) -> Option<Cadence> {
+    let mut per_year = BTreeMap::new();
+    for year in volumes
+        .iter()
+        .filter_map(|v| year_of(v.publication_year.as_deref()))
+    {
+        *per_year.entry(year).or_default() += 1;
+    }
+    if per_year.is_empty() {
+        return None;
+    }
+    let recent: usize = per_year
+        .range(this_year - CADENCE_YEARS..this_year)
+        .map(|(_, count)| count)
+        .sum();
+    Some(Cadence {
+        per_year,
+        average: recent as f64 / CADENCE_YEARS as f64,
+    })
+}
+
+fn author_forecasts(volumes: &[Volume], this_year: i32) -> Vec<AuthorForecast> {
+    let mut by_author: HashMap<String, Vec<&Volume>> = HashMap::new();
+    for volume in volumes.iter().filter(|v| !v.author.is_empty()) {
+        by_author
+            .entry(author_identity(volume))
+            .or_default()
+            .push(volume);
+    }
+
+    let mut forecasts: Vec<AuthorForecast> = by_author
+        .into_values()
+        .map(|volumes| {
+            let mut forecast = AuthorForecast {
+                author: volumes[0].author.clone(),
+                volumes: volumes.len(),
+                score: 0,
+                reasons: Vec::new(),
+            };
+
+            let covered = volumes
+                .iter()
+                .filter_map(|v| v.title_period())
+                .map(|p| p.1)
+                .max();
+            let died = volumes
+                .iter()
+                .find_map(|v| year_of(v.author_death_date.as_deref()));
+            // Without a death date, only an author known to be alive is still writing
+            let living = died.is_none()
+                && volumes
+                    .iter()
+                    .any(|v| year_of(v.author_birth_date.as_deref()).is_some());
+            if let Some(covered) = covered
+                && (died.is_some() || living)
+                && died.unwrap_or(this_year) - covered >= UNCOVERED_YEARS
+            {
+                forecast.score += 2;
+                forecast.reasons.push(match died {
+                    Some(died) => format!(
+                        "Volumes so far cover up to {}; they wrote until {}",
+                        covered, died
+                    ),
+                    None => format!(
+                        "Volumes so far cover up to {}; they're still writing",
+                        covered
+                    ),
+                });
+            }
+
+            let latest = volumes
+                .iter()
+                .filter_map(|v| year_of(v.publication_year.as_deref()))
+                .max();
+            if let Some(latest) = latest
+                && this_year - latest <= RECENT_YEARS
+            {
+                forecast.score += 1;
+                forecast
+                    .reasons
+                    .push(format!("LOA last added to them in {}", latest));
+            }
+
+            if volumes.iter().any(|v| status(v) == STATUS_FORTHCOMING) {
+                forecast.score += 1;
+                forecast
+                    .reasons
+                    .push("Already has a volume announced".to_string());
+            }
+
+            if volumes.len() >= 3 {
+                forecast.score += 1;
+                forecast
+                    .reasons
+                    .push(format!("LOA has given them {} volumes", volumes.len()));
+            }
+            forecast
+        })
+        .filter(|f| f.score > 0)
+        .collect();
+    sort_authors(&mut forecasts);
+    forecasts
+}
+
+fn sort_authors(forecasts: &mut [AuthorForecast]) {
+    forecasts.sort_by(|a, b| (Reverse(a.score), &a.author).cmp(&(Reverse(b.score), &b.author)));
+}
+
+fn period_forecasts(volumes: &[Volume], this_year: i32) -> Vec<PeriodForecast> {
+    let mut decades: BTreeMap<i32, PeriodForecast> = BTreeMap::new();
+    for volume in volumes {
+        let Some((first, last)) = volume.period() else {
+            continue;
+        };
+        let middle = (first + last) / 2;
+        let decade = middle - middle.rem_euclid(10);
+        let entry = decades.entry(decade).or_insert(PeriodForecast {
+            decade,
+            volumes: 0,
+            recent: 0,
+        });
+        entry.volumes += 1;
+        if year_of(volume.publication_year.as_deref())
+            .is_some_and(|year| this_year - year <= RECENT_YEARS)
+            || status(volume) == STATUS_FORTHCOMING
+        {
+            entry.recent += 1;
+        }
+    }
+    let mut periods: Vec<PeriodForecast> = decades.into_values().filter(|p| p.recent > 0).collect();
+    periods.sort_by_key(|p| (Reverse(p.recent), p.decade));
+    periods
+}
+
+impl Forecast {
+    /// The forecast from the catalog alone, as of `this_year`. Delisted volumes are
+    /// left out; forthcoming ones are both announced and a sign of momentum.
+    pub fn from_volumes(volumes: &[Volume], this_year: i32) -> Self {
+        let listed: Vec<Volume> = volumes
+            .iter()
+            .filter(|v| status(v) != STATUS_DELISTED)
+            .cloned()
+            .collect();
+        Forecast {
+            note: DISCLAIMER,
+            announced: listed
+                .iter()
+                .filter(|v| status(v) == STATUS_FORTHCOMING)
+                .map(|v| Announced {
+                    volume: v.key(),
+                    title: v.title.clone(),
+                    author: v.author.clone(),
+                    release_date: release(v).map(str::to_string),
+                })
+                .collect(),
+            cadence: cadence(&listed, this_year),
+            authors: author_forecasts(&listed, this_year),
+            periods: period_forecasts(&listed, this_year),
+        }
+    }
+
+    /// Folds an author's gap analysis into their score: notable works no volume
+    /// collects yet are material for another.
+    pub fn add_gaps(&mut self, report: &GapReport) {
+        let gaps: Vec<&str> = report.gaps().map(|w| w.title.as_str()).collect();
+        if gaps.is_empty() {
+            return;
+        }
+        let Some(forecast) = self.authors.iter_mut().find(|f| f.author == report.author) else {
+            return;
+        };
+        forecast.score += gaps.len().min(MAX_GAP_SCORE);
+        let mut named = gaps
+            .iter()
+            .take(GAP_WORKS_SHOWN)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if gaps.len() > GAP_WORKS_SHOWN {
+            named.push_str(&format!(" and {} more", gaps.len() - GAP_WORKS_SHOWN));
+        }
+        forecast.reasons.push(format!(
+            "{} notable works not yet collected: {}",
+            gaps.len(),
+            named
+        ));
+        sort_authors(&mut self.authors);
+    }
+}
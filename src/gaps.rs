@@ -0,0 +1,79 @@
+//! Which of an author's notable works LOA has collected, and which it hasn't yet.
+
+use crate::Volume;
+use crate::coverage::{contained_works, work_key};
+use crate::volume::normalize_title;
+use crate::wikidata::AuthorWorks;
+use serde::Serialize;
+
+/// A notable work and the LOA volumes that collect it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkCoverage {
+    pub title: String,
+    pub year: Option<i32>,
+    pub qid: String,
+    /// Keys of the volumes whose title lists the work; empty for a gap.
+    pub volumes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GapReport {
+    pub author: String,
+    pub qid: String,
+    /// Keys of the author's volumes the works were looked for in.
+    pub author_volumes: Vec<String>,
+    pub works: Vec<WorkCoverage>,
+}
+
+impl GapReport {
+    pub fn collected(&self) -> usize {
+        self.works.iter().filter(|w| !w.volumes.is_empty()).count()
+    }
+
+    pub fn gaps(&self) -> impl Iterator<Item = &WorkCoverage> {
+        self.works.iter().filter(|w| w.volumes.is_empty())
+    }
+}
+
+/// Whether a volume is by the author: the Wikidata ID when enrichment recorded one,
+/// otherwise the name (which also catches volumes shared with other authors).
+fn by_author(volume: &Volume, author: &str, qid: &str) -> bool {
+    match volume.author_wikidata_id.as_deref() {
+        Some(id) if !id.is_empty() => id == qid,
+        _ => normalize_title(&volume.author).contains(&normalize_title(author)),
+    }
+}
+
+/// Matches the author's notable works against the works their volumes' titles list.
+/// Volumes titled by genre and period ("Novels 1930–1942") list no works, so a work
+/// they collect still shows as a gap.
+pub fn gaps(volumes: &[Volume], author: &str, notable: &AuthorWorks) -> GapReport {
+    let author_volumes: Vec<&Volume> = volumes
+        .iter()
+        .filter(|v| by_author(v, author, &notable.qid))
+        .collect();
+    let works = notable
+        .works
+        .iter()
+        .map(|work| {
+            let key = work_key(&work.title);
+            WorkCoverage {
+                title: work.title.clone(),
+                year: work.year,
+                qid: work.qid.clone(),
+                volumes: author_volumes
+                    .iter()
+                    .filter(|v| contained_works(v).contains(&key))
+                    .map(|v| v.key())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    GapReport {
+        author: author.to_string(),
+        qid: notable.qid.clone(),
+        author_volumes: author_volumes.iter().map(|v| v.key()).collect(),
+        works,
+    }
+}
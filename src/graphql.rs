@@ -0,0 +1,365 @@
+//! A GraphQL schema over the volumes, served by `serve` at `/graphql`, so frontends
+//! can ask for exactly the fields they need and follow author → volumes links.
+
+use crate::api::Filter;
+use crate::output::author_identity;
+use crate::ownership::OWNED;
+use crate::scrape::absolute_url;
+use crate::volume::{Author, Work};
+use crate::{Volume, api};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The volumes a query runs against: a snapshot taken when the request came in, so a
+/// refresh halfway through doesn't mix old and new data.
+type Snapshot = Arc<Vec<Volume>>;
+
+pub type LoaSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+pub fn schema() -> LoaSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription).finish()
+}
+
+/// Runs one GraphQL request against `volumes`.
+pub async fn execute(
+    schema: &LoaSchema,
+    request: async_graphql::Request,
+    volumes: Vec<Volume>,
+) -> async_graphql::Response {
+    let snapshot: Snapshot = Arc::new(volumes);
+    schema.execute(request.data(snapshot)).await
+}
+
+fn snapshot<'a>(ctx: &Context<'a>) -> &'a Snapshot {
+    ctx.data_unchecked::<Snapshot>()
+}
+
+fn page<T>(items: impl Iterator<Item = T>, limit: Option<usize>, offset: Option<usize>) -> Vec<T> {
+    items
+        .skip(offset.unwrap_or_default())
+        .take(limit.unwrap_or(api::DEFAULT_LIMIT).min(api::MAX_LIMIT))
+        .collect()
+}
+
+/// Each author once, in order of their first volume, keyed as the author index does.
+fn authors(volumes: &[Volume]) -> Vec<AuthorNode> {
+    let mut seen = HashSet::new();
+    volumes
+        .iter()
+        .filter(|v| seen.insert(author_identity(v)))
+        .filter_map(|v| {
+            Author::from_volume(v).map(|author| AuthorNode {
+                identity: author_identity(v),
+                author,
+            })
+        })
+        .collect()
+}
+
+/// The `/volumes` query parameters, as a GraphQL input.
+#[derive(Debug, Default, InputObject)]
+pub struct VolumeFilter {
+    /// Words that must all appear in the author or title.
+    pub q: Option<String>,
+    /// Part of the author's name, ignoring case.
+    pub author: Option<String>,
+    /// `loa` for the main series, or another series' name.
+    pub series: Option<String>,
+    /// `published`, `forthcoming`, or `delisted`.
+    pub status: Option<String>,
+    pub owned: Option<bool>,
+    pub year: Option<String>,
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Volumes matching every filter given, as `/volumes` filters them.
+    async fn volumes(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: VolumeFilter,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<VolumeNode> {
+        let filter = Filter {
+            q: filter.q,
+            author: filter.author.map(|a| a.to_lowercase()),
+            series: filter.series.map(|s| s.to_lowercase()),
+            status: filter.status.map(|s| s.to_lowercase()),
+            owned: filter.owned,
+            year: filter.year,
+            ..Default::default()
+        };
+        page(
+            filter
+                .apply(snapshot(ctx))
+                .into_iter()
+                .cloned()
+                .map(VolumeNode),
+            limit,
+            offset,
+        )
+    }
+
+    /// A volume by number in the main series, or by key (e.g. `aps-3`).
+    async fn volume(
+        &self,
+        ctx: &Context<'_>,
+        number: Option<u32>,
+        key: Option<String>,
+    ) -> Option<VolumeNode> {
+        let key = key.or_else(|| number.map(|n| format!("loa-{}", n)))?;
+        snapshot(ctx)
+            .iter()
+            .find(|v| v.key() == key)
+            .cloned()
+            .map(VolumeNode)
+    }
+
+    /// Authors, optionally only those whose name contains `name` (ignoring case).
+    async fn authors(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<AuthorNode> {
+        let name = name.map(|n| n.to_lowercase());
+        page(
+            authors(snapshot(ctx)).into_iter().filter(|a| {
+                name.as_ref()
+                    .is_none_or(|n| a.author.name.to_lowercase().contains(n))
+            }),
+            limit,
+            offset,
+        )
+    }
+
+    /// The author with exactly this name, ignoring case.
+    async fn author(&self, ctx: &Context<'_>, name: String) -> Option<AuthorNode> {
+        authors(snapshot(ctx))
+            .into_iter()
+            .find(|a| a.author.name.eq_ignore_ascii_case(&name))
+    }
+
+    /// The bibliographic records of the volumes, optionally only those whose title
+    /// contains `title` (ignoring case).
+    async fn works(
+        &self,
+        ctx: &Context<'_>,
+        title: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<WorkNode> {
+        let title = title.map(|t| t.to_lowercase());
+        page(
+            snapshot(ctx)
+                .iter()
+                .filter(|v| {
+                    title
+                        .as_ref()
+                        .is_none_or(|t| v.title.to_lowercase().contains(t))
+                })
+                .map(|v| WorkNode {
+                    key: v.key(),
+                    work: Work::from_volume(v),
+                }),
+            limit,
+            offset,
+        )
+    }
+}
+
+pub struct VolumeNode(Volume);
+
+#[Object(name = "Volume")]
+impl VolumeNode {
+    /// Unique across series, e.g. `loa-1` or `aps-3`.
+    async fn key(&self) -> String {
+        self.0.key()
+    }
+
+    /// Null for special publications outside the numbered series.
+    async fn volume_number(&self) -> Option<u32> {
+        Some(self.0.volume_number).filter(|&n| n > 0)
+    }
+
+    /// `loa` for the main series.
+    async fn series(&self) -> &str {
+        match self.0.series.as_str() {
+            "" => "loa",
+            series => series,
+        }
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn original_volume_name(&self) -> &str {
+        &self.0.original_volume_name
+    }
+
+    /// The author's name as LOA prints it; empty for anthologies.
+    async fn author_name(&self) -> &str {
+        &self.0.author
+    }
+
+    async fn author(&self) -> Option<AuthorNode> {
+        Author::from_volume(&self.0).map(|author| AuthorNode {
+            identity: author_identity(&self.0),
+            author,
+        })
+    }
+
+    async fn work(&self) -> WorkNode {
+        WorkNode {
+            key: self.0.key(),
+            work: Work::from_volume(&self.0),
+        }
+    }
+
+    async fn loa_detail_link(&self) -> String {
+        absolute_url(&self.0.loa_detail_link)
+    }
+
+    async fn cover_image_link(&self) -> &str {
+        &self.0.cover_image_link
+    }
+
+    async fn owned(&self) -> bool {
+        self.0.own_volume == OWNED
+    }
+
+    async fn status(&self) -> Option<&str> {
+        self.0.status.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn release_date(&self) -> Option<&str> {
+        self.0.release_date.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn isbn(&self) -> Option<&str> {
+        self.0.isbn.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn publication_year(&self) -> Option<&str> {
+        self.0.publication_year.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn price_usd(&self) -> Option<&str> {
+        self.0.price_usd.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn member_price_usd(&self) -> Option<&str> {
+        self.0.member_price_usd.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn availability(&self) -> Option<&str> {
+        self.0.availability.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn first_seen(&self) -> Option<&str> {
+        self.0.first_seen.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn last_seen(&self) -> Option<&str> {
+        self.0.last_seen.as_deref().filter(|s| !s.is_empty())
+    }
+}
+
+pub struct AuthorNode {
+    identity: String,
+    author: Author,
+}
+
+#[Object(name = "Author")]
+impl AuthorNode {
+    async fn name(&self) -> &str {
+        &self.author.name
+    }
+
+    async fn wikipedia_link(&self) -> Option<&str> {
+        Some(self.author.wikipedia_link.as_str()).filter(|s| !s.is_empty())
+    }
+
+    async fn wikidata_id(&self) -> Option<&str> {
+        self.author.wikidata_id.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn viaf_id(&self) -> Option<&str> {
+        self.author.viaf_id.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn birth_date(&self) -> Option<&str> {
+        self.author.birth_date.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn death_date(&self) -> Option<&str> {
+        self.author.death_date.as_deref().filter(|s| !s.is_empty())
+    }
+
+    /// Every volume devoted to this author.
+    async fn volumes(&self, ctx: &Context<'_>) -> Vec<VolumeNode> {
+        snapshot(ctx)
+            .iter()
+            .filter(|v| !v.author.is_empty() && author_identity(v) == self.identity)
+            .cloned()
+            .map(VolumeNode)
+            .collect()
+    }
+}
+
+pub struct WorkNode {
+    /// The key of the volume the record belongs to.
+    key: String,
+    work: Work,
+}
+
+#[Object(name = "Work")]
+impl WorkNode {
+    async fn title(&self) -> &str {
+        &self.work.title
+    }
+
+    async fn author_name(&self) -> &str {
+        &self.work.author
+    }
+
+    async fn isbn(&self) -> Option<&str> {
+        self.work.isbn.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn publication_year(&self) -> Option<&str> {
+        self.work
+            .publication_year
+            .as_deref()
+            .filter(|s| !s.is_empty())
+    }
+
+    async fn openlibrary_work_id(&self) -> Option<&str> {
+        self.work
+            .openlibrary_work_id
+            .as_deref()
+            .filter(|s| !s.is_empty())
+    }
+
+    async fn oclc_number(&self) -> Option<&str> {
+        self.work.oclc_number.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn lccn(&self) -> Option<&str> {
+        self.work.lccn.as_deref().filter(|s| !s.is_empty())
+    }
+
+    async fn volume(&self, ctx: &Context<'_>) -> Option<VolumeNode> {
+        snapshot(ctx)
+            .iter()
+            .find(|v| v.key() == self.key)
+            .cloned()
+            .map(VolumeNode)
+    }
+}
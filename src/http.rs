@@ -0,0 +1,414 @@
+//! The HTTP client every request goes through, so per-run limits apply across all hosts.
+//! Requests are sent by a pluggable [`Backend`]; reqwest is the default. [`Middleware`]
+//! registered on the client can adjust each request and observe each response. Requests
+//! to loa.org follow its robots.txt (see [`Robots`]).
+//!
+//! Long operations can be stopped early in two ways: dropping their future, which
+//! abandons any request under way, or cancelling the client's [`CancellationToken`],
+//! after which every loop stops before its next item with a [`Cancelled`] error.
+
+use crate::rate::{AdaptiveRate, RateLimit, RateLimiter};
+use crate::robots::Robots;
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode, Url};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio_util::sync::CancellationToken;
+
+/// Returned once the run has used up its `--max-requests` budget.
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetExhausted {
+    pub limit: usize,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request budget of {} exhausted", self.limit)
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// Returned once the client's cancellation token has been cancelled.
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[derive(Debug, Default)]
+struct Budget {
+    limit: Option<usize>,
+    used: AtomicUsize,
+    refused: AtomicBool,
+}
+
+impl Budget {
+    fn error(&self) -> BudgetExhausted {
+        BudgetExhausted {
+            limit: self.limit.unwrap_or_default(),
+        }
+    }
+}
+
+/// A request as handed to a [`Backend`].
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// Empty for requests without a body, such as GETs.
+    pub body: Vec<u8>,
+}
+
+/// A fully buffered response from a [`Backend`].
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Vec<u8>) -> Self {
+        Response {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Sends requests on behalf of a [`Client`]. Implement this to swap in another HTTP
+/// stack, or a fake that serves canned responses in tests.
+#[async_trait]
+pub trait Backend: Send + Sync {
+    async fn execute(&self, request: Request) -> Result<Response>;
+}
+
+/// Hooks run around every request a [`Client`] sends, e.g. to add headers or auth, or
+/// to log responses and collect metrics. Both methods do nothing by default.
+pub trait Middleware: Send + Sync {
+    /// Called just before the request goes to the backend.
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Called with the backend's result for `request`.
+    fn on_response(&self, _request: &Request, _response: &Result<Response>) {}
+}
+
+/// The default backend.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestBackend(pub reqwest::Client);
+
+#[async_trait]
+impl Backend for ReqwestBackend {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let mut builder = self
+            .0
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if !request.body.is_empty() {
+            builder = builder.body(request.body);
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        Ok(Response::new(status, headers, body))
+    }
+}
+
+/// Sends requests through a [`Backend`] with a shared request budget. Clones share the
+/// same backend, budget, pacing, and cancellation token.
+#[derive(Clone)]
+pub struct Client {
+    backend: Arc<dyn Backend>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    budget: Arc<Budget>,
+    limiter: Arc<RateLimiter>,
+    rate: Option<Arc<AdaptiveRate>>,
+    robots: Arc<Robots>,
+    cancel: CancellationToken,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::with_backend(ReqwestBackend::default())
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("middleware", &self.middleware.len())
+            .field("budget", &self.budget)
+            .field("rate_limit", &self.limiter.limit())
+            .field("adaptive", &self.is_adaptive())
+            .field("cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_backend(backend: impl Backend + 'static) -> Self {
+        Client {
+            backend: Arc::new(backend),
+            middleware: Vec::new(),
+            budget: Arc::default(),
+            limiter: Arc::new(RateLimiter::new(RateLimit::default())),
+            rate: None,
+            robots: Arc::default(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn from_reqwest(inner: reqwest::Client) -> Self {
+        Client::with_backend(ReqwestBackend(inner))
+    }
+
+    /// Adds a hook around every request. Request hooks run in the order they were added;
+    /// response hooks run in reverse, so the first one added sees the final result.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Caps the total number of requests this client (and its clones) will send.
+    pub fn with_max_requests(self, limit: Option<usize>) -> Self {
+        Client {
+            budget: Arc::new(Budget {
+                limit,
+                ..Budget::default()
+            }),
+            ..self
+        }
+    }
+
+    /// Limits how fast requests go to each host; [`RateLimit::default`] unless set.
+    pub fn with_rate_limit(self, limit: RateLimit) -> Self {
+        Client {
+            limiter: Arc::new(RateLimiter::new(limit)),
+            ..self
+        }
+    }
+
+    /// Also paces requests per host with an [`AdaptiveRate`], within the rate limit.
+    /// Callers check [`is_adaptive`](Self::is_adaptive) to run several at once.
+    pub fn with_adaptive_rate(self) -> Self {
+        Client {
+            rate: Some(Arc::new(AdaptiveRate::new())),
+            ..self
+        }
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        self.rate.is_some()
+    }
+
+    /// Stops work when `token` is cancelled: requests under way are abandoned, new ones
+    /// fail, and the scraping and enrichment loops return [`Cancelled`] before their
+    /// next item.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        Client {
+            cancel: token,
+            ..self
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        RequestBuilder {
+            client: self.clone(),
+            request: Url::parse(url).map(|url| Request {
+                method,
+                url,
+                headers: HeaderMap::new(),
+                body: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn requests_made(&self) -> usize {
+        self.budget.used.load(Ordering::SeqCst)
+    }
+
+    /// Fails with [`BudgetExhausted`] if no budget is left, or [`Cancelled`] once the
+    /// run is cancelled, so loops can stop before starting another item.
+    pub fn check_budget(&self) -> Result<()> {
+        match self.budget.limit {
+            Some(limit) if self.requests_made() >= limit => Err(self.budget.error().into()),
+            _ => self.check_refused(),
+        }
+    }
+
+    /// Fails if any request was refused for lack of budget or by robots.txt, or the run
+    /// was cancelled. Callers that treat request errors as "no data" check this so a
+    /// refused or abandoned item isn't recorded as finished.
+    pub fn check_refused(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else if let Some(disallowed) = self.robots.refused() {
+            Err(disallowed.into())
+        } else if self.budget.refused.load(Ordering::SeqCst) {
+            Err(self.budget.error().into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Builds a request; a malformed URL is reported when it's sent.
+pub struct RequestBuilder {
+    client: Client,
+    request: Result<Request, url::ParseError>,
+}
+
+impl RequestBuilder {
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let Ok(request) = &mut self.request
+            && let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            )
+        {
+            request.headers.insert(name, value);
+        }
+        self
+    }
+
+    pub fn basic_auth(self, username: &str, password: &str) -> Self {
+        let credentials = BASE64.encode(format!("{}:{}", username, password));
+        self.header(AUTHORIZATION.as_str(), &format!("Basic {}", credentials))
+    }
+
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.header(AUTHORIZATION.as_str(), &format!("Bearer {}", token))
+    }
+
+    pub fn query<K: AsRef<str>, V: AsRef<str>>(mut self, pairs: &[(K, V)]) -> Self {
+        if let Ok(request) = &mut self.request {
+            request.url.query_pairs_mut().extend_pairs(pairs);
+        }
+        self
+    }
+
+    /// Sends `body` as is; set its content type with `header`.
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        if let Ok(request) = &mut self.request {
+            request.body = body;
+        }
+        self
+    }
+
+    /// Sends `value` as the JSON request body.
+    pub fn json<T: Serialize + ?Sized>(mut self, value: &T) -> Self {
+        if let Ok(request) = &mut self.request {
+            request.body = serde_json::to_vec(value).unwrap_or_default();
+        }
+        self.header(CONTENT_TYPE.as_str(), "application/json")
+    }
+
+    /// Sends `pairs` as a URL-encoded form body.
+    pub fn form(mut self, pairs: &[(&str, &str)]) -> Self {
+        if let Ok(request) = &mut self.request {
+            request.body = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(pairs)
+                .finish()
+                .into_bytes();
+        }
+        self.header(CONTENT_TYPE.as_str(), "application/x-www-form-urlencoded")
+    }
+
+    pub async fn send(self) -> Result<Response> {
+        let cancel = self.client.cancel.clone();
+        tokio::select! {
+            biased;
+            () = cancel.cancelled() => Err(Cancelled.into()),
+            response = self.send_now() => response,
+        }
+    }
+
+    async fn send_now(self) -> Result<Response> {
+        let mut request = self.request?;
+        self.client
+            .robots
+            .admit(self.client.backend.as_ref(), &request.url)
+            .await?;
+        let budget = &self.client.budget;
+        let used = budget.used.fetch_add(1, Ordering::SeqCst);
+        if budget.limit.is_some_and(|limit| used >= limit) {
+            budget.used.fetch_sub(1, Ordering::SeqCst);
+            budget.refused.store(true, Ordering::SeqCst);
+            return Err(budget.error().into());
+        }
+
+        let middleware = &self.client.middleware;
+        for hook in middleware {
+            hook.on_request(&mut request);
+        }
+        // The token comes first so time spent waiting for it doesn't count as the
+        // host's response time
+        let host = request.url.host_str().unwrap_or_default().to_string();
+        self.client.limiter.acquire(&host).await;
+        let permit = match &self.client.rate {
+            Some(rate) => Some(rate.acquire(&host).await),
+            None => None,
+        };
+        if middleware.is_empty() && permit.is_none() {
+            return self.client.backend.execute(request).await;
+        }
+
+        let sent = request.clone();
+        let response = self.client.backend.execute(request).await;
+        if let Some(permit) = permit {
+            permit.finish(&response);
+        }
+        for hook in middleware.iter().rev() {
+            hook.on_response(&sent, &response);
+        }
+        response
+    }
+}
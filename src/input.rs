@@ -0,0 +1,160 @@
+use crate::Volume;
+use crate::output::protobuf::read_protobuf;
+use crate::sqlite::read_sqlite;
+use anyhow::{Context, Result};
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Header spellings seen in hand-edited or spreadsheet-exported files, by the column
+/// they mean. Headers are compared after `normalize_header`.
+const HEADER_ALIASES: &[(&str, &str)] = &[
+    ("volume", "volume_number"),
+    ("number", "volume_number"),
+    ("no", "volume_number"),
+    ("loa_number", "volume_number"),
+    ("wikipedia", "author_wikipedia_link"),
+    ("wikipedia_link", "author_wikipedia_link"),
+    ("author_wikipedia", "author_wikipedia_link"),
+    ("detail_link", "loa_detail_link"),
+    ("loa_link", "loa_detail_link"),
+    ("link", "loa_detail_link"),
+    ("url", "loa_detail_link"),
+    ("volume_name", "original_volume_name"),
+    ("full_title", "original_volume_name"),
+    ("owned", "own_volume"),
+    ("own", "own_volume"),
+    ("cover", "cover_image_link"),
+    ("cover_url", "cover_image_link"),
+    ("cover_image", "cover_image_link"),
+];
+
+/// Reads a previous export back into volumes: SQLite databases (`.db`, `.sqlite`,
+/// `.sqlite3`), JSON (`.json`) and protobuf (`.pb`) by extension, CSV otherwise.
+pub fn read_volumes(path: &Path) -> Result<Vec<Volume>> {
+    let is_sqlite = path.extension().is_some_and(|ext| {
+        ["db", "sqlite", "sqlite3"]
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    });
+
+    if is_sqlite {
+        return read_sqlite(&path.to_string_lossy())
+            .with_context(|| format!("Failed to read volumes from {}", path.display()));
+    }
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        return serde_json::from_str(&text)
+            .with_context(|| format!("Failed to read volumes from {}", path.display()));
+    }
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pb"))
+    {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        return read_protobuf(&bytes)
+            .with_context(|| format!("Failed to read volumes from {}", path.display()));
+    }
+
+    read_volumes_csv(path)
+}
+
+/// "Volume Number" and "volume-number" both become `volume_number`.
+fn normalize_header(header: &str) -> String {
+    header
+        .trim()
+        .trim_start_matches('\u{feff}')
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Spreadsheets tend to turn `12` into `12.0` or keep a leading `#`.
+fn normalize_volume_number(cell: &str) -> &str {
+    let cell = cell.trim().trim_start_matches('#');
+    cell.strip_suffix(".0").unwrap_or(cell)
+}
+
+/// Reads volume CSVs written by any version of the tool, or edited since: headers
+/// are matched loosely and through `HEADER_ALIASES`, columns that older versions
+/// didn't write are filled in empty, and extra columns are ignored.
+fn read_volumes_csv(path: &Path) -> Result<Vec<Volume>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    // Map each file column to the field it holds; the first column claiming a field wins
+    let mut headers: Vec<String> = Vec::new();
+    for header in reader.headers()?.iter() {
+        let normalized = normalize_header(header);
+        let canonical = HEADER_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == normalized)
+            .map_or(normalized.as_str(), |(_, field)| field)
+            .to_string();
+        headers.push(if headers.contains(&canonical) {
+            header.to_string()
+        } else {
+            canonical
+        });
+    }
+
+    // Every field a current Volume always has, so files predating one still deserialize
+    let required: Vec<String> = match serde_json::to_value(Volume::default())? {
+        serde_json::Value::Object(fields) => fields.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    let missing: Vec<String> = required
+        .into_iter()
+        .filter(|field| !headers.contains(field))
+        .collect();
+    let volume_column = headers.iter().position(|h| h == "volume_number");
+
+    let header_record: StringRecord = headers.iter().chain(&missing).collect();
+    let mut volumes = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record =
+            record.with_context(|| format!("Failed to read volumes from {}", path.display()))?;
+        let fields: StringRecord = (0..headers.len())
+            .map(|i| {
+                let cell = record.get(i).unwrap_or("");
+                if Some(i) == volume_column {
+                    normalize_volume_number(cell)
+                } else {
+                    cell
+                }
+            })
+            .chain(missing.iter().map(|_| ""))
+            .collect();
+        let volume = fields.deserialize(Some(&header_record)).with_context(|| {
+            format!(
+                "Failed to read volume on line {} of {}",
+                line + 2,
+                path.display()
+            )
+        })?;
+        volumes.push(volume);
+    }
+
+    Ok(volumes)
+}
+
+/// Reads CSV rows into any record type, matching columns to fields by header.
+pub fn read_csv<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .with_context(|| format!("Failed to read records from {}", path.display()))
+}
@@ -0,0 +1,64 @@
+//! Scraping, parsing, and export of the Library of America volume catalog.
+//!
+//! The `loa-scraper` binary is a thin CLI over this crate; embedding applications
+//! can call the same functions directly.
+
+pub mod airtable;
+pub mod api;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod author_cache;
+pub mod author_overrides;
+pub mod constants;
+pub mod coverage;
+pub mod covers;
+pub mod details;
+pub mod digest;
+#[cfg(feature = "duckdb")]
+pub mod duckdb;
+#[cfg(feature = "email")]
+pub mod email;
+pub mod enrich;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod forecast;
+pub mod gaps;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod http;
+pub mod input;
+pub mod links;
+pub mod notify;
+pub mod output;
+pub mod ownership;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod progress;
+#[cfg(feature = "python")]
+mod python;
+pub mod rate;
+pub mod recommend;
+pub mod report;
+pub mod robots;
+#[cfg(feature = "s3")]
+pub mod s3;
+pub mod sanity;
+pub mod scrape;
+pub mod search;
+pub mod sets;
+#[cfg(feature = "sheets")]
+pub mod sheets;
+pub mod sqlite;
+pub mod stats;
+pub mod store;
+pub mod story;
+pub mod title_style;
+pub mod update;
+pub mod viaf;
+pub mod volume;
+pub mod wayback;
+pub mod webhook;
+pub mod wikidata;
+pub mod wikipedia;
+
+pub use volume::{Author, Ownership, Volume, Work};
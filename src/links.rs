@@ -0,0 +1,127 @@
+use crate::Volume;
+use crate::http::{Client, Response};
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use reqwest::redirect::Policy;
+use reqwest::{Method, StatusCode};
+use std::fmt;
+
+/// What a link check found at a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkStatus {
+    Ok(u16),
+    Redirected { status: u16, location: String },
+    Dead(u16),
+    Failed(String),
+}
+
+impl LinkStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, LinkStatus::Ok(_))
+    }
+}
+
+impl fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkStatus::Ok(status) => write!(f, "{}", status),
+            LinkStatus::Redirected { status, location } => {
+                write!(f, "{} -> {}", status, location)
+            }
+            LinkStatus::Dead(status) => write!(f, "{}", status),
+            LinkStatus::Failed(error) => write!(f, "request failed: {}", error),
+        }
+    }
+}
+
+/// One link that was checked, and the volume and column it came from.
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    pub volume_number: u32,
+    pub field: &'static str,
+    pub url: String,
+    pub status: LinkStatus,
+}
+
+/// A client that reports redirects instead of following them.
+pub fn link_check_client() -> Result<Client> {
+    Ok(Client::from_reqwest(
+        reqwest::Client::builder()
+            .redirect(Policy::none())
+            .build()?,
+    ))
+}
+
+async fn request(client: &Client, method: Method, url: &str) -> Result<Response> {
+    client.request(method, url).send().await
+}
+
+/// Checks a URL with HEAD, retrying with GET for servers that don't support HEAD.
+pub async fn check_link(client: &Client, url: &str) -> LinkStatus {
+    let response = match request(client, Method::HEAD, url).await {
+        Ok(response)
+            if response.status() == StatusCode::METHOD_NOT_ALLOWED
+                || response.status() == StatusCode::NOT_IMPLEMENTED =>
+        {
+            request(client, Method::GET, url).await
+        }
+        other => other,
+    };
+
+    match response {
+        Err(e) => LinkStatus::Failed(e.to_string()),
+        Ok(response) => {
+            let status = response.status();
+            if status.is_redirection() {
+                LinkStatus::Redirected {
+                    status: status.as_u16(),
+                    location: response
+                        .headers()
+                        .get(reqwest::header::LOCATION)
+                        .and_then(|l| l.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string(),
+                }
+            } else if status.is_success() {
+                LinkStatus::Ok(status.as_u16())
+            } else {
+                LinkStatus::Dead(status.as_u16())
+            }
+        }
+    }
+}
+
+/// Checks every volume's detail and Wikipedia link, calling `on_volume` as each
+/// volume's links are done.
+pub async fn check_volume_links(
+    client: &Client,
+    volumes: &[Volume],
+    mut on_volume: impl FnMut(&Volume),
+) -> Vec<LinkCheck> {
+    let mut checks = Vec::new();
+
+    for volume in volumes {
+        let links = [
+            ("loa_detail_link", absolute_url(&volume.loa_detail_link)),
+            (
+                "author_wikipedia_link",
+                volume.author_wikipedia_link.clone(),
+            ),
+        ];
+        for (field, url) in links {
+            if url.is_empty() {
+                continue;
+            }
+            checks.push(LinkCheck {
+                volume_number: volume.volume_number,
+                field,
+                status: check_link(client, &url).await,
+                url,
+            });
+        }
+
+        on_volume(volume);
+    }
+
+    checks
+}
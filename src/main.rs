@@ -1,356 +1,109 @@
-use anyhow::{Result, anyhow};
-use clap::Parser;
-use colored::*;
-use csv::Writer;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
-use scraper::{Html, Selector};
-use serde::Serialize;
-use std::fs::File;
-use std::io::{self, Write};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use commands::checkpoint::EXIT_RESUMABLE;
+use loa_scraper::http::{BudgetExhausted, Cancelled};
+
+mod commands;
 
 #[derive(Parser, Debug)]
 #[command(name = "loa-scraper")]
-#[command(about = "Scrape Library of America volumes and generate CSV")]
-struct Args {
-    #[arg(short, long, help = "Starting volume number (default: 1)")]
-    start: Option<u32>,
-
-    #[arg(short, long, help = "Ending volume number (default: last available)")]
-    end: Option<u32>,
-
-    #[arg(short, long, help = "Output CSV file path (default: stdout)")]
-    output: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-struct Volume {
-    volume_number: u32,
-    title: String,
-    author: String,
-    author_wikipedia_link: String,
-    loa_detail_link: String,
-    original_volume_name: String,
-    own_volume: String,
-}
-
-async fn scrape_collection_page(client: &Client) -> Result<Html> {
-    let url = "https://www.loa.org/books/loa_collection/";
-    let response = client.get(url).send().await?;
-    let body = response.text().await?;
-
-    Ok(Html::parse_document(&body))
-}
-
-async fn get_wikipedia_link(client: &Client, author: &str) -> Result<String> {
-    // Skip if no author or if it's not a real author name
-    if author.is_empty() || author == "Unknown" {
-        return Ok(String::new());
-    }
-
-    let search_url = format!(
-        "https://en.wikipedia.org/w/api.php?action=opensearch&search={}&limit=1&format=json",
-        urlencoding::encode(author)
-    );
-
-    match client
-        .get(&search_url)
-        .header(
-            "User-Agent",
-            "LOA-Scraper/1.0 (https://github.com/example/loa-scraper)",
-        )
-        .send()
-        .await
-    {
-        Ok(response) => {
-            match response.text().await {
-                Ok(text) => {
-                    if text.trim().is_empty() {
-                        return Ok(String::new());
-                    }
-
-                    match serde_json::from_str::<serde_json::Value>(&text) {
-                        Ok(json) => {
-                            // OpenSearch API returns: [query, [titles], [descriptions], [urls]]
-                            if let Some(urls) = json.get(3).and_then(|v| v.as_array())
-                                && let Some(url) = urls.first().and_then(|v| v.as_str())
-                                    && !url.is_empty() {
-                                        return Ok(url.to_string());
-                                    }
-                        }
-                        Err(_) => {
-                            // If JSON parsing fails, it might be an error page - just return empty
-                            return Ok(String::new());
-                        }
-                    }
-                }
-                Err(_) => {
-                    return Ok(String::new());
-                }
-            }
-        }
-        Err(_) => {
-            return Ok(String::new());
-        }
-    }
-
-    Ok(String::new())
+#[command(about = "Scrape Library of America volumes and export them as CSV and other formats")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    scrape: commands::scrape::ScrapeArgs,
 }
 
-fn is_likely_author(text: &str) -> bool {
-    // Heuristics to determine if text is likely an author name vs. a series/collection title
-
-    // If it contains "The " at the start, it's more likely a title
-    if text.starts_with("The ") {
-        return false;
-    }
-
-    // Common patterns that indicate it's NOT an author name
-    let non_author_patterns = [
-        "The American Short Story",
-        "The Best American",
-        "American Poetry",
-        "Collected Works",
-        "Complete Works",
-        "Selected Works",
-        "Early Works",
-        "Later Works",
-        "Writings",
-        "Letters",
-        "Speeches",
-        "Documents",
-        "Chronicles",
-        "Anthology",
-        "Collection",
-    ];
-
-    for pattern in &non_author_patterns {
-        if text.contains(pattern) {
-            return false;
-        }
-    }
-
-    // If it looks like "Firstname Lastname" or "F. Lastname" or "Firstname M. Lastname", it's likely an author
-    let words: Vec<&str> = text.split_whitespace().collect();
-
-    // Single word is unlikely to be an author (unless it's like "Aristotle")
-    if words.len() == 1 {
-        // Some single-name authors exist, but let's be conservative
-        return text.chars().any(|c| c.is_lowercase()); // Has lowercase letters (not all caps title)
-    }
-
-    // Two or more words - check if it looks like a name
-    if words.len() >= 2 {
-        let first_word = words[0];
-        let last_word = words[words.len() - 1];
-
-        // Check if first and last words start with capital letters (name pattern)
-        if first_word
-            .chars()
-            .next()
-            .is_some_and(|c| c.is_uppercase())
-            && last_word.chars().next().is_some_and(|c| c.is_uppercase())
-        {
-            // Additional check: avoid things like "Civil War" or "New England"
-            if words.len() == 2
-                && (text.contains("War")
-                    || text.contains("American")
-                    || text.contains("New ")
-                    || text.contains("Old "))
-            {
-                return false;
-            }
-
-            return true;
-        }
-    }
-
-    false
-}
-
-type VolumeData = (u32, String, String, String, String);
-
-fn parse_volumes(html: &Html) -> Result<Vec<VolumeData>> {
-    let book_listing_selector = Selector::parse("li.content-listing.content-listing--book")
-        .map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
-    let link_selector = Selector::parse("a").map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
-    let number_selector = Selector::parse("i.book-listing__number")
-        .map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
-    let title_selector = Selector::parse("b.content-listing__title")
-        .map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
-    let mut volumes = Vec::new();
-
-    for book_element in html.select(&book_listing_selector) {
-        let link_element = book_element.select(&link_selector).next();
-        let number_element = book_element.select(&number_selector).next();
-        let title_element = book_element.select(&title_selector).next();
-
-        if let (Some(link), Some(number), Some(title)) =
-            (link_element, number_element, title_element)
-        {
-            let href = link.value().attr("href").unwrap_or("");
-            let volume_number = number
-                .text()
-                .collect::<String>()
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(0);
-            let title_text = title.text().collect::<String>().trim().to_string();
-
-            if volume_number > 0 {
-                // Parse title which could be "Author: Title" or "Series Title: Subtitle"
-                let (author, book_title) = if let Some(colon_pos) = title_text.find(':') {
-                    let before_colon = title_text[..colon_pos].trim();
-                    let after_colon = title_text[colon_pos + 1..].trim();
-
-                    if is_likely_author(before_colon) {
-                        // It's an author: use as author and title
-                        (before_colon.to_string(), after_colon.to_string())
-                    } else {
-                        // It's likely a series or collection title: treat whole thing as title
-                        (String::new(), title_text.clone())
-                    }
-                } else {
-                    // No colon found: treat as title with unknown author
-                    (String::new(), title_text.clone())
-                };
-
-                volumes.push((
-                    volume_number,
-                    book_title,
-                    author,
-                    href.to_string(),
-                    title_text.clone(),
-                ));
-            }
-        }
-    }
-
-    volumes.sort_by_key(|v| v.0);
-    Ok(volumes)
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert an export to another format without touching the network
+    Convert(commands::convert::ConvertArgs),
+    /// Report how much of your library LOA covers, and volumes that duplicate books you own
+    Coverage(commands::coverage::CoverageArgs),
+    /// Summarize new, forthcoming, and delisted volumes for a newsletter or blog post
+    Digest(commands::digest::DigestArgs),
+    /// Check connectivity, the page selectors, config, and caches before filing a bug
+    Doctor(commands::doctor::DoctorArgs),
+    /// Export the volumes in the sync store in any output format
+    Export(commands::export::ExportArgs),
+    /// Guess which authors and periods future volumes will cover, from past patterns
+    Forecast(commands::forecast::ForecastArgs),
+    /// List an author's notable works (per Wikidata) that no LOA volume collects yet
+    Gaps(commands::gaps::GapsArgs),
+    /// Flag unowned volumes whose works you mostly own in other editions already
+    OverlapAdvisor(commands::overlap::OverlapArgs),
+    /// Record which volumes you own, joined into the own_volume column on every scrape
+    Own(commands::own::OwnArgs),
+    /// Suggest volumes to buy next from what you own: sets to finish, authors, and eras
+    Recommend(commands::recommend::RecommendArgs),
+    /// Check the parser against stored collection-page fixtures
+    Regress(commands::regress::RegressArgs),
+    /// Write a small, optionally anonymized slice of the dataset for bug reports and tests
+    Sample(commands::sample::SampleArgs),
+    /// Find volumes by author or title in the store or an export
+    Search(commands::search::SearchArgs),
+    /// Serve the store as a JSON API: /volumes, /volumes/{n}, /authors, and /graphql
+    Serve(commands::serve::ServeArgs),
+    /// List LOA's boxed sets with their prices and volumes, cheapest way to fill gaps first
+    Sets(commands::sets::SetsArgs),
+    /// Summarize the catalog: ownership, authors, decades, and Wikipedia coverage
+    Stats(commands::stats::StatsArgs),
+    /// List LOA's Story of the Week archive, with the volume each story comes from
+    StoryOfTheWeek(commands::story::StoryArgs),
+    /// Scrape into the local store, recording when each volume was first and last seen
+    Sync(commands::sync::SyncArgs),
+    /// Write an interactive timeline of the volumes by the years of their works
+    Timeline(commands::timeline::TimelineArgs),
+    /// Browse the volumes in a terminal UI: search, filter, mark owned, and open links
+    Tui(commands::tui::TuiArgs),
+    /// Check for a newer release and replace this binary with it
+    Update(commands::update::UpdateArgs),
+    /// Check an export's detail and Wikipedia links for dead or redirected URLs
+    Validate(commands::validate::ValidateArgs),
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
-    let start_volume = args.start.unwrap_or(1);
-
-    eprintln!(
-        "{} {}",
-        "🔍".cyan(),
-        "Scraping Library of America volumes".bright_blue().bold()
-    );
-
-    let client = Client::new();
-
-    // Create spinner for fetching page
-    eprintln!(
-        "{} {}",
-        "📡".yellow(),
-        "Fetching collection page...".yellow()
-    );
-    let html = scrape_collection_page(&client).await?;
-
-    eprintln!("{} {}", "📚".green(), "Parsing volumes...".green());
-    let volumes_data = parse_volumes(&html)?;
-
-    // Filter by start and end volume
-    let filtered_volumes: Vec<_> = volumes_data
-        .into_iter()
-        .filter(|(num, _, _, _, _)| {
-            *num >= start_volume && args.end.is_none_or(|end| *num <= end)
-        })
-        .collect();
-
-    let volume_range = if let Some(end) = args.end {
-        format!("{}-{}", start_volume, end)
-    } else {
-        format!("{}+", start_volume)
-    };
-
-    eprintln!(
-        "{} {} volumes {} (volumes {})",
-        "✅".green(),
-        "Found".green().bold(),
-        filtered_volumes.len().to_string().bright_white().bold(),
-        volume_range.cyan()
-    );
-
-    if filtered_volumes.is_empty() {
-        eprintln!(
-            "{} {}",
-            "⚠️".yellow(),
-            "No volumes found in specified range".yellow()
-        );
-        return Ok(());
+    let cli = Cli::parse();
+    // doctor reports on a broken config instead of refusing to start
+    if !matches!(cli.command, Some(Command::Doctor(_))) {
+        loa_scraper::constants::load_configured_metadata()?;
     }
 
-    // Setup output writer
-    let mut writer: Writer<Box<dyn Write>> = if let Some(output_path) = &args.output {
-        let file = File::create(output_path)?;
-        Writer::from_writer(Box::new(file))
-    } else {
-        Writer::from_writer(Box::new(io::stdout()))
+    let result = match cli.command {
+        None => commands::scrape::run(cli.scrape).await,
+        Some(Command::Convert(args)) => commands::convert::run(args),
+        Some(Command::Coverage(args)) => commands::coverage::run(args).await,
+        Some(Command::Digest(args)) => commands::digest::run(args),
+        Some(Command::Doctor(args)) => commands::doctor::run(args).await,
+        Some(Command::Export(args)) => commands::export::run(args),
+        Some(Command::Forecast(args)) => commands::forecast::run(args).await,
+        Some(Command::Gaps(args)) => commands::gaps::run(args).await,
+        Some(Command::OverlapAdvisor(args)) => commands::overlap::run(args).await,
+        Some(Command::Own(args)) => commands::own::run(args),
+        Some(Command::Recommend(args)) => commands::recommend::run(args),
+        Some(Command::Regress(args)) => commands::regress::run(args),
+        Some(Command::Sample(args)) => commands::sample::run(args),
+        Some(Command::Search(args)) => commands::search::run(args),
+        Some(Command::Serve(args)) => commands::serve::run(args).await,
+        Some(Command::Sets(args)) => commands::sets::run(args).await,
+        Some(Command::Stats(args)) => commands::stats::run(args),
+        Some(Command::StoryOfTheWeek(args)) => commands::story::run(args).await,
+        Some(Command::Sync(args)) => commands::sync::run(args).await,
+        Some(Command::Timeline(args)) => commands::timeline::run(args),
+        Some(Command::Tui(args)) => commands::tui::run(args),
+        Some(Command::Update(args)) => commands::update::run(args).await,
+        Some(Command::Validate(args)) => commands::validate::run(args).await,
     };
 
-    // Progress bar for processing
-    let pb = ProgressBar::new(filtered_volumes.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
-            )
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-
-    eprintln!(
-        "{} {}",
-        "🔗".magenta(),
-        "Processing volumes and finding Wikipedia links...".magenta()
-    );
-
-    for (i, (volume_number, title, author, loa_link, original_name)) in
-        filtered_volumes.iter().enumerate()
+    // The scrape already reported where its checkpoint went
+    if let Err(e) = &result
+        && (e.is::<BudgetExhausted>() || e.is::<Cancelled>())
     {
-        pb.set_message(format!(
-            "Volume {}: {}",
-            volume_number,
-            title.chars().take(40).collect::<String>()
-        ));
-
-        if i > 0 && i % 10 == 0 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        }
-
-        let wikipedia_link: String = get_wikipedia_link(&client, author).await.unwrap_or_default();
-
-        let volume = Volume {
-            volume_number: *volume_number,
-            title: title.clone(),
-            author: author.clone(),
-            author_wikipedia_link: wikipedia_link,
-            loa_detail_link: loa_link.clone(),
-            original_volume_name: original_name.clone(),
-            own_volume: String::new(),
-        };
-
-        writer.serialize(&volume)?;
-        pb.inc(1);
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        std::process::exit(EXIT_RESUMABLE);
     }
-
-    pb.finish_with_message("Complete!");
-    writer.flush()?;
-
-    if let Some(output_path) = &args.output {
-        eprintln!(
-            "{} {} '{}'",
-            "💾".green(),
-            "CSV file created successfully:".green().bold(),
-            output_path.bright_white()
-        );
-    }
-
-    Ok(())
+    result
 }
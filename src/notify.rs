@@ -0,0 +1,198 @@
+//! Chat messages announcing new volumes, formatted natively for Slack and Discord
+//! incoming webhooks, and desktop notifications for new volumes and price changes.
+
+use crate::Volume;
+use crate::digest::{number_label, release};
+use crate::http::Client;
+use crate::scrape::absolute_url;
+use crate::store::PriceChange;
+use anyhow::{Result, bail};
+use reqwest::Method;
+use serde_json::{Value, json};
+
+/// Discord allows at most ten embeds per message.
+const DISCORD_EMBEDS: usize = 10;
+
+/// Slack allows 50 blocks per message; each volume takes one plus a header.
+const SLACK_VOLUMES: usize = 40;
+
+/// Up to this many volumes get a desktop notification each; more are summed up in one.
+const DESKTOP_TOASTS: usize = 5;
+
+/// What a chat message says about one volume, e.g. "#342 · Herman Melville" or
+/// "Coming 2025-03-04 · Herman Melville".
+fn subtitle(volume: &Volume) -> String {
+    let mut parts = Vec::new();
+    if let Some(label) = number_label(volume) {
+        parts.push(label);
+    }
+    if let Some(date) = release(volume) {
+        parts.push(format!("Coming {}", date));
+    }
+    if !volume.author.is_empty() {
+        parts.push(volume.author.clone());
+    }
+    parts.join(" · ")
+}
+
+fn heading(count: usize) -> String {
+    match count {
+        1 => "New from the Library of America".to_string(),
+        n => format!("{} new volumes from the Library of America", n),
+    }
+}
+
+/// Escapes text for Slack mrkdwn. `|` would end a link's text early and `*` its
+/// bold, so they're dropped.
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['|', '*'], "")
+}
+
+/// Slack Block Kit messages: a header, then a section per volume linking its title,
+/// with the cover as a thumbnail.
+pub fn slack_messages(volumes: &[Volume]) -> Vec<Value> {
+    volumes
+        .chunks(SLACK_VOLUMES)
+        .map(|chunk| {
+            let mut blocks = vec![json!({
+                "type": "header",
+                "text": {"type": "plain_text", "text": heading(volumes.len())},
+            })];
+            for volume in chunk {
+                let title = slack_escape(&volume.title);
+                let title = match volume.loa_detail_link.as_str() {
+                    "" => format!("*{}*", title),
+                    link => format!("*<{}|{}>*", absolute_url(link), title),
+                };
+                let mut section = json!({
+                    "type": "section",
+                    "text": {"type": "mrkdwn", "text": format!("{}\n{}", title, subtitle(volume))},
+                });
+                if !volume.cover_image_link.is_empty() {
+                    section["accessory"] = json!({
+                        "type": "image",
+                        "image_url": volume.cover_image_link,
+                        "alt_text": volume.title,
+                    });
+                }
+                blocks.push(section);
+            }
+            // Shown in notifications, where blocks aren't rendered
+            json!({"text": heading(volumes.len()), "blocks": blocks})
+        })
+        .collect()
+}
+
+/// Discord messages with one embed per volume: linked title, author, and cover.
+pub fn discord_messages(volumes: &[Volume]) -> Vec<Value> {
+    volumes
+        .chunks(DISCORD_EMBEDS)
+        .map(|chunk| {
+            let embeds: Vec<Value> = chunk
+                .iter()
+                .map(|volume| {
+                    let mut embed = json!({
+                        "title": volume.title,
+                        "description": subtitle(volume),
+                    });
+                    if !volume.loa_detail_link.is_empty() {
+                        embed["url"] = json!(absolute_url(&volume.loa_detail_link));
+                    }
+                    if !volume.cover_image_link.is_empty() {
+                        embed["thumbnail"] = json!({"url": volume.cover_image_link});
+                    }
+                    embed
+                })
+                .collect();
+            json!({"content": heading(volumes.len()), "embeds": embeds})
+        })
+        .collect()
+}
+
+/// Posts each message to an incoming-webhook URL in order.
+pub async fn post_messages(client: &Client, url: &str, messages: &[Value]) -> Result<()> {
+    for message in messages {
+        let response = client
+            .request(Method::POST, url)
+            .json(message)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!(
+                "Webhook returned {}: {}",
+                response.status(),
+                response.text()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A desktop notification: a one-line summary and a few lines of body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub summary: String,
+    pub body: String,
+}
+
+/// One toast per item, or a single toast listing the first few when there are more
+/// than would be welcome at once (e.g. the first sync into an empty store).
+fn toasts<T>(
+    items: &[T],
+    summary: impl Fn(usize) -> String,
+    line: impl Fn(&T) -> String,
+) -> Vec<Toast> {
+    if items.len() > DESKTOP_TOASTS {
+        let mut lines: Vec<String> = items.iter().take(DESKTOP_TOASTS).map(&line).collect();
+        lines.push(format!("and {} more", items.len() - DESKTOP_TOASTS));
+        return vec![Toast {
+            summary: summary(items.len()),
+            body: lines.join("\n"),
+        }];
+    }
+    items
+        .iter()
+        .map(|item| Toast {
+            summary: summary(1),
+            body: line(item),
+        })
+        .collect()
+}
+
+/// Desktop notifications for the volumes a sync added and the prices it saw change.
+pub fn desktop_toasts(added: &[Volume], repriced: &[PriceChange]) -> Vec<Toast> {
+    let mut all = toasts(added, heading, |volume| match subtitle(volume).as_str() {
+        "" => volume.title.clone(),
+        subtitle => format!("{} ({})", volume.title, subtitle),
+    });
+    all.extend(toasts(
+        repriced,
+        |count| match count {
+            1 => "Library of America price change".to_string(),
+            n => format!("{} Library of America price changes", n),
+        },
+        |change| {
+            format!(
+                "{}: ${} → ${}",
+                change.volume.title, change.old_price, change.new_price
+            )
+        },
+    ));
+    all
+}
+
+/// Shows each toast through the platform's notification service.
+#[cfg(feature = "desktop")]
+pub fn show_toasts(toasts: &[Toast]) -> Result<()> {
+    for toast in toasts {
+        notify_rust::Notification::new()
+            .appname("loa-scraper")
+            .summary(&toast.summary)
+            .body(&toast.body)
+            .show()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use super::write_csv_records;
+use crate::Volume;
+use crate::scrape::SERIES_APS;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// What makes two volumes' authors the same person: the Wikidata ID when the volume
+/// was matched, otherwise the name as LOA prints it.
+pub(crate) fn author_identity(volume: &Volume) -> String {
+    match volume.author_wikidata_id.as_deref() {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _ => volume.author.clone(),
+    }
+}
+
+/// One author in the index.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthorIndexRow {
+    pub name: String,
+    pub wikipedia_link: String,
+    pub volume_count: usize,
+    /// Numbered volumes only ("APS 3" for the American Poets Project); unnumbered
+    /// publications still count toward `volume_count`.
+    pub volume_numbers: String,
+}
+
+/// Each author once, in order of their first volume, with the volumes they wrote.
+/// Anthologies and other volumes without an author are left out.
+pub fn author_index(volumes: &[Volume]) -> Vec<AuthorIndexRow> {
+    let volumes = crate::volume::dedupe(volumes);
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut rows: Vec<AuthorIndexRow> = Vec::new();
+    let mut numbers: Vec<Vec<String>> = Vec::new();
+    for volume in volumes.iter().filter(|v| !v.author.is_empty()) {
+        let pos = *positions.entry(author_identity(volume)).or_insert_with(|| {
+            rows.push(AuthorIndexRow {
+                name: volume.author.clone(),
+                wikipedia_link: String::new(),
+                volume_count: 0,
+                volume_numbers: String::new(),
+            });
+            numbers.push(Vec::new());
+            rows.len() - 1
+        });
+
+        let row = &mut rows[pos];
+        row.volume_count += 1;
+        if row.wikipedia_link.is_empty() {
+            row.wikipedia_link = volume.author_wikipedia_link.clone();
+        }
+        match (volume.volume_number, volume.series.as_str()) {
+            (0, _) => {}
+            (n, SERIES_APS) => numbers[pos].push(format!("APS {}", n)),
+            (n, _) => numbers[pos].push(n.to_string()),
+        }
+    }
+
+    for (row, numbers) in rows.iter_mut().zip(numbers) {
+        row.volume_numbers = numbers.join("; ");
+    }
+    rows
+}
+
+/// Writes [`author_index`] as CSV.
+pub fn write_author_index(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    write_csv_records(&author_index(volumes), out)
+}
@@ -0,0 +1,56 @@
+use super::{record_id, series_title, split_name};
+use crate::Volume;
+use crate::constants::metadata;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+
+fn csl_item(volume: &Volume) -> Value {
+    let mut item = Map::new();
+    item.insert("id".into(), json!(record_id(volume)));
+    item.insert("type".into(), json!("book"));
+    item.insert("title".into(), json!(volume.title));
+
+    if !volume.author.is_empty() {
+        let (given, family) = split_name(&volume.author);
+        let mut name = Map::new();
+        name.insert("family".into(), json!(family));
+        if let Some(given) = given {
+            name.insert("given".into(), json!(given));
+        }
+        item.insert("author".into(), json!([name]));
+    }
+
+    item.insert("publisher".into(), json!(metadata().publisher));
+    item.insert(
+        "publisher-place".into(),
+        json!(metadata().publication_place),
+    );
+    item.insert("collection-title".into(), json!(series_title(volume)));
+    if let Some(issn) = metadata().issn(volume) {
+        item.insert("ISSN".into(), json!(issn));
+    }
+    if volume.volume_number > 0 {
+        item.insert(
+            "collection-number".into(),
+            json!(volume.volume_number.to_string()),
+        );
+    }
+
+    if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+        item.insert("ISBN".into(), json!(isbn));
+    }
+    if !volume.loa_detail_link.is_empty() {
+        item.insert("URL".into(), json!(absolute_url(&volume.loa_detail_link)));
+    }
+
+    Value::Object(item)
+}
+
+pub fn write_csl_json(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let items: Vec<Value> = volumes.iter().map(csl_item).collect();
+    serde_json::to_writer_pretty(&mut *out, &items)?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::Volume;
+use crate::constants::metadata;
+use anyhow::Result;
+use csv::Writer;
+use serde::Serialize;
+use std::io::Write;
+
+/// The shelf every exported volume is placed on, so imports are easy to find.
+const SERIES_SHELF: &str = "library-of-america";
+const OWNED_SHELF: &str = "owned";
+
+#[derive(Serialize)]
+struct GoodreadsRow<'a> {
+    #[serde(rename = "Title")]
+    title: &'a str,
+    #[serde(rename = "Author")]
+    author: &'a str,
+    #[serde(rename = "ISBN13")]
+    isbn13: &'a str,
+    #[serde(rename = "Publisher")]
+    publisher: &'a str,
+    #[serde(rename = "Binding")]
+    binding: &'a str,
+    #[serde(rename = "Shelves")]
+    shelves: String,
+}
+
+pub fn write_goodreads(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let mut writer = Writer::from_writer(out);
+
+    for volume in volumes {
+        let mut shelves = vec![SERIES_SHELF];
+        if !volume.own_volume.is_empty() {
+            shelves.push(OWNED_SHELF);
+        }
+
+        writer.serialize(GoodreadsRow {
+            title: &volume.title,
+            author: &volume.author,
+            isbn13: volume.isbn.as_deref().unwrap_or(""),
+            publisher: &metadata().publisher,
+            binding: "Hardcover",
+            shelves: shelves.join(", "),
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
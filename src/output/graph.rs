@@ -0,0 +1,190 @@
+use super::{escape_xml, record_id};
+use crate::Volume;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+struct Node {
+    id: String,
+    label: String,
+    kind: &'static str,
+    owned: bool,
+}
+
+struct Edge {
+    source: String,
+    target: String,
+    relation: &'static str,
+}
+
+/// A bipartite author/volume graph. Authors link to the volumes they wrote, and to
+/// authorless collections (anthologies, series volumes) whose title names them.
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+fn author_id(index: usize) -> String {
+    format!("a{}", index)
+}
+
+/// Volume nodes take the volume's record ID, so series that share numbers and
+/// unnumbered publications don't collide.
+fn volume_id(volume: &Volume) -> String {
+    record_id(volume)
+}
+
+impl Graph {
+    fn from_volumes(volumes: &[Volume]) -> Self {
+        let mut authors: BTreeMap<&str, String> = BTreeMap::new();
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for volume in volumes.iter().filter(|v| !v.author.is_empty()) {
+            if !authors.contains_key(volume.author.as_str()) {
+                let id = author_id(authors.len());
+                nodes.push(Node {
+                    id: id.clone(),
+                    label: volume.author.clone(),
+                    kind: "author",
+                    owned: false,
+                });
+                authors.insert(&volume.author, id);
+            }
+        }
+
+        for volume in volumes {
+            let id = volume_id(volume);
+            let is_collection = volume.author.is_empty();
+            nodes.push(Node {
+                id: id.clone(),
+                label: volume.original_volume_name.clone(),
+                kind: if is_collection { "anthology" } else { "volume" },
+                owned: !volume.own_volume.is_empty(),
+            });
+
+            if let Some(author) = authors.get(volume.author.as_str()) {
+                edges.push(Edge {
+                    source: author.clone(),
+                    target: id,
+                    relation: "wrote",
+                });
+            } else if is_collection {
+                for (name, author) in &authors {
+                    if volume.original_volume_name.contains(name) {
+                        edges.push(Edge {
+                            source: author.clone(),
+                            target: id.clone(),
+                            relation: "appears_in",
+                        });
+                    }
+                }
+            }
+        }
+
+        Graph { nodes, edges }
+    }
+}
+
+pub fn write_graphml(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let graph = Graph::from_volumes(volumes);
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="owned" for="node" attr.name="owned" attr.type="boolean"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="relation" for="edge" attr.name="relation" attr.type="string"/>"#
+    )?;
+    writeln!(out, r#"  <graph id="loa" edgedefault="directed">"#)?;
+
+    for node in &graph.nodes {
+        writeln!(out, r#"    <node id="{}">"#, escape_xml(&node.id))?;
+        writeln!(
+            out,
+            r#"      <data key="label">{}</data>"#,
+            escape_xml(&node.label)
+        )?;
+        writeln!(out, r#"      <data key="kind">{}</data>"#, node.kind)?;
+        writeln!(out, r#"      <data key="owned">{}</data>"#, node.owned)?;
+        writeln!(out, "    </node>")?;
+    }
+
+    for edge in &graph.edges {
+        writeln!(
+            out,
+            r#"    <edge source="{}" target="{}"><data key="relation">{}</data></edge>"#,
+            escape_xml(&edge.source),
+            escape_xml(&edge.target),
+            edge.relation
+        )?;
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")?;
+    Ok(())
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn write_dot(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let graph = Graph::from_volumes(volumes);
+
+    writeln!(out, "digraph loa {{")?;
+    writeln!(out, "  rankdir=LR;")?;
+
+    for node in &graph.nodes {
+        let shape = match node.kind {
+            "author" => "ellipse",
+            "anthology" => "folder",
+            _ => "box",
+        };
+        let style = if node.owned {
+            ", style=filled, fillcolor=\"#eef6ea\""
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  \"{}\" [label=\"{}\", shape={}{}];",
+            escape_dot(&node.id),
+            escape_dot(&node.label),
+            shape,
+            style
+        )?;
+    }
+
+    for edge in &graph.edges {
+        let style = if edge.relation == "appears_in" {
+            " [style=dashed]"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\"{};",
+            escape_dot(&edge.source),
+            escape_dot(&edge.target),
+            style
+        )?;
+    }
+
+    writeln!(out, "}}")?;
+    Ok(())
+}
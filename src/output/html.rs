@@ -0,0 +1,234 @@
+use super::{OutputOptions, escape_xml};
+use crate::Volume;
+use crate::covers::inline_covers;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use std::io::Write;
+
+const STYLE: &str = r#"
+body { font-family: Georgia, serif; margin: 2rem; color: #222; background: #fafaf7; }
+h1 { font-weight: normal; margin-bottom: 0.25rem; }
+.controls { margin: 1rem 0; display: flex; gap: 1rem; align-items: center; }
+.controls input[type=search] { padding: 0.4rem; width: 20rem; }
+.visually-hidden { position: absolute; width: 1px; height: 1px; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap; }
+table { border-collapse: collapse; width: 100%; background: #fff; }
+caption { text-align: left; padding: 0.4rem 0; color: #555; }
+th, td { padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; text-align: left; vertical-align: middle; }
+th { background: #7a1f1f; color: #fff; position: sticky; top: 0; }
+th button { font: inherit; color: inherit; background: none; border: 0; padding: 0; cursor: pointer; }
+th[aria-sort=ascending] button::after { content: " \25B2"; }
+th[aria-sort=descending] button::after { content: " \25BC"; }
+td.cover img { height: 64px; display: block; }
+tr.owned { background: #eef6ea; }
+a { color: #7a1f1f; }
+a:focus-visible, input:focus-visible { outline: 2px solid #7a1f1f; outline-offset: 2px; }
+th button:focus-visible { outline: 2px solid #fff; outline-offset: 2px; }
+@media (prefers-color-scheme: dark) {
+  body { color: #eee; background: #1b1b1b; }
+  table { background: #242424; }
+  caption { color: #bbb; }
+  th, td { border-bottom-color: #444; }
+  tr.owned { background: #1f3a1f; }
+  a { color: #f2a7a7; }
+  a:focus-visible, input:focus-visible { outline-color: #f2a7a7; }
+}
+"#;
+
+/// With `print_optimized`: page numbers in the margin, the column headings repeated
+/// on every page, rows kept whole, and the screen-only controls and colors dropped.
+const PRINT_STYLE: &str = r#"
+@page {
+  margin: 1.5cm 1.2cm;
+  @bottom-right { content: "Page " counter(page) " of " counter(pages); font: 9pt Georgia, serif; }
+}
+@media print {
+  body { margin: 0; font-size: 10pt; color: #000; background: #fff; }
+  h1 { font-size: 16pt; break-after: avoid; }
+  .controls { display: none; }
+  table { background: #fff; }
+  thead { display: table-header-group; }
+  tr { break-inside: avoid; page-break-inside: avoid; }
+  th { position: static; color: #000; background: #fff; border-bottom: 2px solid #000; }
+  th[aria-sort] button::after { content: none; }
+  td { padding: 0.2rem 0.4rem; border-bottom-color: #999; }
+  td.cover img { height: 40px; }
+  tr.owned { background: #fff; }
+  tr.owned td:last-child { font-weight: bold; }
+  a { color: #000; text-decoration: none; }
+}
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var table = document.getElementById("volumes");
+  var tbody = table.tBodies[0];
+  var search = document.getElementById("search");
+  var missingOnly = document.getElementById("missing-only");
+  var count = document.getElementById("count");
+
+  function applyFilter() {
+    var query = search.value.toLowerCase();
+    var shown = 0;
+    Array.prototype.forEach.call(tbody.rows, function (row) {
+      var matches = row.textContent.toLowerCase().indexOf(query) !== -1;
+      var visible = matches && !(missingOnly.checked && row.classList.contains("owned"));
+      row.hidden = !visible;
+      if (visible) shown++;
+    });
+    count.textContent = shown + " volumes shown";
+  }
+
+  Array.prototype.forEach.call(table.tHead.rows[0].cells, function (th, index) {
+    if (!th.dataset.sort) return;
+    th.querySelector("button").addEventListener("click", function () {
+      var ascending = th.getAttribute("aria-sort") !== "ascending";
+      Array.prototype.forEach.call(th.parentNode.cells, function (cell) {
+        cell.removeAttribute("aria-sort");
+      });
+      th.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+      var numeric = th.dataset.sort === "number";
+      var rows = Array.prototype.slice.call(tbody.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[index].textContent.trim();
+        var y = b.cells[index].textContent.trim();
+        var cmp = numeric ? (parseFloat(x) || 0) - (parseFloat(y) || 0) : x.localeCompare(y);
+        return ascending ? cmp : -cmp;
+      });
+      rows.forEach(function (row) { tbody.appendChild(row); });
+    });
+  });
+
+  search.addEventListener("input", applyFilter);
+  missingOnly.addEventListener("change", applyFilter);
+  applyFilter();
+})();
+"#;
+
+/// A column heading; sortable ones hold a button so they can be sorted from the
+/// keyboard, and announce their order through `aria-sort`.
+fn header(label: &str, sort: Option<&str>) -> String {
+    match sort {
+        Some(kind) => format!(
+            "<th scope=\"col\" data-sort=\"{}\"><button type=\"button\">{}</button></th>",
+            kind, label
+        ),
+        None => format!("<th scope=\"col\">{}</th>", label),
+    }
+}
+
+fn link_or_text(text: &str, href: &str) -> String {
+    if href.is_empty() {
+        escape_xml(text)
+    } else {
+        format!("<a href=\"{}\">{}</a>", escape_xml(href), escape_xml(text))
+    }
+}
+
+/// The volumes with their covers inlined by `inline_covers`, for `self_contained`.
+fn with_inlined_covers(volumes: &[Volume]) -> Vec<Volume> {
+    let mut volumes = volumes.to_vec();
+    inline_covers(&mut volumes);
+    volumes
+}
+
+pub fn write_html(volumes: &[Volume], options: &OutputOptions, out: &mut dyn Write) -> Result<()> {
+    let inlined;
+    let volumes = if options.self_contained {
+        inlined = with_inlined_covers(volumes);
+        &inlined
+    } else {
+        volumes
+    };
+    let owned = volumes.iter().filter(|v| !v.own_volume.is_empty()).count();
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    )?;
+    writeln!(out, "<title>Library of America Volumes</title>")?;
+    writeln!(out, "<style>{}</style>", STYLE)?;
+    if options.print_optimized {
+        writeln!(out, "<style>{}</style>", PRINT_STYLE)?;
+    }
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<main>")?;
+    writeln!(out, "<h1>Library of America Volumes</h1>")?;
+    writeln!(out, "<p>{} volumes, {} owned</p>", volumes.len(), owned)?;
+    writeln!(out, "<div class=\"controls\">")?;
+    writeln!(
+        out,
+        "<label class=\"visually-hidden\" for=\"search\">Filter by title or author</label>"
+    )?;
+    writeln!(
+        out,
+        "<input type=\"search\" id=\"search\" placeholder=\"Filter by title or author\">"
+    )?;
+    writeln!(
+        out,
+        "<label><input type=\"checkbox\" id=\"missing-only\"> Missing only</label>"
+    )?;
+    writeln!(
+        out,
+        "<span id=\"count\" role=\"status\" aria-live=\"polite\"></span>"
+    )?;
+    writeln!(out, "</div>")?;
+    writeln!(out, "<table id=\"volumes\">")?;
+    writeln!(
+        out,
+        "<caption>Volumes in the catalog. Select a column heading to sort by it.</caption>"
+    )?;
+    writeln!(
+        out,
+        "<thead><tr>{}{}{}{}{}</tr></thead>",
+        header("Cover", None),
+        header("#", Some("number")),
+        header("Title", Some("text")),
+        header("Author", Some("text")),
+        header("Owned", Some("text"))
+    )?;
+    writeln!(out, "<tbody>")?;
+
+    for volume in volumes {
+        let cover = if volume.cover_image_link.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "<img src=\"{}\" alt=\"Cover of {}\" loading=\"lazy\">",
+                escape_xml(&volume.cover_image_link),
+                escape_xml(&volume.title)
+            )
+        };
+        let row_class = if volume.own_volume.is_empty() {
+            ""
+        } else {
+            " class=\"owned\""
+        };
+
+        writeln!(
+            out,
+            "<tr{}><td class=\"cover\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            row_class,
+            cover,
+            match volume.volume_number {
+                0 => String::new(),
+                n => n.to_string(),
+            },
+            link_or_text(&volume.title, &absolute_url(&volume.loa_detail_link)),
+            link_or_text(&volume.author, &volume.author_wikipedia_link),
+            escape_xml(&volume.own_volume)
+        )?;
+    }
+
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "</table>")?;
+    writeln!(out, "</main>")?;
+    writeln!(out, "<script>{}</script>", SCRIPT)?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::series_title;
+use crate::Volume;
+use crate::constants::metadata;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+
+fn book(volume: &Volume) -> Value {
+    let mut book = Map::new();
+    let url = absolute_url(&volume.loa_detail_link);
+
+    book.insert("@type".into(), json!("Book"));
+    if !url.is_empty() {
+        book.insert("@id".into(), json!(url));
+        book.insert("url".into(), json!(url));
+    }
+    book.insert("name".into(), json!(volume.title));
+
+    if !volume.author.is_empty() {
+        let mut author = Map::new();
+        author.insert("@type".into(), json!("Person"));
+        author.insert("name".into(), json!(volume.author));
+        if !volume.author_wikipedia_link.is_empty() {
+            author.insert("sameAs".into(), json!(volume.author_wikipedia_link));
+        }
+        book.insert("author".into(), Value::Object(author));
+    }
+
+    if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+        book.insert("isbn".into(), json!(isbn));
+    }
+    book.insert(
+        "bookEdition".into(),
+        json!(format!("{} edition", series_title(volume))),
+    );
+    book.insert(
+        "publisher".into(),
+        json!({ "@type": "Organization", "name": metadata().publisher }),
+    );
+    let mut series = json!({ "@type": "BookSeries", "name": series_title(volume) });
+    if let Some(issn) = metadata().issn(volume) {
+        series["issn"] = json!(issn);
+    }
+    book.insert("isPartOf".into(), series);
+    if volume.volume_number > 0 {
+        book.insert("position".into(), json!(volume.volume_number));
+    }
+    if !volume.cover_image_link.is_empty() {
+        book.insert("image".into(), json!(volume.cover_image_link));
+    }
+
+    Value::Object(book)
+}
+
+pub fn write_jsonld(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let document = json!({
+        "@context": "https://schema.org",
+        "@graph": volumes.iter().map(book).collect::<Vec<_>>(),
+    });
+    serde_json::to_writer_pretty(&mut *out, &document)?;
+    writeln!(out)?;
+    Ok(())
+}
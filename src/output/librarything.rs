@@ -0,0 +1,57 @@
+use super::{inverted_name, series_title};
+use crate::Volume;
+use crate::constants::metadata;
+use anyhow::Result;
+use csv::Writer;
+use serde::Serialize;
+use std::io::Write;
+
+/// Column names LibraryThing's universal import recognizes (they match its own export).
+#[derive(Serialize)]
+struct LibraryThingRow<'a> {
+    #[serde(rename = "Title")]
+    title: &'a str,
+    #[serde(rename = "Primary Author")]
+    primary_author: String,
+    #[serde(rename = "ISBNs")]
+    isbns: &'a str,
+    #[serde(rename = "Publication")]
+    publication: String,
+    #[serde(rename = "Series")]
+    series: String,
+    #[serde(rename = "Tags")]
+    tags: &'a str,
+    #[serde(rename = "Collections")]
+    collections: &'a str,
+}
+
+pub fn write_librarything(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let mut writer = Writer::from_writer(out);
+
+    for volume in volumes {
+        writer.serialize(LibraryThingRow {
+            title: &volume.title,
+            primary_author: inverted_name(&volume.author),
+            isbns: volume.isbn.as_deref().unwrap_or(""),
+            publication: format!(
+                "{} : {}",
+                metadata().publication_place,
+                metadata().publisher
+            ),
+            series: match volume.volume_number {
+                0 => series_title(volume).to_string(),
+                number => format!("{} ; {}", series_title(volume), number),
+            },
+            tags: "library-of-america",
+            // "Your library" and "Wishlist" are LibraryThing's built-in collections
+            collections: if volume.own_volume.is_empty() {
+                "Wishlist"
+            } else {
+                "Your library"
+            },
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use super::{escape_xml, inverted_name, record_id, series_title};
+use crate::Volume;
+use crate::constants::metadata;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use std::io::Write;
+
+/// Number of leading characters a catalog should skip when filing the title.
+fn nonfiling_characters(title: &str) -> usize {
+    ["The ", "An ", "A "]
+        .iter()
+        .find(|article| title.starts_with(*article))
+        .map_or(0, |article| article.len())
+}
+
+fn write_datafield(
+    out: &mut dyn Write,
+    tag: &str,
+    ind1: char,
+    ind2: char,
+    subfields: &[(char, &str)],
+) -> Result<()> {
+    writeln!(
+        out,
+        "    <datafield tag=\"{}\" ind1=\"{}\" ind2=\"{}\">",
+        tag, ind1, ind2
+    )?;
+    for (code, value) in subfields {
+        writeln!(
+            out,
+            "      <subfield code=\"{}\">{}</subfield>",
+            code,
+            escape_xml(value)
+        )?;
+    }
+    writeln!(out, "    </datafield>")?;
+    Ok(())
+}
+
+fn write_record(volume: &Volume, out: &mut dyn Write) -> Result<()> {
+    // Unnumbered publications are in the series but have no number in it
+    let number = (volume.volume_number > 0).then(|| volume.volume_number.to_string());
+    let series = series_title(volume);
+    let has_author = !volume.author.is_empty();
+
+    writeln!(out, "  <record>")?;
+    writeln!(out, "    <leader>00000nam a2200000 i 4500</leader>")?;
+    let control_number = match number {
+        Some(_) => format!(
+            "{}{:03}",
+            if volume.series.is_empty() {
+                "loa"
+            } else {
+                &volume.series
+            },
+            volume.volume_number
+        ),
+        None => record_id(volume),
+    };
+    writeln!(
+        out,
+        "    <controlfield tag=\"001\">{}</controlfield>",
+        escape_xml(&control_number)
+    )?;
+
+    if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+        write_datafield(out, "020", ' ', ' ', &[('a', isbn)])?;
+    }
+
+    if has_author {
+        write_datafield(
+            out,
+            "100",
+            '1',
+            ' ',
+            &[('a', &inverted_name(&volume.author))],
+        )?;
+    }
+
+    let nonfiling = nonfiling_characters(&volume.title).to_string();
+    write_datafield(
+        out,
+        "245",
+        if has_author { '1' } else { '0' },
+        nonfiling.chars().next().unwrap_or('0'),
+        &[('a', &volume.title)],
+    )?;
+    write_datafield(
+        out,
+        "264",
+        ' ',
+        '1',
+        &[
+            ('a', &metadata().publication_place),
+            ('b', &metadata().publisher),
+        ],
+    )?;
+    let mut statement = vec![('a', series)];
+    if let Some(issn) = metadata().issn(volume) {
+        statement.push(('x', issn));
+    }
+    let mut entry = vec![('a', series)];
+    if let Some(number) = &number {
+        statement.push(('v', number));
+        entry.push(('v', number));
+    }
+    write_datafield(out, "490", '1', ' ', &statement)?;
+    write_datafield(out, "830", ' ', '0', &entry)?;
+
+    if !volume.loa_detail_link.is_empty() {
+        write_datafield(
+            out,
+            "856",
+            '4',
+            '2',
+            &[('u', &absolute_url(&volume.loa_detail_link))],
+        )?;
+    }
+
+    writeln!(out, "  </record>")?;
+    Ok(())
+}
+
+pub fn write_marcxml(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out, "<collection xmlns=\"http://www.loc.gov/MARC21/slim\">")?;
+    for volume in volumes {
+        write_record(volume, out)?;
+    }
+    writeln!(out, "</collection>")?;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use super::{Table, is_url};
+use crate::Volume;
+use anyhow::Result;
+use std::io::Write;
+
+fn markdown_cell(cell: &str) -> String {
+    let escaped = cell.replace('|', "\\|").replace('\n', " ");
+    if is_url(cell) {
+        // Use the host as link text so tables stay narrow
+        let host = cell.split('/').nth(2).unwrap_or(cell);
+        format!("[{}]({})", host, cell)
+    } else {
+        escaped
+    }
+}
+
+pub fn write_markdown(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let table = Table::from_volumes(volumes)?;
+
+    writeln!(out, "| {} |", table.headers.join(" | "))?;
+    writeln!(
+        out,
+        "|{}|",
+        table
+            .headers
+            .iter()
+            .map(|_| " --- ")
+            .collect::<Vec<_>>()
+            .join("|")
+    )?;
+
+    for row in &table.rows {
+        let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,371 @@
+use crate::Volume;
+use crate::constants::metadata;
+use crate::sanity::comparable_count;
+use crate::scrape::ScrapeOptions;
+use crate::volume::KEY_COLUMNS;
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+mod authors;
+mod csl;
+mod goodreads;
+mod graph;
+mod html;
+mod jsonld;
+mod librarything;
+mod marcxml;
+mod markdown;
+mod opds;
+pub mod protobuf;
+mod relational;
+mod ris;
+mod sink;
+mod storygraph;
+mod timeline;
+mod turtle;
+mod xlsx;
+
+pub(crate) use authors::author_identity;
+pub use authors::{AuthorIndexRow, author_index, write_author_index};
+pub(crate) use sink::block_on;
+pub use sink::{OutputSink, OutputTarget, format_for_path, write_all};
+pub use timeline::{TimelineEntry, timeline_entries, write_timeline_html};
+
+/// A citation ID that stays unique across series, e.g. `loa-1`, `aps-1`, or
+/// `loa-poems-of-emily-dickinson` for an unnumbered publication.
+pub(crate) fn record_id(volume: &Volume) -> String {
+    if volume.volume_number > 0 {
+        return volume.key();
+    }
+    let slug = volume
+        .loa_detail_link
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    format!("loa-{}", slug)
+}
+
+/// The series title a volume was published in, for formats that record it.
+pub(crate) fn series_title(volume: &Volume) -> &'static str {
+    metadata().series_title(volume)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Csv,
+    Xlsx,
+    Sqlite,
+    Markdown,
+    Html,
+    Protobuf,
+    Marcxml,
+    CslJson,
+    Ris,
+    Jsonld,
+    Opds,
+    Goodreads,
+    Storygraph,
+    Librarything,
+    Graphml,
+    Dot,
+    Turtle,
+    Json,
+    Relational,
+    Duckdb,
+}
+
+/// Column names and stringified cell values for a set of records, in field order.
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn from_volumes(volumes: &[Volume]) -> Result<Self> {
+        Self::from_records(volumes)
+    }
+
+    /// Builds a table from any records that serialize to flat objects, such as
+    /// `Volume`, `Author`, `Work`, or `Ownership`.
+    pub fn from_records<T: Serialize>(records: &[T]) -> Result<Self> {
+        let objects = records
+            .iter()
+            .map(|record| match serde_json::to_value(record)? {
+                serde_json::Value::Object(object) => Ok(object),
+                _ => Err(anyhow!("Record did not serialize to an object")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Optional fields can be present on some records only (e.g. volumes from the
+        // sync store), so merge every record's columns while keeping field order.
+        let mut headers: Vec<String> = Vec::new();
+        for object in &objects {
+            let mut insert_at = 0;
+            for key in object.keys() {
+                match headers.iter().position(|h| h == key) {
+                    Some(pos) => insert_at = pos + 1,
+                    None => {
+                        headers.insert(insert_at, key.clone());
+                        insert_at += 1;
+                    }
+                }
+            }
+        }
+
+        let rows = objects
+            .iter()
+            .map(|object| {
+                headers
+                    .iter()
+                    .map(|header| match object.get(header) {
+                        Some(serde_json::Value::String(s)) => s.clone(),
+                        None | Some(serde_json::Value::Null) => String::new(),
+                        Some(other) => other.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Table { headers, rows })
+    }
+}
+
+/// Columns that describe the user rather than the catalog: what they own, where their
+/// files live, and when they ran `sync`.
+pub const PERSONAL_COLUMNS: &[&str] = &["own_volume", "cover_path", "first_seen", "last_seen"];
+
+/// Strips the named columns before sharing an export: optional fields are dropped
+/// entirely and always-present ones (like `own_volume`) are emptied. Returns the names
+/// no volume had.
+pub fn redact(volumes: &mut [Volume], columns: &[String]) -> Result<Vec<String>> {
+    if let Some(key) = columns.iter().find(|c| KEY_COLUMNS.contains(&c.as_str())) {
+        return Err(anyhow!("{} identifies each row and can't be redacted", key));
+    }
+
+    let required = match serde_json::to_value(Volume::default())? {
+        serde_json::Value::Object(fields) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let mut found = HashSet::new();
+    for volume in volumes.iter_mut() {
+        let serde_json::Value::Object(mut object) = serde_json::to_value(&*volume)? else {
+            return Err(anyhow!("Volume did not serialize to an object"));
+        };
+        for column in columns {
+            if object.remove(column).is_some() {
+                found.insert(column.as_str());
+                if let Some(empty) = required.get(column) {
+                    object.insert(column.clone(), empty.clone());
+                }
+            }
+        }
+        *volume = serde_json::from_value(serde_json::Value::Object(object))?;
+    }
+
+    Ok(columns
+        .iter()
+        .filter(|c| !found.contains(c.as_str()))
+        .cloned()
+        .collect())
+}
+
+fn open_output(output: Option<&str>) -> Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    })
+}
+
+/// Switches for individual formats; the others ignore them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutputOptions {
+    /// HTML: lay the report out for printing, with repeated headers and page numbers.
+    pub print_optimized: bool,
+    /// HTML: embed the covers on disk at `cover_path` as `data:` URIs so the file works
+    /// offline. Covers are never fetched; those not downloaded are left out.
+    pub self_contained: bool,
+}
+
+pub fn write_volumes(volumes: &[Volume], format: OutputFormat, output: Option<&str>) -> Result<()> {
+    write_volumes_with(volumes, format, output, &OutputOptions::default())
+}
+
+pub fn write_volumes_with(
+    volumes: &[Volume],
+    format: OutputFormat,
+    output: Option<&str>,
+    options: &OutputOptions,
+) -> Result<()> {
+    let volumes = &crate::volume::dedupe(volumes);
+    if let Some(url) = output.filter(|o| is_postgres_url(o)) {
+        #[cfg(feature = "postgres")]
+        return crate::postgres::write_postgres(volumes, url);
+        #[cfg(not(feature = "postgres"))]
+        return Err(anyhow!(
+            "Can't write to {}: built without the postgres feature",
+            url
+        ));
+    }
+    if format == OutputFormat::Sqlite {
+        let path = output.ok_or_else(|| anyhow!("--format sqlite requires --output <FILE>"))?;
+        return crate::sqlite::write_sqlite(volumes, path);
+    }
+    if format == OutputFormat::Duckdb {
+        let path = output.ok_or_else(|| anyhow!("--format duckdb requires --output <FILE>"))?;
+        #[cfg(feature = "duckdb")]
+        return crate::duckdb::write_duckdb(volumes, path);
+        #[cfg(not(feature = "duckdb"))]
+        return Err(anyhow!(
+            "Can't write {}: built without the duckdb feature",
+            path
+        ));
+    }
+    if format == OutputFormat::Relational {
+        let dir = output.ok_or_else(|| anyhow!("--format relational requires --output <DIR>"))?;
+        return relational::write_relational(volumes, dir);
+    }
+
+    let mut out = open_output(output)?;
+
+    match format {
+        OutputFormat::Csv => write_csv(volumes, &mut out)?,
+        OutputFormat::Xlsx => xlsx::write_xlsx(volumes, &mut out)?,
+        OutputFormat::Markdown => markdown::write_markdown(volumes, &mut out)?,
+        OutputFormat::Html => html::write_html(volumes, options, &mut out)?,
+        OutputFormat::Protobuf => protobuf::write_protobuf(volumes, &mut out)?,
+        OutputFormat::Marcxml => marcxml::write_marcxml(volumes, &mut out)?,
+        OutputFormat::CslJson => csl::write_csl_json(volumes, &mut out)?,
+        OutputFormat::Ris => ris::write_ris(volumes, &mut out)?,
+        OutputFormat::Jsonld => jsonld::write_jsonld(volumes, &mut out)?,
+        OutputFormat::Opds => opds::write_opds(volumes, &mut out)?,
+        OutputFormat::Goodreads => goodreads::write_goodreads(volumes, &mut out)?,
+        OutputFormat::Storygraph => storygraph::write_storygraph(volumes, &mut out)?,
+        OutputFormat::Librarything => librarything::write_librarything(volumes, &mut out)?,
+        OutputFormat::Graphml => graph::write_graphml(volumes, &mut out)?,
+        OutputFormat::Dot => graph::write_dot(volumes, &mut out)?,
+        OutputFormat::Turtle => turtle::write_turtle(volumes, &mut out)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, volumes)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Sqlite => unreachable!("SQLite output is written directly to a file"),
+        OutputFormat::Relational => unreachable!("Relational output is written to a directory"),
+        OutputFormat::Duckdb => unreachable!("DuckDB output is written directly to a file"),
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// The columns of a CSV row that decide whether it's counted, so outputs with other
+/// columns redacted still count.
+#[derive(Deserialize)]
+struct CountedRow {
+    #[serde(default)]
+    volume_number: u32,
+    #[serde(default)]
+    series: String,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+/// Counts the volumes already in an existing output file that a scrape with `options`
+/// is comparable with (see [`comparable_count`]), for formats we can read back.
+pub fn existing_volume_count(
+    path: &str,
+    format: OutputFormat,
+    options: &ScrapeOptions,
+) -> Option<usize> {
+    if !Path::new(path).exists() {
+        return None;
+    }
+
+    let volumes = match format {
+        OutputFormat::Csv => {
+            let mut reader = csv::Reader::from_path(path).ok()?;
+            reader
+                .deserialize::<CountedRow>()
+                .filter_map(|row| row.ok())
+                .map(|row| Volume {
+                    volume_number: row.volume_number,
+                    series: row.series,
+                    status: row.status.filter(|s| !s.is_empty()),
+                    ..Default::default()
+                })
+                .collect()
+        }
+        OutputFormat::Sqlite => crate::sqlite::read_sqlite(path).ok()?,
+        OutputFormat::Json => crate::input::read_volumes(Path::new(path)).ok()?,
+        _ => return None,
+    };
+    Some(comparable_count(&volumes, options))
+}
+
+fn write_csv(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    write_csv_records(volumes, out)
+}
+
+/// Writes records as CSV with one column per field that any record carries.
+pub fn write_csv_records<T: Serialize>(records: &[T], out: &mut dyn Write) -> Result<()> {
+    let table = Table::from_records(records)?;
+    let mut writer = Writer::from_writer(out);
+    // With no records there are no columns, and an empty header record would be `""`
+    if !table.headers.is_empty() {
+        writer.write_record(&table.headers)?;
+    }
+    for row in &table.rows {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Whether `--output` names a PostgreSQL database rather than a file.
+pub fn is_postgres_url(output: &str) -> bool {
+    output.starts_with("postgres://") || output.starts_with("postgresql://")
+}
+
+pub(crate) fn is_url(cell: &str) -> bool {
+    cell.starts_with("http://") || cell.starts_with("https://")
+}
+
+/// Splits "Herman Melville" into given ("Herman") and family ("Melville") names.
+/// Single-word names are treated as family-only.
+pub(crate) fn split_name(author: &str) -> (Option<String>, String) {
+    let words: Vec<&str> = author.split_whitespace().collect();
+    match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() => (Some(rest.join(" ")), last.to_string()),
+        _ => (None, author.trim().to_string()),
+    }
+}
+
+/// Converts "Herman Melville" to the inverted "Melville, Herman" heading form.
+pub(crate) fn inverted_name(author: &str) -> String {
+    match split_name(author) {
+        (Some(given), family) => format!("{}, {}", family, given),
+        (None, family) => family,
+    }
+}
+
+/// Escapes text for inclusion in HTML or XML content and attribute values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
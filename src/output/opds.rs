@@ -0,0 +1,118 @@
+use super::{escape_xml, record_id, series_title};
+use crate::Volume;
+use crate::constants::metadata;
+use crate::scrape::{COLLECTION_URL, absolute_url};
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use std::io::Write;
+
+const ACQUISITION_FEED_TYPE: &str = "application/atom+xml;profile=opds-catalog;kind=acquisition";
+
+fn image_type(url: &str) -> &'static str {
+    let lower = url.to_ascii_lowercase();
+    if lower.ends_with(".png") {
+        "image/png"
+    } else if lower.ends_with(".gif") {
+        "image/gif"
+    } else if lower.ends_with(".webp") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    }
+}
+
+fn write_entry(volume: &Volume, updated: &str, out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "  <entry>")?;
+    writeln!(out, "    <title>{}</title>", escape_xml(&volume.title))?;
+    writeln!(
+        out,
+        "    <id>urn:loa:volume:{}</id>",
+        escape_xml(&record_id(volume))
+    )?;
+    writeln!(out, "    <updated>{}</updated>", updated)?;
+
+    if !volume.author.is_empty() {
+        writeln!(out, "    <author>")?;
+        writeln!(out, "      <name>{}</name>", escape_xml(&volume.author))?;
+        if !volume.author_wikipedia_link.is_empty() {
+            writeln!(
+                out,
+                "      <uri>{}</uri>",
+                escape_xml(&volume.author_wikipedia_link)
+            )?;
+        }
+        writeln!(out, "    </author>")?;
+    }
+
+    if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+        writeln!(out, "    <dc:identifier>urn:isbn:{}</dc:identifier>", isbn)?;
+    }
+    writeln!(
+        out,
+        "    <dc:publisher>{}</dc:publisher>",
+        escape_xml(&metadata().publisher)
+    )?;
+    let summary = if volume.volume_number > 0 {
+        format!("{} volume {}", series_title(volume), volume.volume_number)
+    } else {
+        format!("Published by {}", metadata().publisher)
+    };
+    writeln!(out, "    <summary>{}</summary>", escape_xml(&summary))?;
+
+    if !volume.cover_image_link.is_empty() {
+        let cover = escape_xml(&volume.cover_image_link);
+        let kind = image_type(&volume.cover_image_link);
+        writeln!(
+            out,
+            "    <link rel=\"http://opds-spec.org/image\" href=\"{}\" type=\"{}\"/>",
+            cover, kind
+        )?;
+        writeln!(
+            out,
+            "    <link rel=\"http://opds-spec.org/image/thumbnail\" href=\"{}\" type=\"{}\"/>",
+            cover, kind
+        )?;
+    }
+
+    if !volume.loa_detail_link.is_empty() {
+        writeln!(
+            out,
+            "    <link rel=\"http://opds-spec.org/acquisition/buy\" href=\"{}\" type=\"text/html\"/>",
+            escape_xml(&absolute_url(&volume.loa_detail_link))
+        )?;
+    }
+
+    writeln!(out, "  </entry>")?;
+    Ok(())
+}
+
+pub fn write_opds(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let updated = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        out,
+        "<feed xmlns=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/terms/\" xmlns:opds=\"http://opds-spec.org/2010/catalog\">"
+    )?;
+    writeln!(out, "  <id>urn:loa-scraper:collection</id>")?;
+    writeln!(out, "  <title>Library of America</title>")?;
+    writeln!(out, "  <updated>{}</updated>", updated)?;
+    writeln!(
+        out,
+        "  <author><name>{}</name></author>",
+        escape_xml(&metadata().publisher)
+    )?;
+    writeln!(
+        out,
+        "  <link rel=\"start\" href=\"{}\" type=\"{}\"/>",
+        escape_xml(COLLECTION_URL),
+        ACQUISITION_FEED_TYPE
+    )?;
+
+    for volume in volumes {
+        write_entry(volume, &updated, out)?;
+    }
+
+    writeln!(out, "</feed>")?;
+    Ok(())
+}
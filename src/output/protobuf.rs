@@ -0,0 +1,199 @@
+//! Messages mirroring `proto/loa.proto`. Field tags must stay in sync with the schema.
+
+use crate::Volume;
+use anyhow::Result;
+use prost::Message;
+use std::io::Write;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct VolumeMessage {
+    #[prost(uint32, tag = "1")]
+    pub volume_number: u32,
+    #[prost(string, tag = "2")]
+    pub title: String,
+    #[prost(string, tag = "3")]
+    pub author: String,
+    #[prost(string, tag = "4")]
+    pub author_wikipedia_link: String,
+    #[prost(string, tag = "5")]
+    pub loa_detail_link: String,
+    #[prost(string, tag = "6")]
+    pub original_volume_name: String,
+    #[prost(string, tag = "7")]
+    pub own_volume: String,
+    #[prost(string, tag = "8")]
+    pub cover_image_link: String,
+    #[prost(string, optional, tag = "9")]
+    pub cover_path: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    pub isbn: Option<String>,
+    #[prost(string, optional, tag = "11")]
+    pub raw_listing_html: Option<String>,
+    #[prost(string, optional, tag = "12")]
+    pub publication_year: Option<String>,
+    #[prost(string, optional, tag = "13")]
+    pub author_wikidata_id: Option<String>,
+    #[prost(string, optional, tag = "14")]
+    pub author_birth_date: Option<String>,
+    #[prost(string, optional, tag = "15")]
+    pub author_death_date: Option<String>,
+    #[prost(string, optional, tag = "16")]
+    pub archived_detail_link: Option<String>,
+    #[prost(string, optional, tag = "17")]
+    pub enrichment_status: Option<String>,
+    #[prost(string, optional, tag = "18")]
+    pub openlibrary_work_id: Option<String>,
+    #[prost(string, optional, tag = "19")]
+    pub openlibrary_edition_id: Option<String>,
+    #[prost(string, optional, tag = "20")]
+    pub openlibrary_cover_url: Option<String>,
+    #[prost(string, optional, tag = "21")]
+    pub oclc_number: Option<String>,
+    #[prost(string, optional, tag = "22")]
+    pub lccn: Option<String>,
+    #[prost(string, optional, tag = "23")]
+    pub loc_permalink: Option<String>,
+    #[prost(string, optional, tag = "24")]
+    pub author_viaf_id: Option<String>,
+    #[prost(string, optional, tag = "25")]
+    pub rating_average: Option<String>,
+    #[prost(string, optional, tag = "26")]
+    pub ratings_count: Option<String>,
+    #[prost(string, optional, tag = "27")]
+    pub price_usd: Option<String>,
+    #[prost(string, optional, tag = "28")]
+    pub member_price_usd: Option<String>,
+    #[prost(string, optional, tag = "29")]
+    pub availability: Option<String>,
+    #[prost(string, optional, tag = "30")]
+    pub in_print: Option<String>,
+    #[prost(string, optional, tag = "31")]
+    pub status: Option<String>,
+    #[prost(string, optional, tag = "32")]
+    pub release_date: Option<String>,
+    #[prost(string, tag = "33")]
+    pub series: String,
+    #[prost(string, optional, tag = "34")]
+    pub delisted_on: Option<String>,
+    #[prost(string, optional, tag = "35")]
+    pub publication_type: Option<String>,
+    #[prost(string, optional, tag = "36")]
+    pub first_seen: Option<String>,
+    #[prost(string, optional, tag = "37")]
+    pub last_seen: Option<String>,
+    #[prost(string, optional, tag = "38")]
+    pub previous_price_usd: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct VolumeList {
+    #[prost(message, repeated, tag = "1")]
+    pub volumes: Vec<VolumeMessage>,
+}
+
+impl From<&Volume> for VolumeMessage {
+    fn from(volume: &Volume) -> Self {
+        VolumeMessage {
+            volume_number: volume.volume_number,
+            title: volume.title.clone(),
+            author: volume.author.clone(),
+            author_wikipedia_link: volume.author_wikipedia_link.clone(),
+            loa_detail_link: volume.loa_detail_link.clone(),
+            original_volume_name: volume.original_volume_name.clone(),
+            own_volume: volume.own_volume.clone(),
+            cover_image_link: volume.cover_image_link.clone(),
+            cover_path: volume.cover_path.clone(),
+            isbn: volume.isbn.clone(),
+            raw_listing_html: volume.raw_listing_html.clone(),
+            publication_year: volume.publication_year.clone(),
+            author_wikidata_id: volume.author_wikidata_id.clone(),
+            author_birth_date: volume.author_birth_date.clone(),
+            author_death_date: volume.author_death_date.clone(),
+            archived_detail_link: volume.archived_detail_link.clone(),
+            enrichment_status: volume.enrichment_status.clone(),
+            openlibrary_work_id: volume.openlibrary_work_id.clone(),
+            openlibrary_edition_id: volume.openlibrary_edition_id.clone(),
+            openlibrary_cover_url: volume.openlibrary_cover_url.clone(),
+            oclc_number: volume.oclc_number.clone(),
+            lccn: volume.lccn.clone(),
+            loc_permalink: volume.loc_permalink.clone(),
+            author_viaf_id: volume.author_viaf_id.clone(),
+            rating_average: volume.rating_average.clone(),
+            ratings_count: volume.ratings_count.clone(),
+            price_usd: volume.price_usd.clone(),
+            member_price_usd: volume.member_price_usd.clone(),
+            availability: volume.availability.clone(),
+            in_print: volume.in_print.clone(),
+            status: volume.status.clone(),
+            release_date: volume.release_date.clone(),
+            series: volume.series.clone(),
+            delisted_on: volume.delisted_on.clone(),
+            publication_type: volume.publication_type.clone(),
+            first_seen: volume.first_seen.clone(),
+            last_seen: volume.last_seen.clone(),
+            previous_price_usd: volume.previous_price_usd.clone(),
+        }
+    }
+}
+
+impl From<VolumeMessage> for Volume {
+    fn from(message: VolumeMessage) -> Self {
+        Volume {
+            volume_number: message.volume_number,
+            title: message.title,
+            author: message.author,
+            author_wikipedia_link: message.author_wikipedia_link,
+            loa_detail_link: message.loa_detail_link,
+            original_volume_name: message.original_volume_name,
+            own_volume: message.own_volume,
+            cover_image_link: message.cover_image_link,
+            cover_path: message.cover_path,
+            isbn: message.isbn,
+            raw_listing_html: message.raw_listing_html,
+            publication_year: message.publication_year,
+            author_wikidata_id: message.author_wikidata_id,
+            author_birth_date: message.author_birth_date,
+            author_death_date: message.author_death_date,
+            archived_detail_link: message.archived_detail_link,
+            enrichment_status: message.enrichment_status,
+            openlibrary_work_id: message.openlibrary_work_id,
+            openlibrary_edition_id: message.openlibrary_edition_id,
+            openlibrary_cover_url: message.openlibrary_cover_url,
+            oclc_number: message.oclc_number,
+            lccn: message.lccn,
+            loc_permalink: message.loc_permalink,
+            author_viaf_id: message.author_viaf_id,
+            rating_average: message.rating_average,
+            ratings_count: message.ratings_count,
+            price_usd: message.price_usd,
+            member_price_usd: message.member_price_usd,
+            availability: message.availability,
+            in_print: message.in_print,
+            status: message.status,
+            release_date: message.release_date,
+            series: message.series,
+            delisted_on: message.delisted_on,
+            publication_type: message.publication_type,
+            first_seen: message.first_seen,
+            last_seen: message.last_seen,
+            previous_price_usd: message.previous_price_usd,
+        }
+    }
+}
+
+pub fn write_protobuf(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let list = VolumeList {
+        volumes: volumes.iter().map(VolumeMessage::from).collect(),
+    };
+    out.write_all(&list.encode_to_vec())?;
+    Ok(())
+}
+
+/// Reads a `--format protobuf` export back into volumes.
+pub fn read_protobuf(bytes: &[u8]) -> Result<Vec<Volume>> {
+    Ok(VolumeList::decode(bytes)?
+        .volumes
+        .into_iter()
+        .map(Volume::from)
+        .collect())
+}
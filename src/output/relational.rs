@@ -0,0 +1,108 @@
+use super::authors::author_identity;
+use super::{Table, write_csv_records};
+use crate::volume::UPSERT_KEY;
+use crate::{Author, Volume};
+use anyhow::{Context, Result};
+use csv::Writer;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+/// Author fields that move to `authors.csv` instead of repeating on every volume.
+const AUTHOR_COLUMNS: &[&str] = &[
+    "author",
+    "author_wikipedia_link",
+    "author_wikidata_id",
+    "author_viaf_id",
+    "author_birth_date",
+    "author_death_date",
+];
+
+#[derive(Serialize)]
+struct AuthorRow {
+    author_id: usize,
+    #[serde(flatten)]
+    author: Author,
+}
+
+#[derive(Serialize)]
+struct VolumeAuthorRow {
+    row_key: String,
+    author_id: usize,
+}
+
+/// Deduplicates authors and numbers them in order of first appearance.
+fn authors(volumes: &[Volume]) -> (Vec<AuthorRow>, Vec<VolumeAuthorRow>) {
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut authors = Vec::new();
+    let mut links = Vec::new();
+    for volume in volumes {
+        let Some(author) = Author::from_volume(volume) else {
+            continue;
+        };
+        let author_id = *ids.entry(author_identity(volume)).or_insert_with(|| {
+            authors.push(AuthorRow {
+                author_id: authors.len() + 1,
+                author,
+            });
+            authors.len()
+        });
+        links.push(VolumeAuthorRow {
+            row_key: volume.key(),
+            author_id,
+        });
+    }
+    (authors, links)
+}
+
+/// Writes `volumes.csv`, `authors.csv`, and the `volume_authors.csv` join table into
+/// `dir`. Volumes are keyed by `row_key` (as in SQLite output) and authors by a
+/// numeric `author_id`.
+pub fn write_relational(volumes: &[Volume], dir: &str) -> Result<()> {
+    let dir = Path::new(dir);
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+
+    let table = Table::from_volumes(volumes)?;
+    let kept: Vec<usize> = (0..table.headers.len())
+        .filter(|&i| !AUTHOR_COLUMNS.contains(&table.headers[i].as_str()))
+        .collect();
+    let mut writer = Writer::from_writer(File::create(dir.join("volumes.csv"))?);
+    writer.write_record(
+        std::iter::once(UPSERT_KEY).chain(kept.iter().map(|&i| table.headers[i].as_str())),
+    )?;
+    for (volume, row) in volumes.iter().zip(&table.rows) {
+        let key = volume.key();
+        writer.write_record(
+            std::iter::once(key.as_str()).chain(kept.iter().map(|&i| row[i].as_str())),
+        )?;
+    }
+    writer.flush()?;
+
+    let (authors, links) = authors(volumes);
+    write_linked(
+        &authors,
+        &["author_id", "name", "wikipedia_link"],
+        &dir.join("authors.csv"),
+    )?;
+    write_linked(
+        &links,
+        &[UPSERT_KEY, "author_id"],
+        &dir.join("volume_authors.csv"),
+    )?;
+    Ok(())
+}
+
+/// Writes one of the linked tables, with just its headers when there are no rows so it
+/// still loads as an empty table.
+fn write_linked<T: Serialize>(records: &[T], headers: &[&str], path: &Path) -> Result<()> {
+    let mut file = File::create(path)?;
+    if records.is_empty() {
+        let mut writer = Writer::from_writer(file);
+        writer.write_record(headers)?;
+        writer.flush()?;
+        return Ok(());
+    }
+    write_csv_records(records, &mut file)
+}
@@ -0,0 +1,38 @@
+use super::{inverted_name, record_id, series_title};
+use crate::Volume;
+use crate::constants::metadata;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use std::io::Write;
+
+fn write_tag(out: &mut dyn Write, tag: &str, value: &str) -> Result<()> {
+    // RIS is line-oriented, so values must not contain line breaks
+    let value = value.replace(['\r', '\n'], " ");
+    write!(out, "{}  - {}\r\n", tag, value)?;
+    Ok(())
+}
+
+pub fn write_ris(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    for volume in volumes {
+        write_tag(out, "TY", "BOOK")?;
+        write_tag(out, "ID", &record_id(volume))?;
+        if !volume.author.is_empty() {
+            write_tag(out, "AU", &inverted_name(&volume.author))?;
+        }
+        write_tag(out, "TI", &volume.title)?;
+        write_tag(out, "T3", series_title(volume))?;
+        if volume.volume_number > 0 {
+            write_tag(out, "SV", &volume.volume_number.to_string())?;
+        }
+        write_tag(out, "PB", &metadata().publisher)?;
+        write_tag(out, "CY", &metadata().publication_place)?;
+        if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+            write_tag(out, "SN", isbn)?;
+        }
+        if !volume.loa_detail_link.is_empty() {
+            write_tag(out, "UR", &absolute_url(&volume.loa_detail_link))?;
+        }
+        write!(out, "ER  - \r\n\r\n")?;
+    }
+    Ok(())
+}
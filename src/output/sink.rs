@@ -0,0 +1,164 @@
+use super::{OutputFormat, OutputOptions, is_postgres_url, write_volumes_with};
+use crate::Volume;
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use std::future::Future;
+use std::path::Path;
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+
+/// Somewhere a run's volumes can be written: a file, stdout, or a database.
+pub trait OutputSink {
+    /// Where the volumes go, for progress messages.
+    fn describe(&self) -> String;
+    fn write(&self, volumes: &[Volume]) -> Result<()>;
+}
+
+/// One `--output`: a format and the file (or database URL) to write it to, or stdout
+/// when `path` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTarget {
+    pub format: OutputFormat,
+    pub path: Option<String>,
+    pub options: OutputOptions,
+}
+
+impl OutputSink for OutputTarget {
+    fn describe(&self) -> String {
+        self.path.clone().unwrap_or_else(|| "stdout".to_string())
+    }
+
+    fn write(&self, volumes: &[Volume]) -> Result<()> {
+        write_volumes_with(volumes, self.format, self.path.as_deref(), &self.options)
+    }
+}
+
+/// Runs a sink's async work to completion from the sync [`OutputSink::write`]. It works
+/// outside a Tokio runtime and inside either flavor: a multi-threaded runtime's worker
+/// blocks in place, while a current-thread runtime, which can't, has the work run on a
+/// runtime of its own on another thread. Either way the calling thread waits.
+pub(crate) fn block_on<F, T>(future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>> + Send,
+    T: Send,
+{
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        Ok(_) => std::thread::scope(|scope| {
+            scope
+                .spawn(|| Runtime::new()?.block_on(future))
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        }),
+        Err(_) => Runtime::new()?.block_on(future),
+    }
+}
+
+/// The format a file extension usually means, e.g. `.db` for SQLite.
+pub fn format_for_path(path: &str) -> Option<OutputFormat> {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".csl.json") {
+        return Some(OutputFormat::CslJson);
+    }
+    let extension = Path::new(&lower).extension()?.to_str()?.to_string();
+    Some(match extension.as_str() {
+        "csv" => OutputFormat::Csv,
+        "xlsx" => OutputFormat::Xlsx,
+        "db" | "sqlite" | "sqlite3" => OutputFormat::Sqlite,
+        "duckdb" => OutputFormat::Duckdb,
+        "md" => OutputFormat::Markdown,
+        "html" | "htm" => OutputFormat::Html,
+        "pb" => OutputFormat::Protobuf,
+        "xml" | "marcxml" => OutputFormat::Marcxml,
+        "ris" => OutputFormat::Ris,
+        "jsonld" => OutputFormat::Jsonld,
+        "atom" | "opds" => OutputFormat::Opds,
+        "graphml" => OutputFormat::Graphml,
+        "dot" | "gv" => OutputFormat::Dot,
+        "ttl" => OutputFormat::Turtle,
+        "json" => OutputFormat::Json,
+        _ => return None,
+    })
+}
+
+impl OutputTarget {
+    /// Parses an `--output` value. `FORMAT:PATH` (e.g. `json:catalog.txt`) names the
+    /// format; otherwise it comes from the extension when `infer` is set, and is
+    /// `default` when it isn't or the extension is unknown.
+    pub fn parse(spec: &str, default: OutputFormat, infer: bool) -> Self {
+        if !is_postgres_url(spec)
+            && let Some((prefix, path)) = spec.split_once(':')
+            && let Ok(format) = OutputFormat::from_str(prefix, true)
+        {
+            return OutputTarget {
+                format,
+                path: Some(path.to_string()),
+                options: OutputOptions::default(),
+            };
+        }
+        let format = if infer {
+            format_for_path(spec).unwrap_or(default)
+        } else {
+            default
+        };
+        OutputTarget {
+            format,
+            path: Some(spec.to_string()),
+            options: OutputOptions::default(),
+        }
+    }
+
+    /// The targets for a command's `--output` values. A single output (or stdout)
+    /// uses `--format`; with several, each one's format comes from its extension.
+    pub fn from_args(
+        outputs: &[String],
+        format: OutputFormat,
+        options: OutputOptions,
+    ) -> Result<Vec<Self>> {
+        if outputs.is_empty() {
+            return Ok(vec![OutputTarget {
+                format,
+                path: None,
+                options,
+            }]);
+        }
+        let infer = outputs.len() > 1;
+        let targets: Vec<Self> = outputs
+            .iter()
+            .map(|spec| OutputTarget {
+                options,
+                ..Self::parse(spec, format, infer)
+            })
+            .collect();
+        for (i, target) in targets.iter().enumerate() {
+            if targets[..i].iter().any(|t| t.path == target.path) {
+                return Err(anyhow!("{} is given as --output twice", target.describe()));
+            }
+        }
+        Ok(targets)
+    }
+}
+
+/// Writes the volumes to every sink, carrying on past failures so one bad target
+/// doesn't cost the rest. Returns the sinks written, or an error naming those that
+/// failed.
+pub fn write_all(volumes: &[Volume], sinks: &[&dyn OutputSink]) -> Result<Vec<String>> {
+    if let [sink] = sinks {
+        sink.write(volumes)?;
+        return Ok(vec![sink.describe()]);
+    }
+
+    let mut written = Vec::new();
+    let mut failed = Vec::new();
+    for sink in sinks {
+        match sink.write(volumes) {
+            Ok(()) => written.push(sink.describe()),
+            Err(e) => failed.push(format!("{}: {:#}", sink.describe(), e)),
+        }
+    }
+    if !failed.is_empty() {
+        return Err(anyhow!("Failed to write {}", failed.join("; ")));
+    }
+    Ok(written)
+}
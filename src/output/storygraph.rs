@@ -0,0 +1,45 @@
+use crate::Volume;
+use anyhow::Result;
+use csv::Writer;
+use serde::Serialize;
+use std::io::Write;
+
+/// Column names follow TheStoryGraph's own CSV export, which its importer reads back.
+#[derive(Serialize)]
+struct StoryGraphRow<'a> {
+    #[serde(rename = "Title")]
+    title: &'a str,
+    #[serde(rename = "Authors")]
+    authors: &'a str,
+    #[serde(rename = "ISBN/UID")]
+    isbn: &'a str,
+    #[serde(rename = "Format")]
+    format: &'a str,
+    #[serde(rename = "Read Status")]
+    read_status: &'a str,
+    #[serde(rename = "Tags")]
+    tags: &'a str,
+    #[serde(rename = "Owned?")]
+    owned: &'a str,
+}
+
+pub fn write_storygraph(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let mut writer = Writer::from_writer(out);
+
+    for volume in volumes {
+        let owned = !volume.own_volume.is_empty();
+        writer.serialize(StoryGraphRow {
+            title: &volume.title,
+            authors: &volume.author,
+            isbn: volume.isbn.as_deref().unwrap_or(""),
+            format: "hardcover",
+            // We only track ownership, not reading progress, so everything starts as to-read
+            read_status: "to-read",
+            tags: "library-of-america",
+            owned: if owned { "Yes" } else { "No" },
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use crate::Volume;
+use crate::scrape::absolute_url;
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+
+/// A volume placed on the timeline by the years its works were written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    pub volume: String,
+    pub title: String,
+    pub author: String,
+    pub start: i32,
+    pub end: i32,
+    pub owned: bool,
+    pub link: String,
+}
+
+/// The volumes with a known period, earliest first. Volumes without one (no years in
+/// the title and no author dates) are left out.
+pub fn timeline_entries(volumes: &[Volume]) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = volumes
+        .iter()
+        .filter_map(|volume| {
+            let (start, end) = volume.period()?;
+            Some(TimelineEntry {
+                volume: volume.key(),
+                title: volume.title.clone(),
+                author: volume.author.clone(),
+                start,
+                end,
+                owned: !volume.own_volume.is_empty(),
+                link: absolute_url(&volume.loa_detail_link),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| (a.start, a.end, &a.title).cmp(&(b.start, b.end, &b.title)));
+    entries
+}
+
+const STYLE: &str = r#"
+body { font-family: Georgia, serif; margin: 2rem; color: #222; background: #fafaf7; }
+h1 { font-weight: normal; margin-bottom: 0.25rem; }
+.controls { margin: 1rem 0; display: flex; gap: 1rem; align-items: center; }
+.controls input[type=search] { padding: 0.4rem; width: 20rem; }
+.visually-hidden { position: absolute; width: 1px; height: 1px; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap; }
+.legend span { display: inline-block; width: 0.8rem; height: 0.8rem; margin: 0 0.3rem 0 0.8rem; vertical-align: middle; }
+#viewport { overflow-x: auto; background: #fff; border: 1px solid #ddd; position: relative; }
+#timeline { position: relative; }
+.axis { position: sticky; top: 0; height: 1.6rem; border-bottom: 1px solid #ccc; background: #fff; z-index: 1; }
+.tick { position: absolute; top: 0; height: 100%; border-left: 1px solid #ccc; padding-left: 0.2rem; font-size: 0.75rem; color: #595959; }
+.item { position: absolute; height: 1.3rem; line-height: 1.3rem; padding: 0 0.3rem; border-radius: 3px; font-size: 0.75rem; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; box-sizing: border-box; color: #222; text-decoration: none; }
+.item.owned, .legend .owned { background: #cfe6c4; border: 1px solid #3d6b2f; }
+.item.missing, .legend .missing { background: #f3dede; border: 1px dashed #7a1f1f; }
+.item:focus-visible, input:focus-visible { outline: 2px solid #7a1f1f; outline-offset: 2px; z-index: 1; }
+#tooltip { position: fixed; pointer-events: none; background: #222; color: #fff; padding: 0.4rem 0.6rem; border-radius: 3px; font-size: 0.8rem; max-width: 24rem; z-index: 2; }
+@media (prefers-color-scheme: dark) {
+  body { color: #eee; background: #1b1b1b; }
+  #viewport, .axis { background: #242424; border-color: #444; }
+  .tick { color: #bbb; border-left-color: #444; }
+  .item:focus-visible, input:focus-visible { outline-color: #f2a7a7; }
+  #tooltip { background: #eee; color: #1b1b1b; }
+}
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var entries = JSON.parse(document.getElementById("timeline-data").textContent);
+  var timeline = document.getElementById("timeline");
+  var search = document.getElementById("search");
+  var missingOnly = document.getElementById("missing-only");
+  var zoom = document.getElementById("zoom");
+  var count = document.getElementById("count");
+  var tooltip = document.getElementById("tooltip");
+  var laneHeight = 24, axisHeight = 28;
+  if (!entries.length) return;
+
+  var first = Math.floor(Math.min.apply(null, entries.map(function (e) { return e.start; })) / 10) * 10;
+  var last = Math.ceil((Math.max.apply(null, entries.map(function (e) { return e.end; })) + 1) / 10) * 10;
+
+  function years(e) {
+    return e.start === e.end ? String(e.start) : e.start + "–" + e.end;
+  }
+
+  // What the tooltip shows and screen readers announce for an item
+  function describe(e) {
+    return [e.title, e.author, years(e), e.owned ? "owned" : "missing"].filter(Boolean);
+  }
+
+  function render() {
+    var scale = parseFloat(zoom.value);
+    var query = search.value.toLowerCase();
+    var visible = entries.filter(function (e) {
+      var text = (e.title + " " + e.author).toLowerCase();
+      return text.indexOf(query) !== -1 && !(missingOnly.checked && e.owned);
+    });
+
+    timeline.textContent = "";
+    var axis = document.createElement("div");
+    axis.className = "axis";
+    axis.setAttribute("aria-hidden", "true");
+    for (var year = first; year <= last; year += 10) {
+      var tick = document.createElement("div");
+      tick.className = "tick";
+      tick.style.left = (year - first) * scale + "px";
+      tick.textContent = year;
+      axis.appendChild(tick);
+    }
+    timeline.appendChild(axis);
+
+    // Greedy lanes: each item goes in the first lane that's free by its start. Items
+    // stay in chronological order in the document, so tabbing walks the timeline.
+    var lanes = [];
+    visible.forEach(function (e) {
+      var left = (e.start - first) * scale;
+      var width = Math.max((e.end - e.start + 1) * scale, 6);
+      var lane = 0;
+      while (lane < lanes.length && lanes[lane] > left) lane++;
+      lanes[lane] = left + width + 2;
+
+      var item = document.createElement(e.link ? "a" : "span");
+      if (e.link) {
+        item.href = e.link;
+      } else {
+        item.tabIndex = 0;
+      }
+      item.className = "item " + (e.owned ? "owned" : "missing");
+      item.dataset.index = entries.indexOf(e);
+      item.setAttribute("aria-label", describe(e).join(", "));
+      item.setAttribute("aria-describedby", "tooltip");
+      item.style.left = left + "px";
+      item.style.width = width + "px";
+      item.style.top = axisHeight + lane * laneHeight + "px";
+      item.textContent = e.title;
+      timeline.appendChild(item);
+    });
+    timeline.style.width = (last - first) * scale + "px";
+    timeline.style.height = axisHeight + Math.max(lanes.length, 1) * laneHeight + "px";
+    count.textContent = visible.length + " volumes shown";
+  }
+
+  function entryFor(target) {
+    return target.classList && target.classList.contains("item") ? entries[target.dataset.index] : null;
+  }
+
+  function show(e, x, y) {
+    tooltip.textContent = "";
+    describe(e).forEach(function (line) {
+      var div = document.createElement("div");
+      div.textContent = line;
+      tooltip.appendChild(div);
+    });
+    tooltip.style.left = x + 12 + "px";
+    tooltip.style.top = y + 12 + "px";
+    tooltip.hidden = false;
+  }
+
+  function hide() { tooltip.hidden = true; }
+
+  timeline.addEventListener("mousemove", function (event) {
+    var e = entryFor(event.target);
+    if (e) show(e, event.clientX, event.clientY); else hide();
+  });
+  timeline.addEventListener("mouseleave", hide);
+  timeline.addEventListener("focusin", function (event) {
+    var e = entryFor(event.target);
+    if (!e) return;
+    var box = event.target.getBoundingClientRect();
+    show(e, box.left, box.bottom);
+  });
+  timeline.addEventListener("focusout", hide);
+  document.addEventListener("keydown", function (event) {
+    if (event.key === "Escape") hide();
+  });
+
+  search.addEventListener("input", render);
+  missingOnly.addEventListener("change", render);
+  zoom.addEventListener("input", render);
+  render();
+})();
+"#;
+
+/// An interactive timeline of the volumes on a year axis, colored by ownership, as
+/// one HTML file with no external scripts or styles.
+pub fn write_timeline_html(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let entries = timeline_entries(volumes);
+    let owned = entries.iter().filter(|e| e.owned).count();
+    // The data sits in a <script> element, which only `</` can break out of
+    let data = serde_json::to_string(&entries)?.replace("</", "<\\/");
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    )?;
+    writeln!(out, "<title>Library of America Timeline</title>")?;
+    writeln!(out, "<style>{}</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<main>")?;
+    writeln!(out, "<h1>Library of America Timeline</h1>")?;
+    writeln!(
+        out,
+        "<p>{} volumes by the years of their works, {} owned</p>",
+        entries.len(),
+        owned
+    )?;
+    writeln!(out, "<div class=\"controls\">")?;
+    writeln!(
+        out,
+        "<label class=\"visually-hidden\" for=\"search\">Filter by title or author</label>"
+    )?;
+    writeln!(
+        out,
+        "<input type=\"search\" id=\"search\" placeholder=\"Filter by title or author\">"
+    )?;
+    writeln!(
+        out,
+        "<label><input type=\"checkbox\" id=\"missing-only\"> Missing only</label>"
+    )?;
+    writeln!(
+        out,
+        "<label>Zoom <input type=\"range\" id=\"zoom\" min=\"2\" max=\"40\" value=\"8\"></label>"
+    )?;
+    writeln!(
+        out,
+        "<span id=\"count\" role=\"status\" aria-live=\"polite\"></span>"
+    )?;
+    writeln!(
+        out,
+        "<span class=\"legend\" aria-hidden=\"true\"><span class=\"owned\"></span>Owned<span class=\"missing\"></span>Missing</span>"
+    )?;
+    writeln!(out, "</div>")?;
+    writeln!(out, "<section aria-labelledby=\"timeline-heading\">")?;
+    writeln!(
+        out,
+        "<h2 id=\"timeline-heading\" class=\"visually-hidden\">Volumes in order of their works' years</h2>"
+    )?;
+    writeln!(
+        out,
+        "<div id=\"viewport\"><div id=\"timeline\"></div></div>"
+    )?;
+    writeln!(out, "</section>")?;
+    writeln!(out, "<div id=\"tooltip\" role=\"tooltip\" hidden></div>")?;
+    writeln!(
+        out,
+        "<script type=\"application/json\" id=\"timeline-data\">{}</script>",
+        data
+    )?;
+    writeln!(out, "</main>")?;
+    writeln!(out, "<script>{}</script>", SCRIPT)?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::Volume;
+use crate::constants::{SeriesMetadata, metadata};
+use crate::scrape::{APS_URL, COLLECTION_URL, absolute_url};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+const PREFIXES: &str = "@prefix schema: <https://schema.org/> .
+@prefix dcterms: <http://purl.org/dc/terms/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+";
+
+fn literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Wraps a URL as an IRI, percent-encoding the characters Turtle forbids inside `<>`.
+fn iri(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len() + 2);
+    encoded.push('<');
+    for c in url.chars() {
+        match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' | ' ' => {
+                encoded.push_str(&format!("%{:02X}", c as u32))
+            }
+            _ => encoded.push(c),
+        }
+    }
+    encoded.push('>');
+    encoded
+}
+
+/// The volume's LOA page when known, so the same book gets the same IRI on every run.
+fn volume_iri(volume: &Volume) -> String {
+    match absolute_url(&volume.loa_detail_link) {
+        url if url.is_empty() => format!("<urn:loa:volume:{}>", volume.key()),
+        url => iri(&url),
+    }
+}
+
+fn author_iri(author: &str) -> String {
+    let slug: String = author
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("<urn:loa:author:{}>", slug)
+}
+
+fn write_volume(volume: &Volume, series: &str, out: &mut dyn Write) -> Result<()> {
+    let mut properties = vec![
+        ("a".to_string(), "schema:Book".to_string()),
+        ("schema:name".to_string(), literal(&volume.title)),
+        ("dcterms:title".to_string(), literal(&volume.title)),
+    ];
+
+    if !volume.author.is_empty() {
+        let author = author_iri(&volume.author);
+        properties.push(("schema:author".into(), author.clone()));
+        properties.push(("dcterms:creator".into(), author));
+    }
+    if let Some(isbn) = volume.isbn.as_deref().filter(|isbn| !isbn.is_empty()) {
+        properties.push(("schema:isbn".into(), literal(isbn)));
+        properties.push((
+            "dcterms:identifier".into(),
+            literal(&format!("urn:isbn:{}", isbn)),
+        ));
+    }
+    if let Some(year) = volume.publication_year.as_deref().filter(|y| !y.is_empty()) {
+        properties.push((
+            "dcterms:issued".into(),
+            format!("{}^^xsd:gYear", literal(year)),
+        ));
+    }
+    properties.push(("schema:publisher".into(), literal(&metadata().publisher)));
+    properties.push(("dcterms:publisher".into(), literal(&metadata().publisher)));
+    properties.push(("schema:isPartOf".into(), series.to_string()));
+    properties.push(("dcterms:isPartOf".into(), series.to_string()));
+    if volume.volume_number > 0 {
+        properties.push(("schema:position".into(), volume.volume_number.to_string()));
+    }
+    if !volume.cover_image_link.is_empty() {
+        properties.push(("schema:image".into(), iri(&volume.cover_image_link)));
+    }
+
+    writeln!(out, "{}", volume_iri(volume))?;
+    write_properties(&properties, out)
+}
+
+fn write_properties(properties: &[(String, String)], out: &mut dyn Write) -> Result<()> {
+    for (i, (predicate, object)) in properties.iter().enumerate() {
+        let terminator = if i + 1 == properties.len() { "." } else { ";" };
+        writeln!(out, "    {} {} {}", predicate, object, terminator)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+pub fn write_turtle(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let series = iri(COLLECTION_URL);
+    let aps_series = iri(APS_URL);
+
+    writeln!(out, "{}", PREFIXES)?;
+
+    let SeriesMetadata {
+        series_title,
+        aps_series_title,
+        issn,
+        aps_issn,
+        ..
+    } = metadata();
+    let mut series_nodes = vec![(&series, series_title, issn)];
+    if volumes.iter().any(|v| !v.series.is_empty()) {
+        series_nodes.push((&aps_series, aps_series_title, aps_issn));
+    }
+    for (node, name, issn) in series_nodes {
+        writeln!(out, "{}", node)?;
+        let mut properties = vec![
+            ("a".to_string(), "schema:BookSeries".to_string()),
+            ("schema:name".to_string(), literal(name)),
+        ];
+        if let Some(issn) = issn {
+            properties.push(("schema:issn".into(), literal(issn)));
+        }
+        write_properties(&properties, out)?;
+    }
+
+    // One node per author, linked to Wikipedia, Wikidata, and VIAF where known
+    let mut authors: BTreeMap<&str, &Volume> = BTreeMap::new();
+    for volume in volumes.iter().filter(|v| !v.author.is_empty()) {
+        authors.entry(&volume.author).or_insert(volume);
+    }
+    for (author, volume) in &authors {
+        let mut properties = vec![
+            ("a".to_string(), "schema:Person".to_string()),
+            ("schema:name".to_string(), literal(author)),
+        ];
+        if !volume.author_wikipedia_link.is_empty() {
+            properties.push(("schema:sameAs".into(), iri(&volume.author_wikipedia_link)));
+        }
+        if let Some(qid) = volume
+            .author_wikidata_id
+            .as_deref()
+            .filter(|q| !q.is_empty())
+        {
+            properties.push((
+                "schema:sameAs".into(),
+                format!("<http://www.wikidata.org/entity/{}>", qid),
+            ));
+        }
+        if let Some(viaf) = volume.author_viaf_id.as_deref().filter(|v| !v.is_empty()) {
+            properties.push((
+                "schema:sameAs".into(),
+                format!("<http://viaf.org/viaf/{}>", viaf),
+            ));
+        }
+        writeln!(out, "{}", author_iri(author))?;
+        write_properties(&properties, out)?;
+    }
+
+    for volume in volumes {
+        let part_of = if volume.series.is_empty() {
+            &series
+        } else {
+            &aps_series
+        };
+        write_volume(volume, part_of, out)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use super::{Table, is_url};
+use crate::Volume;
+use anyhow::Result;
+use rust_xlsxwriter::{Format, Url, Workbook};
+use std::io::Write;
+
+pub fn write_xlsx(volumes: &[Volume], out: &mut dyn Write) -> Result<()> {
+    let table = Table::from_volumes(volumes)?;
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("LOA Volumes")?;
+
+    let header_format = Format::new().set_bold();
+    for (col, header) in table.headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, header, &header_format)?;
+    }
+
+    for (i, row) in table.rows.iter().enumerate() {
+        let row_idx = (i + 1) as u32;
+        for (col, cell) in row.iter().enumerate() {
+            let col = col as u16;
+            if is_url(cell) {
+                worksheet.write_url(row_idx, col, Url::new(cell.as_str()))?;
+            } else if let Ok(number) = cell.parse::<u32>() {
+                worksheet.write_number(row_idx, col, number)?;
+            } else {
+                worksheet.write_string(row_idx, col, cell)?;
+            }
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofit();
+
+    out.write_all(&workbook.save_to_buffer()?)?;
+    Ok(())
+}
@@ -0,0 +1,302 @@
+use crate::details::find_isbn13;
+use crate::{Ownership, Volume};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Value written to `own_volume` for volumes found on the user's shelf.
+pub const OWNED: &str = "yes";
+
+/// Minimum token overlap for a title-only match to count.
+const MIN_TITLE_SCORE: f64 = 0.6;
+/// A runner-up this close to the best title match makes the match ambiguous.
+const AMBIGUITY_MARGIN: f64 = 0.1;
+
+/// Words too common in LOA titles to tell volumes apart.
+const STOPWORDS: &[&str] = &["the", "a", "an", "and", "of", "library", "america", "loa"];
+
+/// One book from a Goodreads or LibraryThing export.
+#[derive(Debug, Clone)]
+pub struct ShelfEntry {
+    pub title: String,
+    pub author: String,
+    pub isbns: Vec<String>,
+}
+
+/// A shelf entry whose title matched more than one volume equally well.
+#[derive(Debug, Clone)]
+pub struct AmbiguousMatch {
+    pub title: String,
+    pub candidates: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipReport {
+    pub by_isbn: usize,
+    pub by_title: usize,
+    pub ambiguous: Vec<AmbiguousMatch>,
+}
+
+fn header_index(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)))
+}
+
+/// Converts an ISBN-10 to its ISBN-13 form so both export styles compare equal.
+fn isbn10_to_13(isbn10: &str) -> Option<String> {
+    let digits: String = isbn10
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    if digits.len() != 10 || !digits[..9].chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let stem = format!("978{}", &digits[..9]);
+    let sum: u32 = stem
+        .bytes()
+        .enumerate()
+        .map(|(i, d)| (d - b'0') as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    Some(format!("{}{}", stem, (10 - sum % 10) % 10))
+}
+
+fn parse_isbns(field: &str) -> Vec<String> {
+    // Goodreads wraps ISBNs as ="978..." and LibraryThing lists several per cell
+    field
+        .split([',', ';', ' '])
+        .filter_map(|part| find_isbn13(part).or_else(|| isbn10_to_13(part)))
+        .collect()
+}
+
+/// Reads a Goodreads CSV or LibraryThing CSV/TSV export. Goodreads "to-read" rows are
+/// skipped, since they are books the user does not have yet.
+pub fn read_shelf_export(path: &Path) -> Result<Vec<ShelfEntry>> {
+    let is_tsv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(if is_tsv { b'\t' } else { b',' })
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Failed to open shelf export {}", path.display()))?;
+
+    let headers = reader.headers()?.clone();
+    let title_col = header_index(&headers, &["Title"])
+        .with_context(|| format!("No Title column in {}", path.display()))?;
+    let author_col = header_index(
+        &headers,
+        &["Author", "Primary Author", "Author (Last, First)"],
+    );
+    let isbn_cols: Vec<usize> = ["ISBN13", "ISBN", "ISBNs"]
+        .iter()
+        .filter_map(|name| header_index(&headers, &[name]))
+        .collect();
+    let shelf_col = header_index(&headers, &["Exclusive Shelf"]);
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("").trim();
+
+        if field(shelf_col) == "to-read" {
+            continue;
+        }
+
+        let mut isbns: Vec<String> = isbn_cols
+            .iter()
+            .flat_map(|&col| parse_isbns(field(Some(col))))
+            .collect();
+        isbns.dedup();
+
+        entries.push(ShelfEntry {
+            title: field(Some(title_col)).to_string(),
+            author: field(author_col).to_string(),
+            isbns,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !STOPWORDS.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Pulls the series number out of titles like "Novels (Library of America #72)".
+fn series_number(title: &str) -> Option<u32> {
+    let lower = title.to_lowercase();
+    lower.match_indices('#').find_map(|(pos, _)| {
+        let before = lower[..pos].trim_end();
+        if !(before.ends_with("america") || before.ends_with("loa")) {
+            return None;
+        }
+        let digits: String = lower[pos + 1..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok()
+    })
+}
+
+/// Strips the trailing "(Series #N)" Goodreads appends to titles.
+fn bare_title(title: &str) -> &str {
+    match title.rfind(" (") {
+        Some(pos) if title.ends_with(')') => &title[..pos],
+        _ => title,
+    }
+}
+
+/// Marks volumes on the user's shelf as owned, matching by ISBN first (which needs
+/// `--details`), then by series number in the title, then by fuzzy title. Entries whose
+/// title fits several volumes equally well are left unmarked and reported instead.
+pub fn mark_owned(volumes: &mut [Volume], shelf: &[ShelfEntry]) -> OwnershipReport {
+    let mut report = OwnershipReport::default();
+    let volume_tokens: Vec<HashSet<String>> = volumes
+        .iter()
+        .map(|v| tokens(&format!("{} {}", v.original_volume_name, v.author)))
+        .collect();
+
+    for entry in shelf {
+        if let Some(index) = volumes.iter().position(|v| {
+            v.isbn
+                .as_deref()
+                .is_some_and(|isbn| !isbn.is_empty() && entry.isbns.iter().any(|i| i == isbn))
+        }) {
+            volumes[index].own_volume = OWNED.to_string();
+            report.by_isbn += 1;
+            continue;
+        }
+
+        if let Some(number) = series_number(&entry.title)
+            && let Some(volume) = volumes
+                .iter_mut()
+                .find(|v| v.series.is_empty() && v.volume_number == number)
+        {
+            volume.own_volume = OWNED.to_string();
+            report.by_title += 1;
+            continue;
+        }
+
+        let entry_tokens = tokens(&format!("{} {}", bare_title(&entry.title), entry.author));
+        let mut scored: Vec<(f64, usize)> = volume_tokens
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (similarity(&entry_tokens, t), i))
+            .filter(|(score, _)| *score >= MIN_TITLE_SCORE)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        match scored.as_slice() {
+            [] => {}
+            [(best, index), rest @ ..] => {
+                let close: Vec<usize> = rest
+                    .iter()
+                    .filter(|(score, _)| best - score < AMBIGUITY_MARGIN)
+                    .map(|(_, i)| *i)
+                    .collect();
+                if close.is_empty() {
+                    volumes[*index].own_volume = OWNED.to_string();
+                    report.by_title += 1;
+                } else {
+                    report.ambiguous.push(AmbiguousMatch {
+                        title: entry.title.clone(),
+                        candidates: std::iter::once(*index)
+                            .chain(close)
+                            .map(|i| volumes[i].volume_number)
+                            .collect(),
+                    });
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// Volume numbers the user has marked as owned with `loa-scraper own`, kept between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnedState {
+    pub owned: BTreeSet<u32>,
+    /// Date (YYYY-MM-DD) each volume was added; older state files don't have one.
+    #[serde(default)]
+    pub acquired: BTreeMap<u32, String>,
+}
+
+/// Where the ownership state lives unless overridden with `--state`.
+pub fn default_state_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("owned.json")
+}
+
+impl OwnedState {
+    /// Loads the state file, treating a missing file as owning nothing.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read ownership state {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse ownership state {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write ownership state {}", path.display()))
+    }
+
+    /// Records a volume as owned, returning false if it already was.
+    pub fn add(&mut self, volume_number: u32, acquired_on: &str) -> bool {
+        let added = self.owned.insert(volume_number);
+        if added {
+            self.acquired.insert(volume_number, acquired_on.to_string());
+        }
+        added
+    }
+
+    pub fn remove(&mut self, volume_number: u32) -> bool {
+        self.acquired.remove(&volume_number);
+        self.owned.remove(&volume_number)
+    }
+
+    pub fn ownership(&self, volume_number: u32) -> Ownership {
+        Ownership {
+            volume_number,
+            owned: self.owned.contains(&volume_number),
+            acquired: self.acquired.get(&volume_number).cloned(),
+        }
+    }
+
+    /// Sets `own_volume` on every volume recorded as owned. Recorded numbers are from
+    /// the numbered collection, so other series are left alone.
+    pub fn apply(&self, volumes: &mut [Volume]) -> usize {
+        let mut marked = 0;
+        for volume in volumes {
+            if volume.series.is_empty() && self.owned.contains(&volume.volume_number) {
+                volume.own_volume = OWNED.to_string();
+                marked += 1;
+            }
+        }
+        marked
+    }
+}
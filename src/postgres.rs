@@ -0,0 +1,114 @@
+use crate::Volume;
+use crate::output::block_on;
+use crate::volume::{UPSERT_KEY, upsert_rows};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tokio_postgres::NoTls;
+use tokio_postgres::types::ToSql;
+
+/// The table `--output postgres://...` creates and keeps up to date.
+pub const TABLE: &str = "loa_volumes";
+
+fn pg_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Bool(_) => "BOOLEAN",
+        serde_json::Value::Number(n) if n.is_i64() => "BIGINT",
+        serde_json::Value::Number(_) => "DOUBLE PRECISION",
+        _ => "TEXT",
+    }
+}
+
+/// Every value is sent as text and cast to its column's type in the statement, so one
+/// parameter type covers nulls and every column.
+fn to_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+async fn upsert(url: &str, volumes: &[Volume]) -> Result<()> {
+    let (mut client, connection) = tokio_postgres::connect(url, NoTls)
+        .await
+        .context("Failed to connect to PostgreSQL")?;
+    let connection = tokio::spawn(connection);
+
+    let (rows, sample) = upsert_rows(volumes)?;
+
+    let tx = client.transaction().await?;
+    tx.batch_execute(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (\"{}\" TEXT PRIMARY KEY, volume_number BIGINT)",
+        TABLE, UPSERT_KEY
+    ))
+    .await?;
+
+    // Values are cast to each column's type as the table has it, not as this batch
+    // would guess it (a batch of only unnumbered volumes has no volume_number)
+    let mut types: HashMap<String, String> = tx
+        .query(
+            "SELECT column_name, data_type FROM information_schema.columns
+             WHERE table_name = $1 AND table_schema = current_schema()",
+            &[&TABLE],
+        )
+        .await?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    for (column, value) in &sample {
+        if !types.contains_key(column) {
+            tx.batch_execute(&format!(
+                "ALTER TABLE {} ADD COLUMN \"{}\" {}",
+                TABLE,
+                column,
+                pg_type(value)
+            ))
+            .await?;
+            types.insert(column.clone(), pg_type(value).to_string());
+        }
+    }
+
+    for row in &rows {
+        let columns: Vec<&String> = row.keys().collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT (\"{}\") DO UPDATE SET {}",
+            TABLE,
+            columns
+                .iter()
+                .map(|c| format!("\"{}\"", c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("${}::text::{}", i + 1, types[c.as_str()]))
+                .collect::<Vec<_>>()
+                .join(", "),
+            UPSERT_KEY,
+            columns
+                .iter()
+                .map(|c| format!("\"{0}\" = excluded.\"{0}\"", c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let values: Vec<Option<String>> = row.values().map(to_text).collect();
+        let params: Vec<&(dyn ToSql + Sync)> =
+            values.iter().map(|v| v as &(dyn ToSql + Sync)).collect();
+        tx.execute(&sql, &params).await?;
+    }
+    tx.commit().await?;
+
+    drop(client);
+    connection.await??;
+    Ok(())
+}
+
+/// Creates or updates the `loa_volumes` table at a `postgres://` connection string,
+/// upserting each volume on its `row_key` (`loa-1`, `aps-3`, ...) so reruns update
+/// rows in place. Columns newer versions of `Volume` add are added to the table.
+/// Connections are unencrypted; use a local socket or a tunnel for remote servers.
+/// It can be called with or without a Tokio runtime, of either flavor, and blocks
+/// until the rows are written.
+pub fn write_postgres(volumes: &[Volume], url: &str) -> Result<()> {
+    block_on(upsert(url, volumes))
+}
@@ -0,0 +1,159 @@
+//! What the progress line of a long run shows beyond a count: an ETA that follows each
+//! phase's recent pace, and which hosts are throttling or failing requests.
+
+use crate::http::{Middleware, Request, Response};
+use anyhow::Result;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How many recent items the moving average covers.
+const WINDOW: usize = 20;
+
+/// How long a host still shows as throttled after its last throttled response.
+const THROTTLE_MEMORY: Duration = Duration::from_secs(60);
+
+/// Estimates the time left in one phase of a run from the moving average of its recent
+/// items.
+#[derive(Debug, Clone)]
+pub struct Eta {
+    total: usize,
+    done: usize,
+    last: Instant,
+    /// Recent per-item times.
+    recent: VecDeque<Duration>,
+}
+
+impl Eta {
+    /// A phase of `total` items, `done` of which were finished before `now`.
+    pub fn new(total: usize, done: usize, now: Instant) -> Self {
+        Eta {
+            total,
+            done,
+            last: now,
+            recent: VecDeque::with_capacity(WINDOW),
+        }
+    }
+
+    /// Notes that `done` items are finished as of `now`. Items finished together share
+    /// the time since the last update.
+    pub fn record(&mut self, done: usize, now: Instant) {
+        if done <= self.done {
+            return;
+        }
+        let elapsed = now.saturating_duration_since(self.last);
+        let per_item = elapsed / (done - self.done) as u32;
+        for _ in self.done..done {
+            if self.recent.len() == WINDOW {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(per_item);
+        }
+        self.done = done;
+        self.last = now;
+    }
+
+    /// The recent time per item, once an item has finished.
+    pub fn average(&self) -> Option<Duration> {
+        let total: Duration = self.recent.iter().sum();
+        Some(total / self.recent.len() as u32).filter(|_| !self.recent.is_empty())
+    }
+
+    /// The time left as of `now`, once an item has finished.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let average = self.average()?;
+        let left = self.total.saturating_sub(self.done);
+        let estimate = average * left as u32;
+        if left == 0 {
+            return Some(Duration::ZERO);
+        }
+        // Count down while the current item is under way instead of jumping per item
+        let under_way = now.saturating_duration_since(self.last).min(average);
+        Some(estimate.saturating_sub(under_way))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HostStatus {
+    throttled: usize,
+    failed: usize,
+    last_throttled: Option<Instant>,
+    last_failed: Option<Instant>,
+    /// The last throttled response's `Retry-After`, when it gave one in seconds.
+    retry_after: Option<Duration>,
+}
+
+/// Middleware that notes, per host, responses asking us to slow down (429 or 503) and
+/// requests that failed outright, for the progress line. Clones share the same counts.
+#[derive(Debug, Clone, Default)]
+pub struct ThrottleMonitor {
+    hosts: Arc<Mutex<BTreeMap<String, HostStatus>>>,
+}
+
+impl ThrottleMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The hosts throttled or failing in the last minute, e.g.
+    /// `en.wikipedia.org throttled 3×, retry in 25s`; empty when every host is fine.
+    pub fn summary(&self, now: Instant) -> String {
+        let hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let recent = |at: Option<Instant>| {
+            at.is_some_and(|at| now.saturating_duration_since(at) < THROTTLE_MEMORY)
+        };
+        hosts
+            .iter()
+            .filter_map(|(host, status)| {
+                if recent(status.last_throttled) {
+                    let retry = status
+                        .retry_after
+                        .zip(status.last_throttled)
+                        .map(|(after, at)| after.saturating_sub(now.saturating_duration_since(at)))
+                        .filter(|left| !left.is_zero())
+                        .map(|left| format!(", retry in {}s", left.as_secs().max(1)))
+                        .unwrap_or_default();
+                    Some(format!("{} throttled {}×{}", host, status.throttled, retry))
+                } else if recent(status.last_failed) {
+                    Some(format!("{} failing {}×", host, status.failed))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl Middleware for ThrottleMonitor {
+    fn on_response(&self, request: &Request, response: &Result<Response>) {
+        let Some(host) = request.url.host_str() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let status = hosts.entry(host.to_string()).or_default();
+        match response {
+            Ok(response)
+                if response.status() == StatusCode::TOO_MANY_REQUESTS
+                    || response.status() == StatusCode::SERVICE_UNAVAILABLE =>
+            {
+                status.throttled += 1;
+                status.last_throttled = Some(now);
+                status.retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+                    .map(Duration::from_secs);
+            }
+            Ok(_) => {}
+            Err(_) => {
+                status.failed += 1;
+                status.last_failed = Some(now);
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Python bindings, built with `maturin build --features python`.
+
+use crate::http::Client;
+use crate::scrape::ScrapeOptions;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+fn json_to_py<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        serde_json::Value::Null => py.None().into_bound(py),
+        serde_json::Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any(),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_pyobject(py)?.into_any(),
+            None => n.as_f64().unwrap_or_default().into_pyobject(py)?.into_any(),
+        },
+        serde_json::Value::String(s) => s.into_pyobject(py)?.into_any(),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// Scrapes the collection and returns one dict per volume, ready for `pandas.DataFrame`.
+#[pyfunction]
+#[pyo3(signature = (start = 1, end = None))]
+fn scrape(py: Python<'_>, start: u32, end: Option<u32>) -> PyResult<Bound<'_, PyList>> {
+    let options = ScrapeOptions {
+        start,
+        end,
+        ..Default::default()
+    };
+    let volumes = py
+        .detach(|| {
+            let runtime = tokio::runtime::Runtime::new()?;
+            runtime.block_on(crate::scrape::scrape(&Client::new(), &options))
+        })
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let list = PyList::empty(py);
+    for volume in &volumes {
+        let value =
+            serde_json::to_value(volume).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        list.append(json_to_py(py, &value)?)?;
+    }
+    Ok(list)
+}
+
+#[pymodule]
+fn loa_scraper(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(scrape, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,317 @@
+//! Request pacing. Every client holds each host to a token bucket: `--requests-per-second`
+//! on average, with up to `--burst` at once after a quiet spell.
+//!
+//! `--adaptive-rate` adds, within those limits, a controller that starts slow and speeds
+//! up while the host answers quickly and cleanly, first by shortening the gap between
+//! requests and then by allowing more at once, and halves its pace at any sign of
+//! throttling or trouble.
+
+use crate::Volume;
+use crate::http::{Client, Response};
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// The token bucket's defaults: a little gentler than a request every 100ms, with a
+/// pause every ten, which is how the network loops used to pace themselves.
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 5.0;
+pub const DEFAULT_BURST: u32 = 10;
+
+/// How fast a client may send requests to each host.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    /// How many requests may go at once after the host has been left alone a while.
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            requests_per_second: DEFAULT_REQUESTS_PER_SECOND,
+            burst: DEFAULT_BURST,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Below zero when requests are queued for tokens not yet refilled.
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Per-host token buckets enforcing a [`RateLimit`].
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    hosts: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        RateLimiter {
+            limit,
+            hosts: Mutex::default(),
+        }
+    }
+
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Waits for `host`'s next token. Each caller takes its token up front, so
+    /// concurrent requests queue in the order they arrived.
+    pub async fn acquire(&self, host: &str) {
+        let RateLimit {
+            requests_per_second: rate,
+            burst,
+        } = self.limit;
+        if !(rate.is_finite() && rate > 0.0) {
+            return;
+        }
+        let wait = {
+            let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let bucket = hosts.entry(host.to_string()).or_insert(Bucket {
+                tokens: f64::from(burst.max(1)),
+                refilled: now,
+            });
+            let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(f64::from(burst.max(1)));
+            bucket.refilled = now;
+            bucket.tokens -= 1.0;
+            Duration::from_secs_f64((-bucket.tokens).max(0.0) / rate)
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The most requests one host has in flight at once.
+pub const MAX_CONCURRENCY: usize = 4;
+
+/// Gap between the starts of requests to a host: where it begins, and its bounds.
+const START_GAP: Duration = Duration::from_millis(300);
+const MIN_GAP: Duration = Duration::from_millis(100);
+const MAX_GAP: Duration = Duration::from_secs(10);
+
+/// Healthy responses in a row before the pace steps up.
+const STEP_UP_AFTER: usize = 5;
+
+/// A response this many times slower than the host's usual counts as a sign of strain.
+const SLOW_FACTOR: u32 = 3;
+
+#[derive(Debug)]
+struct HostRate {
+    concurrency: usize,
+    gap: Duration,
+    in_flight: usize,
+    next_start: Instant,
+    /// Set from `Retry-After`: nothing starts before then.
+    hold_until: Instant,
+    /// Moving average of response times, and the lowest it has been.
+    latency: Option<Duration>,
+    usual_latency: Option<Duration>,
+    healthy_streak: usize,
+    last_backoff: Option<Instant>,
+}
+
+impl HostRate {
+    fn new(now: Instant) -> Self {
+        HostRate {
+            concurrency: 1,
+            gap: START_GAP,
+            in_flight: 0,
+            next_start: now,
+            hold_until: now,
+            latency: None,
+            usual_latency: None,
+            healthy_streak: 0,
+            last_backoff: None,
+        }
+    }
+
+    fn step_up(&mut self) {
+        if self.gap > MIN_GAP {
+            self.gap = (self.gap * 3 / 4).max(MIN_GAP);
+        } else if self.concurrency < MAX_CONCURRENCY {
+            self.concurrency += 1;
+        }
+    }
+
+    fn back_off(&mut self, now: Instant, retry_after: Option<Duration>) {
+        self.concurrency = (self.concurrency / 2).max(1);
+        self.gap = (self.gap * 2).min(MAX_GAP);
+        self.healthy_streak = 0;
+        self.last_backoff = Some(now);
+        if let Some(retry_after) = retry_after {
+            self.hold_until = self.hold_until.max(now + retry_after.min(MAX_GAP * 6));
+        }
+    }
+}
+
+/// Paces a client's requests per host; see the module docs.
+#[derive(Debug, Default)]
+pub struct AdaptiveRate {
+    hosts: Mutex<HashMap<String, HostRate>>,
+    released: Notify,
+}
+
+/// A request's turn to run. Dropping it frees the slot for the next request.
+pub struct Permit<'a> {
+    rate: &'a AdaptiveRate,
+    host: String,
+    started: Instant,
+}
+
+impl AdaptiveRate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Waits until a request to `host` may start.
+    pub async fn acquire(&self, host: &str) -> Permit<'_> {
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            // Registered before checking, so a release in between isn't missed
+            released.as_mut().enable();
+
+            let wait = {
+                let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let rate = hosts
+                    .entry(host.to_string())
+                    .or_insert_with(|| HostRate::new(now));
+                let ready_at = rate.next_start.max(rate.hold_until);
+                if rate.in_flight >= rate.concurrency {
+                    None
+                } else if now >= ready_at {
+                    rate.in_flight += 1;
+                    rate.next_start = now + rate.gap;
+                    return Permit {
+                        rate: self,
+                        host: host.to_string(),
+                        started: now,
+                    };
+                } else {
+                    Some(ready_at - now)
+                }
+            };
+            match wait {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => released.await,
+            }
+        }
+    }
+}
+
+impl Permit<'_> {
+    /// Adjusts the host's pace from how its request went.
+    pub fn finish(self, response: &Result<Response>) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.started);
+        let mut hosts = self.rate.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(rate) = hosts.get_mut(&self.host) else {
+            return;
+        };
+        // Requests sent before the last backoff reflect the old pace; don't punish twice
+        let stale = rate.last_backoff.is_some_and(|at| self.started < at);
+
+        let (trouble, retry_after) = match response {
+            Ok(response)
+                if response.status() == StatusCode::TOO_MANY_REQUESTS
+                    || response.status().is_server_error() =>
+            {
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+                    .map(Duration::from_secs);
+                (true, retry_after)
+            }
+            Ok(_) => (false, None),
+            Err(_) => (true, None),
+        };
+        if trouble {
+            if !stale {
+                rate.back_off(now, retry_after);
+            }
+            return;
+        }
+
+        let latency = match rate.latency {
+            Some(average) => (average * 4 + elapsed) / 5,
+            None => elapsed,
+        };
+        rate.latency = Some(latency);
+        let usual = rate
+            .usual_latency
+            .map_or(latency, |usual| usual.min(latency));
+        rate.usual_latency = Some(usual);
+        if elapsed > usual * SLOW_FACTOR {
+            // A slow answer doesn't count toward speeding up, and once the average
+            // shows the host straining too, ease off
+            rate.healthy_streak = 0;
+            if !stale && latency > usual * 2 {
+                rate.back_off(now, None);
+            }
+            return;
+        }
+        rate.healthy_streak += 1;
+        if rate.healthy_streak >= STEP_UP_AFTER {
+            rate.healthy_streak = 0;
+            rate.step_up();
+        }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut hosts = self.rate.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rate) = hosts.get_mut(&self.host) {
+            rate.in_flight = rate.in_flight.saturating_sub(1);
+        }
+        drop(hosts);
+        self.rate.released.notify_waiters();
+    }
+}
+
+/// Runs `work` on each volume, calling `on_volume` with each in order once it's done.
+/// Budget checks happen around every volume. With an adaptive client several volumes
+/// are under way at once, the client deciding how many requests actually run;
+/// otherwise they go one at a time. Stops at the first error, so the volumes reported
+/// are always the first ones.
+pub async fn for_each_volume(
+    client: &Client,
+    volumes: &mut [Volume],
+    work: impl AsyncFn(&mut Volume) -> Result<()>,
+    mut on_volume: impl FnMut(&Volume),
+) -> Result<()> {
+    let work = &work;
+    let mut done = stream::iter(volumes.iter_mut())
+        .map(|volume| async move {
+            client.check_budget()?;
+            work(volume).await?;
+            client.check_refused()?;
+            Ok::<_, anyhow::Error>(volume)
+        })
+        .buffered(if client.is_adaptive() {
+            MAX_CONCURRENCY
+        } else {
+            1
+        });
+
+    while let Some(volume) = done.next().await {
+        on_volume(volume?);
+    }
+    Ok(())
+}
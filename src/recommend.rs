@@ -0,0 +1,124 @@
+//! Local suggestions for what to buy next, from the volumes already owned.
+
+use crate::Volume;
+use crate::output::author_identity;
+use crate::scrape::STATUS_DELISTED;
+use crate::volume::normalize_title;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// How close (in years) two volumes' periods must be to count as the same era.
+const PERIOD_WINDOW: i32 = 10;
+
+const SET_SCORE: u32 = 3;
+const AUTHOR_SCORE: u32 = 2;
+const PERIOD_SCORE: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub volume: String,
+    pub title: String,
+    pub author: String,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+/// The middle of the period a volume covers.
+fn period(volume: &Volume) -> Option<i32> {
+    let (first, last) = volume.period()?;
+    Some((first + last) / 2)
+}
+
+/// What's left of a title without its years and part numbers, so "Novels 1930–1942"
+/// and "Novels 1942–1952" are parts of one set.
+fn set_stem(title: &str) -> String {
+    let words: Vec<String> = normalize_title(title)
+        .split(' ')
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .filter(|word| !matches!(*word, "vol" | "volume" | "i" | "ii" | "iii" | "iv" | "v"))
+        .map(str::to_string)
+        .collect();
+    words.join(" ")
+}
+
+fn set_key(volume: &Volume) -> Option<(String, String)> {
+    if volume.author.is_empty() {
+        return None;
+    }
+    Some((author_identity(volume), set_stem(&volume.title)))
+}
+
+/// Scores every volume not yet owned against `basis` (usually what's owned): parts of a
+/// set the basis has started, more by the same authors, and volumes from the same era.
+pub fn recommend(volumes: &[Volume], basis: &[&Volume]) -> Vec<Recommendation> {
+    let mut set_sizes: HashMap<(String, String), usize> = HashMap::new();
+    for key in volumes.iter().filter_map(set_key) {
+        *set_sizes.entry(key).or_default() += 1;
+    }
+    let mut sets_started: HashMap<(String, String), usize> = HashMap::new();
+    let mut author_counts: HashMap<String, usize> = HashMap::new();
+    for volume in basis {
+        if let Some(key) = set_key(volume) {
+            *sets_started.entry(key).or_default() += 1;
+            *author_counts.entry(author_identity(volume)).or_default() += 1;
+        }
+    }
+
+    let mut recommendations: Vec<Recommendation> = volumes
+        .iter()
+        .filter(|v| v.own_volume.is_empty())
+        .filter(|v| v.status.as_deref() != Some(STATUS_DELISTED))
+        .filter_map(|volume| {
+            let mut score = 0;
+            let mut reasons = Vec::new();
+
+            if let Some(key) = set_key(volume)
+                && let Some(&owned) = sets_started.get(&key)
+                && set_sizes[&key] > 1
+            {
+                score += SET_SCORE;
+                reasons.push(format!(
+                    "completes a set: you own {} of {} parts",
+                    owned, set_sizes[&key]
+                ));
+            }
+
+            let identity = author_identity(volume);
+            if let Some(&owned) = author_counts.get(&identity)
+                && !volume.author.is_empty()
+            {
+                score += AUTHOR_SCORE;
+                reasons.push(format!(
+                    "by {}, like {} volume(s) you own",
+                    volume.author, owned
+                ));
+            } else if let Some(middle) = period(volume)
+                && let Some(nearest) = basis
+                    .iter()
+                    .filter_map(|b| Some((*b, (period(b)? - middle).abs())))
+                    .filter(|(_, distance)| *distance <= PERIOD_WINDOW)
+                    .min_by_key(|(_, distance)| *distance)
+                    .map(|(b, _)| b)
+            {
+                score += PERIOD_SCORE;
+                reasons.push(format!(
+                    "same era as {} ({})",
+                    nearest.title, nearest.author
+                ));
+            }
+
+            (score > 0).then(|| Recommendation {
+                volume: volume.key(),
+                title: volume.title.clone(),
+                author: volume.author.clone(),
+                score,
+                reasons,
+            })
+        })
+        .collect();
+
+    // Stable, so equal scores stay in catalog order
+    recommendations.sort_by_key(|r| Reverse(r.score));
+    recommendations
+}
@@ -0,0 +1,94 @@
+use crate::sanity::SanityWarning;
+use std::fmt;
+
+/// Something a scrape noticed but carried on past: a row it couldn't read, a field it
+/// couldn't find, or a value it had to guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrapeWarning {
+    /// A collection-page row without the link or title every listing has, left out.
+    SkippedRow { reason: String, html: String },
+    /// A field the listing doesn't have, left empty.
+    MissingField {
+        volume_number: u32,
+        title: String,
+        field: &'static str,
+    },
+    /// A field filled in from a guess that may well be wrong.
+    LowConfidence {
+        volume_number: u32,
+        field: &'static str,
+        detail: String,
+    },
+    /// An author whose Wikipedia link is the lookup's best guess among several.
+    UncertainAuthor {
+        author: String,
+        wikipedia_link: String,
+    },
+    /// An author the lookup services couldn't be reached for; `cached` says whether
+    /// data from an earlier run stands in.
+    AuthorUnavailable { author: String, cached: bool },
+    /// Parsed listings that look like the collection page changed shape.
+    Suspicious(SanityWarning),
+}
+
+impl fmt::Display for ScrapeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeWarning::SkippedRow { reason, .. } => write!(f, "skipped a row: {}", reason),
+            ScrapeWarning::MissingField {
+                volume_number,
+                title,
+                field,
+            } => write!(f, "volume {} ({}) has no {}", volume_number, title, field),
+            ScrapeWarning::LowConfidence {
+                volume_number,
+                field,
+                detail,
+            } => write!(
+                f,
+                "volume {}'s {} may be wrong: {}",
+                volume_number, field, detail
+            ),
+            ScrapeWarning::UncertainAuthor {
+                author,
+                wikipedia_link,
+            } => write!(
+                f,
+                "{}'s article is a best guess among several: {}",
+                author, wikipedia_link
+            ),
+            ScrapeWarning::AuthorUnavailable { author, cached } => write!(
+                f,
+                "couldn't look up {}; {}",
+                author,
+                if *cached {
+                    "using data from an earlier run"
+                } else {
+                    "no author data"
+                }
+            ),
+            ScrapeWarning::Suspicious(warning) => warning.fmt(f),
+        }
+    }
+}
+
+/// The warnings gathered while scraping, returned alongside the results so an embedding
+/// application can show them in its own way. The library never prints them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrapeReport {
+    pub warnings: Vec<ScrapeWarning>,
+}
+
+impl ScrapeReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn warn(&mut self, warning: ScrapeWarning) {
+        self.warnings.push(warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
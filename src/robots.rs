@@ -0,0 +1,277 @@
+//! robots.txt compliance for loa.org: each run fetches the site's robots.txt before its
+//! first request there, refuses paths it disallows, and spaces requests by any
+//! Crawl-delay it sets.
+
+use crate::http::{Backend, Request};
+use anyhow::Result;
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{Method, Url};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio::time::Instant;
+
+/// The hosts whose robots.txt is honored. Other APIs publish their own usage policies
+/// (Wikipedia's robots.txt, for one, is meant for crawlers, not API clients).
+pub const ROBOTS_HOSTS: &[&str] = &["www.loa.org", "loa.org"];
+
+/// The product token robots.txt groups are matched against.
+pub const ROBOTS_AGENT: &str = "LOA-Scraper";
+
+/// The longest Crawl-delay honored, so a typo can't stall a run for hours.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(60);
+
+/// Returned for a request to a path the host's robots.txt disallows, or to any path on
+/// a host whose robots.txt couldn't be read.
+#[derive(Debug, Clone)]
+pub struct Disallowed {
+    pub url: String,
+    /// Why robots.txt couldn't be read, when that's what refused the request.
+    pub unreadable: Option<String>,
+}
+
+impl fmt::Display for Disallowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.unreadable {
+            Some(reason) => write!(
+                f,
+                "couldn't read robots.txt ({}), so not fetching {}",
+                reason, self.url
+            ),
+            None => write!(f, "robots.txt disallows fetching {}", self.url),
+        }
+    }
+}
+
+impl std::error::Error for Disallowed {}
+
+/// The rules one robots.txt sets for this scraper.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` pairs; the longest matching pattern decides.
+    rules: Vec<(bool, String)>,
+    pub crawl_delay: Option<Duration>,
+    /// Why robots.txt couldn't be read, when these rules stand in for it.
+    unreadable: Option<String>,
+}
+
+impl RobotsRules {
+    /// Disallows everything, for a host whose robots.txt failed with a server error or
+    /// couldn't be reached (RFC 9309, section 2.3.1.4).
+    pub fn unreadable(reason: impl Into<String>) -> Self {
+        RobotsRules {
+            rules: vec![(false, "/".to_string())],
+            crawl_delay: None,
+            unreadable: Some(reason.into()),
+        }
+    }
+
+    /// Reads the groups of `text` that name `agent`, or the `*` group when none do.
+    /// Lines it doesn't understand are ignored, as robots.txt parsers are expected to.
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut named = RobotsRules::default();
+        let mut any = RobotsRules::default();
+        let mut found_named = false;
+        // The user agents of the group being read, and whether its rules have started
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+            if key == "user-agent" {
+                if in_rules {
+                    agents.clear();
+                    in_rules = false;
+                }
+                agents.push(value.to_ascii_lowercase());
+                continue;
+            }
+            in_rules = true;
+
+            let is_named = agents
+                .iter()
+                .any(|name| name != "*" && !name.is_empty() && agent.contains(name.as_str()));
+            let target = if is_named {
+                found_named = true;
+                &mut named
+            } else if agents.iter().any(|name| name == "*") {
+                &mut any
+            } else {
+                continue;
+            };
+            match key.as_str() {
+                // An empty Disallow allows everything, so it adds no rule
+                "disallow" if !value.is_empty() => target.rules.push((false, value.to_string())),
+                "allow" if !value.is_empty() => target.rules.push((true, value.to_string())),
+                "crawl-delay" => {
+                    if let Ok(seconds) = value.parse::<f64>()
+                        && seconds.is_finite()
+                        && seconds >= 0.0
+                    {
+                        target.crawl_delay =
+                            Some(Duration::from_secs_f64(seconds).min(MAX_CRAWL_DELAY));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if found_named { named } else { any }
+    }
+
+    /// Whether `path` (with its query) may be fetched: the longest matching pattern
+    /// wins, Allow winning ties, and anything unmatched is allowed.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Matches a robots.txt path pattern, where `*` is any run of characters and a
+/// trailing `$` anchors the end; otherwise the pattern is a prefix.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let pieces: Vec<&str> = pieces.collect();
+    for (i, piece) in pieces.iter().enumerate() {
+        if anchored && i == pieces.len() - 1 {
+            return rest.ends_with(piece);
+        }
+        match rest.find(piece) {
+            Some(at) => rest = &rest[at + piece.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[derive(Debug, Default)]
+struct HostRobots {
+    rules: OnceCell<RobotsRules>,
+    /// When the next request may start under the Crawl-delay.
+    next_start: Mutex<Option<Instant>>,
+}
+
+/// Per-host robots.txt rules for a client, fetched on first use. Clones of a client
+/// share them.
+#[derive(Debug, Default)]
+pub struct Robots {
+    hosts: Mutex<HashMap<String, Arc<HostRobots>>>,
+    /// The first request refused, so loops that treat errors as "no data" can stop.
+    refused: Mutex<Option<Disallowed>>,
+}
+
+impl Robots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The first request refused as disallowed, if any.
+    pub fn refused(&self) -> Option<Disallowed> {
+        self.refused
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Fails with [`Disallowed`] if robots.txt forbids `url`, and otherwise waits out
+    /// any Crawl-delay since the last request to its host. Hosts outside
+    /// [`ROBOTS_HOSTS`] pass straight through.
+    pub async fn admit(&self, backend: &dyn Backend, url: &Url) -> Result<()> {
+        let Some(host) = url.host_str().filter(|host| ROBOTS_HOSTS.contains(host)) else {
+            return Ok(());
+        };
+        let host_robots = self
+            .hosts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(host.to_string())
+            .or_default()
+            .clone();
+        let rules = host_robots
+            .rules
+            .get_or_init(|| fetch_rules(backend, url))
+            .await;
+
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path = format!("{}?{}", path, query);
+        }
+        if url.path() != "/robots.txt" && !rules.allows(&path) {
+            let disallowed = Disallowed {
+                url: url.to_string(),
+                unreadable: rules.unreadable.clone(),
+            };
+            self.refused
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_or_insert_with(|| disallowed.clone());
+            return Err(disallowed.into());
+        }
+
+        if let Some(delay) = rules.crawl_delay {
+            // Reserve a start time under the lock so concurrent requests queue up
+            let start = {
+                let mut next_start = host_robots
+                    .next_start
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let start = next_start.map_or(now, |next| next.max(now));
+                *next_start = Some(start + delay);
+                start
+            };
+            tokio::time::sleep_until(start).await;
+        }
+        Ok(())
+    }
+}
+
+/// Fetches `url`'s host's robots.txt. A missing one (a 4xx) means no rules; a server
+/// error or failed request disallows everything, so an outage can't lift the site's
+/// limits. It goes straight to the backend, outside the budget and middleware, as it
+/// isn't one of the run's own requests.
+async fn fetch_rules(backend: &dyn Backend, url: &Url) -> RobotsRules {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return RobotsRules::default();
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static(ROBOTS_AGENT));
+    let request = Request {
+        method: Method::GET,
+        url: robots_url,
+        headers,
+        body: Vec::new(),
+    };
+    match backend.execute(request).await {
+        Ok(response) if response.status().is_success() => {
+            RobotsRules::parse(&response.text(), ROBOTS_AGENT)
+        }
+        // Including redirects past what the backend follows, which RFC 9309 also treats
+        // as unavailable
+        Ok(response)
+            if response.status().is_client_error() || response.status().is_redirection() =>
+        {
+            RobotsRules::default()
+        }
+        Ok(response) => RobotsRules::unreadable(format!("HTTP {}", response.status())),
+        Err(e) => RobotsRules::unreadable(e.to_string()),
+    }
+}
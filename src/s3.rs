@@ -0,0 +1,225 @@
+use crate::Volume;
+use crate::http::Client;
+use crate::output::{OutputFormat, OutputSink, block_on, format_for_path, write_volumes};
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::Method;
+use ring::{digest, hmac};
+use std::env;
+use std::fs;
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(digest::digest(&digest::SHA256, data).as_ref())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// Percent-encodes a key the way SigV4 expects: everything but unreserved characters,
+/// keeping the `/` between segments.
+fn encode_key(key: &str) -> String {
+    key.split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// AWS credentials and endpoint, from the standard environment variables.
+#[derive(Debug, Clone)]
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    region: String,
+    /// `AWS_ENDPOINT_URL` for S3-compatible services; AWS itself when unset.
+    endpoint: Option<String>,
+}
+
+impl Credentials {
+    fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+        Ok(Credentials {
+            access_key_id: var("AWS_ACCESS_KEY_ID")
+                .ok_or_else(|| anyhow!("--upload needs AWS_ACCESS_KEY_ID"))?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")
+                .ok_or_else(|| anyhow!("--upload needs AWS_SECRET_ACCESS_KEY"))?,
+            session_token: var("AWS_SESSION_TOKEN"),
+            region: var("AWS_REGION")
+                .or_else(|| var("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            endpoint: var("AWS_ENDPOINT_URL_S3").or_else(|| var("AWS_ENDPOINT_URL")),
+        })
+    }
+}
+
+/// An object in an S3-compatible bucket that the volumes are uploaded to, in the
+/// format its key's extension names (e.g. `s3://bucket/loa/latest.csv`). Uploading
+/// works with or without a Tokio runtime, of either flavor.
+#[derive(Debug, Clone)]
+pub struct S3Sink {
+    pub bucket: String,
+    pub key: String,
+    pub format: OutputFormat,
+    credentials: Credentials,
+}
+
+impl S3Sink {
+    /// Parses an `s3://bucket/key` URL and reads the credentials from the environment.
+    pub fn new(url: &str) -> Result<Self> {
+        let (bucket, key) = url
+            .strip_prefix("s3://")
+            .and_then(|rest| rest.split_once('/'))
+            .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+            .ok_or_else(|| anyhow!("--upload takes s3://bucket/key, not {}", url))?;
+        let format = format_for_path(key).ok_or_else(|| {
+            anyhow!(
+                "Can't tell what format to upload {} in: end the key with .csv, .json, or another output format's extension",
+                url
+            )
+        })?;
+        Ok(S3Sink {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+            format,
+            credentials: Credentials::from_env()?,
+        })
+    }
+
+    /// Path-style URLs for custom endpoints (MinIO and most other S3-compatible
+    /// services), virtual-hosted ones for AWS.
+    fn url(&self) -> String {
+        match &self.credentials.endpoint {
+            Some(endpoint) => format!(
+                "{}/{}/{}",
+                endpoint.trim_end_matches('/'),
+                self.bucket,
+                encode_key(&self.key)
+            ),
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.bucket,
+                self.credentials.region,
+                encode_key(&self.key)
+            ),
+        }
+    }
+
+    /// The `Authorization` header for a PUT, per AWS Signature Version 4, signing
+    /// all of `headers`.
+    fn authorization(
+        &self,
+        url: &reqwest::Url,
+        headers: &[(&str, String)],
+        amz_date: &str,
+        payload_hash: &str,
+    ) -> String {
+        let credentials = &self.credentials;
+        let date = &amz_date[..8];
+
+        let mut sorted: Vec<&(&str, String)> = headers.iter().collect();
+        sorted.sort_by_key(|(name, _)| *name);
+        let canonical_headers: String = sorted
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = sorted
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "PUT\n{}\n\n{}\n{}\n{}",
+            url.path(),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, credentials.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let key = [date, credentials.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part),
+            );
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            credentials.access_key_id,
+            scope,
+            signed_headers,
+            hex(&hmac_sha256(&key, &string_to_sign))
+        )
+    }
+
+    async fn upload(&self, body: Vec<u8>) -> Result<()> {
+        let url = reqwest::Url::parse(&self.url())?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = sha256_hex(&body);
+        let mut headers = vec![
+            ("host", host),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let authorization = self.authorization(&url, &headers, &amz_date, &payload_hash);
+
+        let mut request = Client::new().request(Method::PUT, url.as_str());
+        // Host is set from the URL by the HTTP client
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+        let response = request
+            .header("authorization", &authorization)
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!(
+                "S3 rejected the upload to {} ({}): {}",
+                self.describe(),
+                response.status(),
+                response.text()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl OutputSink for S3Sink {
+    fn describe(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.key)
+    }
+
+    fn write(&self, volumes: &[Volume]) -> Result<()> {
+        // Every format can write a file, and some (SQLite, XLSX) only a file
+        let file_name = self.key.rsplit('/').next().unwrap_or_default();
+        let path = env::temp_dir().join(format!("loa-upload-{}-{}", std::process::id(), file_name));
+        let _ = fs::remove_file(&path);
+        write_volumes(volumes, self.format, Some(&path.to_string_lossy()))?;
+        let body = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()));
+        let _ = fs::remove_file(&path);
+        let body = body?;
+
+        block_on(self.upload(body))
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Volume;
+use crate::scrape::{STATUS_DELISTED, STATUS_FORTHCOMING, ScrapeOptions, VolumeData};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Fraction of the previous run's volume count that may disappear before we warn.
+const MAX_COUNT_DROP: f64 = 0.10;
+/// Fraction of listings that may have an empty title before we warn.
+const MAX_EMPTY_TITLES: f64 = 0.05;
+
+/// Signs that the collection page changed shape and the parser is producing bad data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanityWarning {
+    CountDropped { previous: usize, current: usize },
+    EmptyTitles { empty: usize, total: usize },
+    DuplicateNumbers(Vec<u32>),
+}
+
+impl fmt::Display for SanityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanityWarning::CountDropped { previous, current } => write!(
+                f,
+                "volume count dropped from {} to {} since the previous output",
+                previous, current
+            ),
+            SanityWarning::EmptyTitles { empty, total } => {
+                write!(f, "{} of {} volumes have an empty title", empty, total)
+            }
+            SanityWarning::DuplicateNumbers(numbers) => write!(
+                f,
+                "duplicate volume numbers: {}",
+                numbers
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// How many of an earlier run's `volumes` a scrape with `options` is comparable with:
+/// the numbered volumes in its range and series that were still listed. These are what
+/// [`check_listings`] counts, so delistings and narrower runs don't look like a drop.
+pub fn comparable_count(volumes: &[Volume], options: &ScrapeOptions) -> usize {
+    volumes
+        .iter()
+        .filter(|v| {
+            v.volume_number > 0
+                && options.includes(v.volume_number)
+                && options.includes_series(&v.series)
+                && !matches!(
+                    v.status.as_deref(),
+                    Some(STATUS_DELISTED | STATUS_FORTHCOMING)
+                )
+        })
+        .count()
+}
+
+/// Runs cheap checks on freshly parsed listings, before any time is spent enriching them.
+/// `previous_count` is the number of volumes in the last output, when known.
+pub fn check_listings(
+    listings: &[VolumeData],
+    previous_count: Option<usize>,
+) -> Vec<SanityWarning> {
+    let mut warnings = Vec::new();
+    let total = listings.len();
+
+    if let Some(previous) = previous_count
+        && (total as f64) < previous as f64 * (1.0 - MAX_COUNT_DROP)
+    {
+        warnings.push(SanityWarning::CountDropped {
+            previous,
+            current: total,
+        });
+    }
+
+    let empty = listings
+        .iter()
+        .filter(|l| l.title.trim().is_empty())
+        .count();
+    if empty > 0 && empty as f64 > total as f64 * MAX_EMPTY_TITLES {
+        warnings.push(SanityWarning::EmptyTitles { empty, total });
+    }
+
+    // Each series numbers its volumes separately, and unnumbered publications all share 0
+    let mut seen: BTreeMap<(&str, u32), usize> = BTreeMap::new();
+    for listing in listings.iter().filter(|l| l.volume_number > 0) {
+        *seen
+            .entry((&listing.series, listing.volume_number))
+            .or_default() += 1;
+    }
+    let duplicates: Vec<u32> = seen
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|((_, number), _)| number)
+        .collect();
+    if !duplicates.is_empty() {
+        warnings.push(SanityWarning::DuplicateNumbers(duplicates));
+    }
+
+    warnings
+}
@@ -0,0 +1,843 @@
+use crate::Volume;
+use crate::author_cache::AuthorCache;
+use crate::author_overrides::{AuthorOverride, AuthorOverrides};
+use crate::http::Client;
+use crate::report::{ScrapeReport, ScrapeWarning};
+use crate::sanity::check_listings;
+use crate::viaf::find_viaf_id;
+use crate::wikidata::{AuthorInfo, AuthorResolution, resolve_authors};
+use crate::wikipedia::{
+    SEARCH_CANDIDATES, get_wikipedia_link, is_confident_match, search_wikipedia,
+};
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const LOA_BASE_URL: &str = "https://www.loa.org";
+pub const COLLECTION_URL: &str = "https://www.loa.org/books/loa_collection/";
+pub const FORTHCOMING_URL: &str = "https://www.loa.org/books/forthcoming/";
+pub const APS_URL: &str = "https://www.loa.org/books/american_poets_project/";
+
+/// `series` value for American Poets Project volumes; the numbered collection leaves
+/// it empty.
+pub const SERIES_APS: &str = "aps";
+
+/// `status` values when forthcoming titles are included.
+pub const STATUS_PUBLISHED: &str = "published";
+pub const STATUS_FORTHCOMING: &str = "forthcoming";
+/// `status` the sync store gives volumes that have dropped off the collection page.
+pub const STATUS_DELISTED: &str = "delisted";
+
+/// Which LOA catalogs to scrape. Each series has its own volume numbering.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Series {
+    /// The numbered Library of America collection
+    #[default]
+    Loa,
+    /// The American Poets Project
+    Aps,
+    /// Both
+    All,
+}
+
+impl Series {
+    pub fn includes_loa(self) -> bool {
+        matches!(self, Series::Loa | Series::All)
+    }
+
+    pub fn includes_aps(self) -> bool {
+        matches!(self, Series::Aps | Series::All)
+    }
+}
+
+/// Which part of the catalog to scrape. The volume range applies within each series.
+#[derive(Debug, Clone)]
+pub struct ScrapeOptions {
+    pub start: u32,
+    pub end: Option<u32>,
+    /// Also list announced volumes that aren't out yet.
+    pub include_forthcoming: bool,
+    /// Also list books outside the numbered series, regardless of the volume range.
+    pub include_unnumbered: bool,
+    pub series: Series,
+}
+
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        ScrapeOptions {
+            start: 1,
+            end: None,
+            include_forthcoming: false,
+            include_unnumbered: false,
+            series: Series::Loa,
+        }
+    }
+}
+
+impl ScrapeOptions {
+    pub fn includes(&self, volume_number: u32) -> bool {
+        volume_number >= self.start && self.end.is_none_or(|end| volume_number <= end)
+    }
+
+    /// Whether a volume's `series` value is one of the catalogs being scraped.
+    pub fn includes_series(&self, series: &str) -> bool {
+        match series {
+            "" => self.series.includes_loa(),
+            SERIES_APS => self.series.includes_aps(),
+            _ => false,
+        }
+    }
+}
+
+pub async fn scrape_collection_page(client: &Client) -> Result<Html> {
+    let response = client.get(COLLECTION_URL).send().await?;
+    let body = response.text();
+
+    Ok(Html::parse_document(&body))
+}
+
+pub fn is_likely_author(text: &str) -> bool {
+    // Heuristics to determine if text is likely an author name vs. a series/collection title
+
+    // If it contains "The " at the start, it's more likely a title
+    if text.starts_with("The ") {
+        return false;
+    }
+
+    // Common patterns that indicate it's NOT an author name
+    let non_author_patterns = [
+        "The American Short Story",
+        "The Best American",
+        "American Poetry",
+        "Collected Works",
+        "Complete Works",
+        "Selected Works",
+        "Early Works",
+        "Later Works",
+        "Writings",
+        "Letters",
+        "Speeches",
+        "Documents",
+        "Chronicles",
+        "Anthology",
+        "Collection",
+    ];
+
+    for pattern in &non_author_patterns {
+        if text.contains(pattern) {
+            return false;
+        }
+    }
+
+    // If it looks like "Firstname Lastname" or "F. Lastname" or "Firstname M. Lastname", it's likely an author
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    // Single word is unlikely to be an author (unless it's like "Aristotle")
+    if words.len() == 1 {
+        // Some single-name authors exist, but let's be conservative
+        return text.chars().any(|c| c.is_lowercase()); // Has lowercase letters (not all caps title)
+    }
+
+    // Two or more words - check if it looks like a name
+    if words.len() >= 2 {
+        let first_word = words[0];
+        let last_word = words[words.len() - 1];
+
+        // Check if first and last words start with capital letters (name pattern)
+        if first_word.chars().next().is_some_and(|c| c.is_uppercase())
+            && last_word.chars().next().is_some_and(|c| c.is_uppercase())
+        {
+            // Additional check: avoid things like "Civil War" or "New England"
+            if words.len() == 2
+                && (text.contains("War")
+                    || text.contains("American")
+                    || text.contains("New ")
+                    || text.contains("Old "))
+            {
+                return false;
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
+
+/// One book as listed on the collection page, before enrichment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeData {
+    pub volume_number: u32,
+    pub title: String,
+    pub author: String,
+    pub loa_detail_link: String,
+    pub original_volume_name: String,
+    pub cover_image_link: String,
+    /// `published` or `forthcoming`; empty unless forthcoming titles were requested.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub status: String,
+    /// Expected release (`2025-05`, `2025-05-06`) for forthcoming listings.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub release_date: String,
+    /// `aps` for American Poets Project listings; empty for the numbered collection.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub series: String,
+    /// What an unnumbered listing is (e.g. `special edition`); empty for numbered ones.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub publication_type: String,
+    /// The `<li>` markup this listing was parsed from, for debugging parser issues.
+    #[serde(skip)]
+    pub raw_listing_html: String,
+}
+
+/// Resolves site-relative links (e.g. `/media/...`) against the LOA host.
+pub fn absolute_url(href: &str) -> String {
+    if href.is_empty() || href.starts_with("http://") || href.starts_with("https://") {
+        href.to_string()
+    } else if let Some(rest) = href.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        format!("{}/{}", LOA_BASE_URL, href.trim_start_matches('/'))
+    }
+}
+
+/// `publication_type` values with `--include-unnumbered`. Unnumbered listings use the
+/// label shown where the number would be, falling back to `PUBLICATION_UNNUMBERED`.
+pub const PUBLICATION_NUMBERED: &str = "numbered";
+pub const PUBLICATION_UNNUMBERED: &str = "unnumbered";
+
+pub fn parse_volumes(html: &Html) -> Result<Vec<VolumeData>> {
+    parse_listings(html, false, &mut ScrapeReport::default())
+}
+
+/// Like `parse_volumes`, noting rows it skips and fields it can't find or has to guess
+/// in `report`.
+pub fn parse_volumes_with_report(
+    html: &Html,
+    report: &mut ScrapeReport,
+) -> Result<Vec<VolumeData>> {
+    parse_listings(html, false, report)
+}
+
+/// Listings outside the numbered series (special editions, paperback classics), which
+/// `parse_volumes` skips. They have volume number 0.
+pub fn parse_unnumbered(html: &Html) -> Result<Vec<VolumeData>> {
+    Ok(parse_listings(html, true, &mut ScrapeReport::default())?
+        .into_iter()
+        .filter(|listing| listing.volume_number == 0)
+        .collect())
+}
+
+pub const LISTING_SELECTOR: &str = "li.content-listing.content-listing--book";
+pub const LISTING_NUMBER_SELECTOR: &str = "i.book-listing__number";
+pub const LISTING_TITLE_SELECTOR: &str = "b.content-listing__title";
+
+/// The selectors the listing parser depends on, by what they find, so `doctor` can
+/// check each still matches the live collection page.
+pub const LISTING_SELECTORS: &[(&str, &str)] = &[
+    ("book listing", LISTING_SELECTOR),
+    ("volume number", LISTING_NUMBER_SELECTOR),
+    ("title", LISTING_TITLE_SELECTOR),
+];
+
+fn parse_listings(
+    html: &Html,
+    include_unnumbered: bool,
+    report: &mut ScrapeReport,
+) -> Result<Vec<VolumeData>> {
+    let book_listing_selector =
+        Selector::parse(LISTING_SELECTOR).map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+    let link_selector = Selector::parse("a").map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+    let number_selector = Selector::parse(LISTING_NUMBER_SELECTOR)
+        .map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+    let title_selector = Selector::parse(LISTING_TITLE_SELECTOR)
+        .map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+    let image_selector =
+        Selector::parse("img").map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+    let mut volumes = Vec::new();
+
+    for book_element in html.select(&book_listing_selector) {
+        let link_element = book_element.select(&link_selector).next();
+        let number_element = book_element.select(&number_selector).next();
+        let title_element = book_element.select(&title_selector).next();
+
+        let (Some(link), Some(title)) = (link_element, title_element) else {
+            let missing = if link_element.is_none() {
+                "no link"
+            } else {
+                "no title"
+            };
+            report.warn(ScrapeWarning::SkippedRow {
+                reason: format!("a book listing with {}", missing),
+                html: book_element.html(),
+            });
+            continue;
+        };
+        let href = link.value().attr("href").unwrap_or("");
+        let label = number_element
+            .map(|number| number.text().collect::<String>().trim().to_string())
+            .unwrap_or_default();
+        let volume_number = label.parse::<u32>().unwrap_or(0);
+        let title_text = title.text().collect::<String>().trim().to_string();
+        // Lazy-loaded images keep the real URL in data-src
+        let cover = book_element
+            .select(&image_selector)
+            .next()
+            .and_then(|img| img.value().attr("data-src").or(img.value().attr("src")))
+            .unwrap_or("");
+
+        if volume_number > 0 || include_unnumbered {
+            // Parse title which could be "Author: Title" or "Series Title: Subtitle"
+            let (author, book_title) = if let Some(colon_pos) = title_text.find(':') {
+                let before_colon = title_text[..colon_pos].trim();
+                let after_colon = title_text[colon_pos + 1..].trim();
+
+                if is_likely_author(before_colon) {
+                    // It's an author: use as author and title
+                    (before_colon.to_string(), after_colon.to_string())
+                } else {
+                    // It's likely a series or collection title: treat whole thing as title
+                    report.warn(ScrapeWarning::LowConfidence {
+                        volume_number,
+                        field: "author",
+                        detail: format!(
+                            "'{}' doesn't look like a name, so no author was split from the title",
+                            before_colon
+                        ),
+                    });
+                    (String::new(), title_text.clone())
+                }
+            } else {
+                // No colon found: treat as title with unknown author
+                report.warn(ScrapeWarning::MissingField {
+                    volume_number,
+                    title: title_text.clone(),
+                    field: "author",
+                });
+                (String::new(), title_text.clone())
+            };
+            if cover.is_empty() {
+                report.warn(ScrapeWarning::MissingField {
+                    volume_number,
+                    title: title_text.clone(),
+                    field: "cover image",
+                });
+            }
+
+            volumes.push(VolumeData {
+                volume_number,
+                title: book_title,
+                author,
+                loa_detail_link: href.to_string(),
+                original_volume_name: title_text.clone(),
+                cover_image_link: absolute_url(cover),
+                status: String::new(),
+                release_date: String::new(),
+                series: String::new(),
+                publication_type: if volume_number > 0 || !include_unnumbered {
+                    String::new()
+                } else if label.is_empty() {
+                    PUBLICATION_UNNUMBERED.to_string()
+                } else {
+                    label.to_lowercase()
+                },
+                raw_listing_html: book_element.html(),
+            });
+        }
+    }
+
+    // Stable, so unnumbered listings keep their page order
+    volumes.sort_by_key(|v| v.volume_number);
+    Ok(volumes)
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Finds an expected release date such as "Coming May 6, 2025" or "May 2025" in a
+/// listing's text, as `2025-05-06` or `2025-05`.
+pub fn find_release_date(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    let year = |word: &str| {
+        (word.len() == 4 && word.starts_with("20") && word.chars().all(|c| c.is_ascii_digit()))
+            .then(|| word.to_string())
+    };
+
+    words.iter().enumerate().find_map(|(i, word)| {
+        let month = MONTHS.iter().position(|m| {
+            m == word || (word.len() >= 3 && m.starts_with(word.trim_end_matches('.')))
+        })? + 1;
+        let next = words.get(i + 1)?;
+        if let Some(year) = year(next) {
+            return Some(format!("{}-{:02}", year, month));
+        }
+        let day: u32 = next
+            .trim_end_matches(|c: char| c.is_alphabetic())
+            .parse()
+            .ok()?;
+        let year = year(words.get(i + 2)?)?;
+        (1..=31)
+            .contains(&day)
+            .then(|| format!("{}-{:02}-{:02}", year, month, day))
+    })
+}
+
+/// Fetches and parses the collection page, keeping only listings in range, plus
+/// forthcoming titles, American Poets Project volumes, and unnumbered publications
+/// when `options` asks for them.
+pub async fn list_volumes(client: &Client, options: &ScrapeOptions) -> Result<Vec<VolumeData>> {
+    list_volumes_with_report(client, options, None, &mut ScrapeReport::default()).await
+}
+
+/// Like `list_volumes`, noting in `report` what the collection page's parse skipped or
+/// guessed, and whether the listings look suspicious. `previous_count` is how many
+/// numbered volumes in range the last run found, when known, to warn if it dropped.
+pub async fn list_volumes_with_report(
+    client: &Client,
+    options: &ScrapeOptions,
+    previous_count: Option<usize>,
+    report: &mut ScrapeReport,
+) -> Result<Vec<VolumeData>> {
+    let mut listings = Vec::new();
+    let mut unnumbered = Vec::new();
+    if options.series.includes_loa() {
+        let html = scrape_collection_page(client).await?;
+        listings = parse_volumes_with_report(&html, report)?
+            .into_iter()
+            .filter(|listing| options.includes(listing.volume_number))
+            .collect();
+        if options.include_unnumbered {
+            unnumbered = parse_unnumbered(&html)?;
+        }
+    }
+    if options.series.includes_aps() {
+        listings.extend(list_aps(client, options).await?);
+    }
+
+    // Checked before forthcoming and unnumbered titles join them, as those aren't
+    // numbered listings of the collection
+    for warning in check_listings(&listings, previous_count) {
+        report.warn(ScrapeWarning::Suspicious(warning));
+    }
+
+    if options.include_forthcoming && options.series.includes_loa() {
+        let forthcoming = list_forthcoming(client, options).await?;
+        merge_forthcoming(&mut listings, forthcoming);
+    }
+    if options.include_unnumbered {
+        merge_unnumbered(&mut listings, unnumbered);
+    }
+    Ok(listings)
+}
+
+/// Marks the numbered listings as such and appends the unnumbered ones after them.
+pub fn merge_unnumbered(listings: &mut Vec<VolumeData>, unnumbered: Vec<VolumeData>) {
+    for listing in listings.iter_mut() {
+        listing.publication_type = PUBLICATION_NUMBERED.to_string();
+    }
+    listings.extend(unnumbered);
+}
+
+/// Fetches the American Poets Project volumes in range, tagged with `series`. The
+/// series page uses the same listing markup as the collection page.
+pub async fn list_aps(client: &Client, options: &ScrapeOptions) -> Result<Vec<VolumeData>> {
+    let response = client.get(APS_URL).send().await?;
+    let html = Html::parse_document(&response.text());
+
+    Ok(parse_volumes(&html)?
+        .into_iter()
+        .filter(|listing| options.includes(listing.volume_number))
+        .map(|listing| VolumeData {
+            series: SERIES_APS.to_string(),
+            ..listing
+        })
+        .collect())
+}
+
+/// Fetches announced volumes in range, with `status` and `release_date` filled in.
+pub async fn list_forthcoming(client: &Client, options: &ScrapeOptions) -> Result<Vec<VolumeData>> {
+    let response = client.get(FORTHCOMING_URL).send().await?;
+    let html = Html::parse_document(&response.text());
+
+    Ok(parse_volumes(&html)?
+        .into_iter()
+        .filter(|listing| options.includes(listing.volume_number))
+        .map(|listing| {
+            let text = Html::parse_fragment(&listing.raw_listing_html)
+                .root_element()
+                .text()
+                .collect::<Vec<_>>()
+                .join(" ");
+            VolumeData {
+                status: STATUS_FORTHCOMING.to_string(),
+                release_date: find_release_date(&text).unwrap_or_default(),
+                ..listing
+            }
+        })
+        .collect())
+}
+
+/// Marks the collection's listings as published and adds forthcoming ones it doesn't
+/// already have, keeping volume order.
+pub fn merge_forthcoming(listings: &mut Vec<VolumeData>, forthcoming: Vec<VolumeData>) {
+    for listing in listings.iter_mut() {
+        listing.status = STATUS_PUBLISHED.to_string();
+    }
+    for upcoming in forthcoming {
+        if !listings
+            .iter()
+            .any(|l| l.series.is_empty() && l.volume_number == upcoming.volume_number)
+        {
+            listings.push(upcoming);
+        }
+    }
+    listings.sort_by(|a, b| (&a.series, a.volume_number).cmp(&(&b.series, b.volume_number)));
+}
+
+/// `enrichment_status` for author data served from the cache.
+pub const STATUS_CACHED: &str = "cached";
+/// `enrichment_status` for authors that could not be looked up at all.
+pub const STATUS_UNAVAILABLE: &str = "unavailable";
+
+/// How long to wait before retrying lookups that failed because a service was down.
+const RETRY_DELAY_MS: u64 = 5000;
+
+/// One of the people or articles an ambiguous author lookup turned up.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorCandidate {
+    /// The article's title, or the Wikidata item's when it has no English article.
+    pub title: String,
+    pub description: String,
+    pub info: AuthorInfo,
+}
+
+/// What to make of an author the lookups couldn't settle on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorChoice {
+    /// The candidate at this index.
+    Pick(usize),
+    /// None of them: the author has no article.
+    NoArticle,
+    /// Take the lookup's best guess this time, and ask again next run.
+    Skip,
+}
+
+/// Looks up each listing's author and builds the output rows, calling `on_volume` as
+/// each one completes. Authors are resolved in bulk through Wikidata; only those it
+/// can't match fall back to a Wikipedia search, and those without a VIAF ID to VIAF's
+/// AutoSuggest, once per distinct author.
+pub async fn enrich_volumes(
+    client: &Client,
+    listings: &[VolumeData],
+    on_volume: impl FnMut(&Volume),
+) -> Result<Vec<Volume>> {
+    enrich_volumes_with_cache(
+        client,
+        listings,
+        &mut AuthorCache::default(),
+        &mut AuthorOverrides::default(),
+        &mut ScrapeReport::default(),
+        |_, _| AuthorChoice::Skip,
+        on_volume,
+    )
+    .await
+}
+
+/// The lookup `chosen` settles on: the chosen person's dates and IDs when Wikidata
+/// matched them this run, with the chosen article.
+fn overridden(author: &str, chosen: &AuthorOverride, resolution: &AuthorResolution) -> AuthorInfo {
+    let matched = match chosen.qid.as_str() {
+        "" if chosen.wikipedia_link.is_empty() => None,
+        "" => resolution.found.get(author).cloned(),
+        qid => resolution
+            .ambiguous
+            .get(author)
+            .into_iter()
+            .flatten()
+            .map(|candidate| &candidate.info)
+            .chain(resolution.found.get(author))
+            .find(|info| info.qid == qid)
+            .cloned()
+            .or_else(|| {
+                Some(AuthorInfo {
+                    qid: qid.to_string(),
+                    ..Default::default()
+                })
+            }),
+    };
+    AuthorInfo {
+        wikipedia_link: chosen.wikipedia_link.clone(),
+        ..matched.unwrap_or_default()
+    }
+}
+
+/// Offers `candidates` to `decide`, recording a definite answer in `overrides`. None
+/// means keep the lookup's best guess, which `report` notes.
+fn ask(
+    author: &str,
+    candidates: &[AuthorCandidate],
+    decide: &mut impl FnMut(&str, &[AuthorCandidate]) -> AuthorChoice,
+    overrides: &mut AuthorOverrides,
+    report: &mut ScrapeReport,
+) -> Option<AuthorInfo> {
+    let info = match decide(author, candidates) {
+        AuthorChoice::Pick(i) => candidates.get(i)?.info.clone(),
+        AuthorChoice::NoArticle => AuthorInfo::default(),
+        AuthorChoice::Skip => {
+            report.warn(ScrapeWarning::UncertainAuthor {
+                author: author.to_string(),
+                wikipedia_link: candidates[0].info.wikipedia_link.clone(),
+            });
+            return None;
+        }
+    };
+    overrides.authors.insert(
+        author.to_string(),
+        AuthorOverride {
+            wikipedia_link: info.wikipedia_link.clone(),
+            qid: info.qid.clone(),
+        },
+    );
+    Some(info)
+}
+
+/// Like `enrich_volumes`, but falls back to `cache` when the lookup services are
+/// unreachable, and records fresh lookups in it. Authors with neither are retried once
+/// at the end; rows whose data didn't come from this run are marked in
+/// `enrichment_status`.
+///
+/// Authors in `overrides` skip the lookups. When a name matches several comparably
+/// notable people, or a search's best article isn't plainly the author's, `decide`
+/// picks among the candidates and its definite answers are added to `overrides`.
+/// Guesses it keeps and authors it couldn't look up are noted in `report`.
+pub async fn enrich_volumes_with_cache(
+    client: &Client,
+    listings: &[VolumeData],
+    cache: &mut AuthorCache,
+    overrides: &mut AuthorOverrides,
+    report: &mut ScrapeReport,
+    mut decide: impl FnMut(&str, &[AuthorCandidate]) -> AuthorChoice,
+    mut on_volume: impl FnMut(&Volume),
+) -> Result<Vec<Volume>> {
+    let mut authors: Vec<String> = listings
+        .iter()
+        .map(|listing| listing.author.clone())
+        .filter(|author| !author.is_empty())
+        .collect();
+    authors.sort();
+    authors.dedup();
+    let resolution = resolve_authors(client, &authors).await;
+    client.check_refused()?;
+
+    // Per author: the data to use and its enrichment_status, or None to retry later
+    let mut lookups: HashMap<String, Option<(AuthorInfo, &str)>> = HashMap::new();
+    let mut volumes = Vec::with_capacity(listings.len());
+
+    for listing in listings {
+        let author = &listing.author;
+        if !author.is_empty() && !lookups.contains_key(author) {
+            let cached = cache.authors.get(author).cloned();
+            let mut lookup = match resolution.found.get(author) {
+                _ if overrides.authors.contains_key(author) => {
+                    let chosen = &overrides.authors[author];
+                    Some((overridden(author, chosen, &resolution), ""))
+                }
+                Some(info) if !info.wikipedia_link.is_empty() => {
+                    let candidates: Vec<AuthorCandidate> = resolution
+                        .ambiguous
+                        .get(author)
+                        .into_iter()
+                        .flatten()
+                        .map(|candidate| AuthorCandidate {
+                            title: match candidate.info.wikipedia_link.rsplit_once("/wiki/") {
+                                Some((_, title)) => urlencoding::decode(title)
+                                    .map_or_else(|_| title.to_string(), |t| t.replace('_', " ")),
+                                None => candidate.info.qid.clone(),
+                            },
+                            description: candidate.description.clone(),
+                            info: candidate.info.clone(),
+                        })
+                        .collect();
+                    let chosen = if candidates.is_empty() {
+                        None
+                    } else {
+                        ask(author, &candidates, &mut decide, overrides, report)
+                    };
+                    Some((chosen.unwrap_or_else(|| info.clone()), ""))
+                }
+                _ if resolution.unreachable.contains(author) && cached.is_some() => {
+                    cached.map(|info| (info, STATUS_CACHED))
+                }
+                found => {
+                    client.check_budget()?;
+                    let search = search_wikipedia(client, author, SEARCH_CANDIDATES).await;
+                    client.check_refused()?;
+
+                    match search {
+                        Ok(articles) => {
+                            let found = found.cloned().unwrap_or_default();
+                            let candidates: Vec<AuthorCandidate> = articles
+                                .iter()
+                                .map(|article| AuthorCandidate {
+                                    title: article.title.clone(),
+                                    description: article.description.clone(),
+                                    info: AuthorInfo {
+                                        wikipedia_link: article.url.clone(),
+                                        ..found.clone()
+                                    },
+                                })
+                                .collect();
+                            let chosen =
+                                if candidates.is_empty() || is_confident_match(author, &articles) {
+                                    None
+                                } else {
+                                    ask(author, &candidates, &mut decide, overrides, report)
+                                };
+                            let best = candidates.into_iter().next().map(|c| c.info);
+                            Some((chosen.or(best).unwrap_or(found), ""))
+                        }
+                        Err(_) => cached.map(|info| (info, STATUS_CACHED)),
+                    }
+                }
+            };
+
+            // Fresh lookups without a VIAF ID from Wikidata ask VIAF directly
+            if let Some((info, "")) = &mut lookup
+                && info.viaf_id.is_empty()
+            {
+                client.check_budget()?;
+                if let Ok(id) = find_viaf_id(client, author).await {
+                    info.viaf_id = id;
+                }
+                client.check_refused()?;
+            }
+            lookups.insert(author.clone(), lookup);
+        }
+
+        let (info, status) = lookups
+            .get(author)
+            .cloned()
+            .flatten()
+            .unwrap_or((AuthorInfo::default(), ""));
+        let volume = Volume {
+            volume_number: listing.volume_number,
+            title: listing.title.clone(),
+            author: listing.author.clone(),
+            author_wikipedia_link: info.wikipedia_link,
+            author_wikidata_id: Some(info.qid),
+            author_viaf_id: Some(info.viaf_id),
+            author_birth_date: Some(info.birth_date),
+            author_death_date: Some(info.death_date),
+            enrichment_status: Some(status.to_string()),
+            loa_detail_link: listing.loa_detail_link.clone(),
+            original_volume_name: listing.original_volume_name.clone(),
+            own_volume: String::new(),
+            cover_image_link: listing.cover_image_link.clone(),
+            status: Some(listing.status.clone()).filter(|s| !s.is_empty()),
+            release_date: (!listing.status.is_empty()).then(|| listing.release_date.clone()),
+            series: listing.series.clone(),
+            publication_type: Some(listing.publication_type.clone()).filter(|t| !t.is_empty()),
+            ..Default::default()
+        };
+
+        on_volume(&volume);
+        volumes.push(volume);
+    }
+
+    let retry: Vec<String> = lookups
+        .iter()
+        .filter(|(_, lookup)| lookup.is_none())
+        .map(|(author, _)| author.clone())
+        .collect();
+    if !retry.is_empty() && !client.is_cancelled() {
+        tokio::time::sleep(tokio::time::Duration::from_millis(RETRY_DELAY_MS)).await;
+    }
+    for author in retry {
+        let found = resolution.found.get(&author).cloned().unwrap_or_default();
+        // Out of budget or cancelled: the rows are already emitted, so settle for
+        // "unavailable" and let the caller's next check stop the run
+        let search = match client.check_budget() {
+            Ok(()) => get_wikipedia_link(client, &author).await,
+            Err(e) => Err(e),
+        };
+        let (info, status) = match search {
+            Ok(link) if client.check_refused().is_ok() => (
+                AuthorInfo {
+                    wikipedia_link: link,
+                    ..found
+                },
+                "",
+            ),
+            _ => (found, STATUS_UNAVAILABLE),
+        };
+        for volume in volumes.iter_mut().filter(|v| v.author == author) {
+            volume.author_wikipedia_link = info.wikipedia_link.clone();
+            volume.author_wikidata_id = Some(info.qid.clone());
+            volume.author_viaf_id = Some(info.viaf_id.clone());
+            volume.author_birth_date = Some(info.birth_date.clone());
+            volume.author_death_date = Some(info.death_date.clone());
+            volume.enrichment_status = Some(status.to_string());
+        }
+        lookups.insert(author, Some((info, status)));
+    }
+
+    let mut lookups: Vec<_> = lookups.into_iter().collect();
+    lookups.sort_by(|a, b| a.0.cmp(&b.0));
+    for (author, lookup) in lookups {
+        match lookup {
+            Some((info, "")) => {
+                cache.authors.insert(author, info);
+            }
+            Some((_, status)) => report.warn(ScrapeWarning::AuthorUnavailable {
+                author,
+                cached: status == STATUS_CACHED,
+            }),
+            None => {}
+        }
+    }
+
+    Ok(volumes)
+}
+
+/// Runs the full scrape: collection page, parsing, and Wikipedia enrichment.
+pub async fn scrape(client: &Client, options: &ScrapeOptions) -> Result<Vec<Volume>> {
+    Ok(scrape_with_report(client, options).await?.0)
+}
+
+/// Like `scrape`, also returning the warnings gathered along the way.
+pub async fn scrape_with_report(
+    client: &Client,
+    options: &ScrapeOptions,
+) -> Result<(Vec<Volume>, ScrapeReport)> {
+    let mut report = ScrapeReport::new();
+    let listings = list_volumes_with_report(client, options, None, &mut report).await?;
+    let volumes = enrich_volumes_with_cache(
+        client,
+        &listings,
+        &mut AuthorCache::default(),
+        &mut AuthorOverrides::default(),
+        &mut report,
+        |_, _| AuthorChoice::Skip,
+        |_| {},
+    )
+    .await?;
+    Ok((volumes, report))
+}
@@ -0,0 +1,64 @@
+use crate::Volume;
+
+/// Edit distance between two words, counted in characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// How many typos a query word of this length may contain and still match.
+fn allowed_typos(word: &str) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Finds volumes whose author or title matches `query`, ignoring case. Every query word
+/// must appear; with `fuzzy`, words also match with a typo or two.
+pub fn search<'a>(volumes: &'a [Volume], query: &str, fuzzy: bool) -> Vec<&'a Volume> {
+    let query_words = words(query);
+    if query_words.is_empty() {
+        return Vec::new();
+    }
+
+    volumes
+        .iter()
+        .filter(|volume| {
+            let haystack = format!(
+                "{} {} {}",
+                volume.author, volume.title, volume.original_volume_name
+            )
+            .to_lowercase();
+            let haystack_words = words(&haystack);
+
+            query_words.iter().all(|word| {
+                haystack.contains(word.as_str())
+                    || (fuzzy
+                        && haystack_words
+                            .iter()
+                            .any(|candidate| levenshtein(word, candidate) <= allowed_typos(word)))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,93 @@
+use crate::details::parse_details;
+use crate::http::Client;
+use crate::scrape::{
+    VolumeData, absolute_url, parse_unnumbered, parse_volumes, scrape_collection_page,
+};
+use anyhow::{Result, anyhow};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const BOXED_SETS_URL: &str = "https://www.loa.org/books/boxed_sets/";
+
+/// A boxed set LOA sells, and the numbered volumes it bundles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoxedSet {
+    pub title: String,
+    pub loa_detail_link: String,
+    /// List and member prices in dollars (e.g. `95.00`); empty when the page didn't say.
+    pub price_usd: String,
+    pub member_price_usd: String,
+    /// Volume numbers in the order the set's page lists them.
+    pub volume_numbers: Vec<u32>,
+}
+
+/// Finds the numbered volumes a set's page includes: links to their detail pages, and
+/// any listings in the collection-page markup.
+pub fn find_members(html: &Html, catalog: &[VolumeData]) -> Result<Vec<u32>> {
+    let by_link: HashMap<String, u32> = catalog
+        .iter()
+        .map(|listing| {
+            (
+                absolute_url(&listing.loa_detail_link),
+                listing.volume_number,
+            )
+        })
+        .collect();
+    let link_selector =
+        Selector::parse("a[href]").map_err(|e| anyhow!("CSS selector error: {:?}", e))?;
+
+    let linked = html
+        .select(&link_selector)
+        .filter_map(|a| a.value().attr("href"))
+        .filter_map(|href| by_link.get(&absolute_url(href)).copied());
+    let listed = parse_volumes(html)?
+        .into_iter()
+        .map(|listing| listing.volume_number);
+
+    let mut members = Vec::new();
+    for number in linked.chain(listed) {
+        if !members.contains(&number) {
+            members.push(number);
+        }
+    }
+    Ok(members)
+}
+
+/// Fetches the boxed-set listing and each set's page, calling `on_set` as each one
+/// completes. Sets whose page fails to load are kept without a price or members.
+pub async fn scrape_boxed_sets(
+    client: &Client,
+    mut on_set: impl FnMut(&BoxedSet),
+) -> Result<Vec<BoxedSet>> {
+    let catalog = parse_volumes(&scrape_collection_page(client).await?)?;
+    let response = client.get(BOXED_SETS_URL).send().await?;
+    let listings = parse_unnumbered(&Html::parse_document(&response.text()))?;
+
+    let mut sets = Vec::with_capacity(listings.len());
+    for listing in listings {
+        client.check_budget()?;
+        let mut set = BoxedSet {
+            title: listing.original_volume_name,
+            loa_detail_link: listing.loa_detail_link,
+            ..Default::default()
+        };
+        let url = absolute_url(&set.loa_detail_link);
+        if !url.is_empty()
+            && let Ok(response) = client.get(&url).send().await
+            && response.status().is_success()
+        {
+            let html = Html::parse_document(&response.text());
+            let details = parse_details(&html);
+            set.price_usd = details.price_usd.unwrap_or_default();
+            set.member_price_usd = details.member_price_usd.unwrap_or_default();
+            set.volume_numbers = find_members(&html, &catalog)?;
+        }
+        client.check_refused()?;
+
+        on_set(&set);
+        sets.push(set);
+    }
+
+    Ok(sets)
+}
@@ -0,0 +1,311 @@
+use crate::Volume;
+use crate::http::Client;
+use crate::output::{OutputSink, Table, block_on};
+use crate::volume::{UPSERT_KEY, dedupe};
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use reqwest::Method;
+use ring::rand::SystemRandom;
+use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const API_URL: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+/// The fields of a Google service-account key file that signing in needs.
+#[derive(Debug, Deserialize)]
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+impl ServiceAccount {
+    /// A signed JWT asking for Sheets access, per Google's server-to-server OAuth flow.
+    fn assertion(&self) -> Result<String> {
+        let now = chrono::Utc::now().timestamp();
+        let header = URL_SAFE_NO_PAD.encode(json!({"alg": "RS256", "typ": "JWT"}).to_string());
+        let claims = URL_SAFE_NO_PAD.encode(
+            json!({
+                "iss": self.client_email,
+                "scope": SCOPE,
+                "aud": self.token_uri,
+                "iat": now,
+                "exp": now + 3600,
+            })
+            .to_string(),
+        );
+        let message = format!("{}.{}", header, claims);
+
+        let pem: String = self
+            .private_key
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let der = STANDARD
+            .decode(pem.trim())
+            .context("Bad private_key in the service account file")?;
+        let key = RsaKeyPair::from_pkcs8(&der)
+            .map_err(|e| anyhow!("Bad private_key in the service account file: {}", e))?;
+        let mut signature = vec![0; key.public().modulus_len()];
+        key.sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            message.as_bytes(),
+            &mut signature,
+        )
+        .map_err(|_| anyhow!("Failed to sign the Google token request"))?;
+
+        Ok(format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature)))
+    }
+
+    async fn access_token(&self, client: &Client) -> Result<String> {
+        let assertion = self.assertion()?;
+        let response = client
+            .request(Method::POST, &self.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", &assertion),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!(
+                "Google rejected the service account ({}): {}",
+                response.status(),
+                response.text()
+            );
+        }
+        let body: Value = response.json()?;
+        body.get("access_token")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Google token response has no access_token"))
+    }
+}
+
+/// `0` → `A`, `26` → `AA`.
+fn column_letter(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// A worksheet of a Google spreadsheet that volumes are written to in place. Rows are
+/// matched on a `row_key` column and new volumes are appended; only the scraped
+/// columns are written, so columns added by hand (and their cells) are left alone.
+/// Writing works with or without a Tokio runtime, of either flavor.
+#[derive(Debug, Clone)]
+pub struct SheetsSink {
+    pub spreadsheet_id: String,
+    pub worksheet: String,
+    pub credentials: PathBuf,
+}
+
+impl SheetsSink {
+    fn range(&self, range: &str) -> String {
+        format!("'{}'!{}", self.worksheet.replace('\'', "''"), range)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}{}",
+            API_URL,
+            urlencoding::encode(&self.spreadsheet_id),
+            path
+        )
+    }
+
+    async fn call(
+        &self,
+        client: &Client,
+        token: &str,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        let mut request = client.request(method, url).bearer_auth(token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            bail!(
+                "Google Sheets request failed ({}): {}",
+                response.status(),
+                response.text()
+            );
+        }
+        response.json()
+    }
+
+    /// Adds the worksheet if the spreadsheet doesn't have it yet.
+    async fn ensure_worksheet(&self, client: &Client, token: &str) -> Result<()> {
+        let url = format!("{}?fields=sheets.properties.title", self.url(""));
+        let spreadsheet = self.call(client, token, Method::GET, &url, None).await?;
+        let exists = spreadsheet
+            .get("sheets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .any(|s| {
+                s.pointer("/properties/title").and_then(Value::as_str) == Some(&self.worksheet)
+            });
+        if !exists {
+            let body =
+                json!({"requests": [{"addSheet": {"properties": {"title": self.worksheet}}}]});
+            self.call(
+                client,
+                token,
+                Method::POST,
+                &self.url(":batchUpdate"),
+                Some(&body),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn sync(&self, volumes: &[Volume]) -> Result<()> {
+        let text = std::fs::read_to_string(&self.credentials)
+            .with_context(|| format!("Failed to open {}", self.credentials.display()))?;
+        let account: ServiceAccount = serde_json::from_str(&text).with_context(|| {
+            format!("{} isn't a service account key", self.credentials.display())
+        })?;
+        let client = Client::new();
+        let token = account.access_token(&client).await?;
+        self.ensure_worksheet(&client, &token).await?;
+
+        let url = self.url(&format!(
+            "/values/{}",
+            urlencoding::encode(&self.range("A:ZZZ"))
+        ));
+        let existing = self.call(&client, &token, Method::GET, &url, None).await?;
+        let mut grid: Vec<Vec<String>> = existing
+            .get("values")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|row| {
+                row.as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|cell| {
+                        cell.as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| cell.to_string())
+                    })
+                    .collect()
+            })
+            .collect();
+        if grid.is_empty() {
+            grid.push(Vec::new());
+        }
+
+        let table = Table::from_volumes(volumes)?;
+        let mut headers = vec![UPSERT_KEY.to_string()];
+        headers.extend(table.headers.iter().cloned());
+
+        // Scraped columns keep their place; ones the sheet doesn't have yet go after
+        // everything else, so they never land on a column added by hand.
+        let mut columns = Vec::with_capacity(headers.len());
+        for header in &headers {
+            let index = match grid[0].iter().position(|h| h == header) {
+                Some(index) => index,
+                None => {
+                    grid[0].push(header.clone());
+                    grid[0].len() - 1
+                }
+            };
+            columns.push(index);
+        }
+
+        let key_column = columns[0];
+        let mut rows_by_key: HashMap<String, usize> = grid
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, row)| Some((row.get(key_column)?.clone(), i)))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+        for (volume, row) in volumes.iter().zip(&table.rows) {
+            let index = *rows_by_key.entry(volume.key()).or_insert_with(|| {
+                grid.push(Vec::new());
+                grid.len() - 1
+            });
+            let cells = std::iter::once(volume.key()).chain(row.iter().cloned());
+            for (&column, cell) in columns.iter().zip(cells) {
+                let line = &mut grid[index];
+                if line.len() <= column {
+                    line.resize(column + 1, String::new());
+                }
+                line[column] = cell;
+            }
+        }
+
+        // Write each contiguous run of scraped columns as its own range
+        let mut sorted = columns.clone();
+        sorted.sort_unstable();
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for column in sorted {
+            match runs.last_mut() {
+                Some((_, end)) if *end + 1 == column => *end = column,
+                _ => runs.push((column, column)),
+            }
+        }
+        let data: Vec<Value> = runs
+            .iter()
+            .map(|&(start, end)| {
+                let values: Vec<Vec<&str>> = grid
+                    .iter()
+                    .map(|row| {
+                        (start..=end)
+                            .map(|c| row.get(c).map(String::as_str).unwrap_or_default())
+                            .collect()
+                    })
+                    .collect();
+                json!({
+                    "range": self.range(&format!(
+                        "{}1:{}{}",
+                        column_letter(start),
+                        column_letter(end),
+                        grid.len()
+                    )),
+                    "values": values,
+                })
+            })
+            .collect();
+        let body = json!({"valueInputOption": "RAW", "data": data});
+        self.call(
+            &client,
+            &token,
+            Method::POST,
+            &self.url("/values:batchUpdate"),
+            Some(&body),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+impl OutputSink for SheetsSink {
+    fn describe(&self) -> String {
+        format!("Google Sheets {} ({})", self.spreadsheet_id, self.worksheet)
+    }
+
+    fn write(&self, volumes: &[Volume]) -> Result<()> {
+        let volumes = dedupe(volumes);
+        block_on(self.sync(&volumes))
+    }
+}
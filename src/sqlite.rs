@@ -0,0 +1,251 @@
+use crate::Volume;
+use crate::volume::{Row, UPSERT_KEY, upsert_rows};
+use anyhow::Result;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, params_from_iter};
+
+// Predefined reports, recreated on every write so they always match the current schema.
+const VIEWS: &[(&str, &str)] = &[
+    (
+        "missing_volumes",
+        "SELECT * FROM volumes WHERE own_volume = '' ORDER BY volume_number",
+    ),
+    (
+        "owned_by_author",
+        "SELECT author,
+                COUNT(*) AS total_volumes,
+                SUM(own_volume <> '') AS owned_volumes
+         FROM volumes
+         WHERE author <> ''
+         GROUP BY author
+         ORDER BY owned_volumes DESC, author",
+    ),
+    (
+        "forthcoming",
+        "SELECT * FROM volumes
+         WHERE status = 'forthcoming'
+         ORDER BY release_date, volume_number",
+    ),
+    (
+        "price_drops",
+        "SELECT *,
+                ROUND(CAST(previous_price_usd AS REAL) - CAST(price_usd AS REAL), 2)
+                    AS price_drop_usd
+         FROM volumes
+         WHERE price_usd <> ''
+           AND CAST(price_usd AS REAL) < CAST(previous_price_usd AS REAL)
+         ORDER BY price_drop_usd DESC, volume_number",
+    ),
+];
+
+/// Optional columns the views read, added empty when no volume has carried them yet.
+const VIEW_COLUMNS: &[&str] = &["status", "release_date", "price_usd", "previous_price_usd"];
+
+fn to_sql_value(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn sql_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Bool(_) => "INTEGER",
+        serde_json::Value::Number(n) if n.is_i64() => "INTEGER",
+        serde_json::Value::Number(_) => "REAL",
+        _ => "TEXT",
+    }
+}
+
+/// Creates the `volumes` table keyed on `UPSERT_KEY`. Tables written before
+/// unnumbered publications and other series were scraped are keyed on
+/// `volume_number`, so they are rebuilt with the same columns and rows under the new
+/// key.
+fn create_table(conn: &Connection) -> Result<()> {
+    let create = format!(
+        "CREATE TABLE IF NOT EXISTS volumes (\"{}\" TEXT PRIMARY KEY, volume_number INTEGER)",
+        UPSERT_KEY
+    );
+    conn.execute_batch(&create)?;
+
+    let columns: Vec<(String, String)> = conn
+        .prepare("SELECT name, type FROM pragma_table_info('volumes')")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    if columns.iter().any(|(name, _)| name == UPSERT_KEY) {
+        return Ok(());
+    }
+
+    for (name, _) in VIEWS {
+        conn.execute_batch(&format!("DROP VIEW IF EXISTS {name};"))?;
+    }
+    conn.execute_batch("ALTER TABLE volumes RENAME TO volumes_unkeyed")?;
+    conn.execute_batch(&create)?;
+    for (name, sql_type) in columns.iter().filter(|(name, _)| name != "volume_number") {
+        conn.execute_batch(&format!(
+            "ALTER TABLE volumes ADD COLUMN \"{}\" {}",
+            name, sql_type
+        ))?;
+    }
+    let names = columns
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Every row in these tables is a numbered volume; see `Volume::key`
+    let series = if columns.iter().any(|(name, _)| name == "series") {
+        "COALESCE(NULLIF(series, ''), 'loa')"
+    } else {
+        "'loa'"
+    };
+    conn.execute_batch(&format!(
+        "INSERT INTO volumes (\"{key}\", {names}) SELECT {series} || '-' || volume_number, {names} FROM volumes_unkeyed; DROP TABLE volumes_unkeyed;",
+        key = UPSERT_KEY
+    ))?;
+    Ok(())
+}
+
+/// Creates the `volumes` table if needed and adds any columns that newer versions
+/// of `Volume` have introduced since the database was first written.
+fn ensure_schema(conn: &Connection, sample: &Row) -> Result<()> {
+    create_table(conn)?;
+
+    let existing: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('volumes')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    for (column, value) in sample {
+        if !existing.contains(column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE volumes ADD COLUMN \"{}\" {}",
+                column,
+                sql_type(value)
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts or updates volumes by `Volume::key`, so writing the same volumes twice leaves one
+/// row each. Fields a volume doesn't carry (e.g. `isbn`
+/// without `--details`) are left as they were in existing rows.
+pub(crate) fn upsert_volumes(conn: &Connection, volumes: &[Volume]) -> Result<()> {
+    let (rows, sample) = upsert_rows(volumes)?;
+    ensure_schema(conn, &sample)?;
+
+    for row in &rows {
+        let columns: Vec<&String> = row.keys().collect();
+        let sql = format!(
+            "INSERT INTO volumes ({}) VALUES ({}) ON CONFLICT(\"{}\") DO UPDATE SET {}",
+            columns
+                .iter()
+                .map(|c| format!("\"{}\"", c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            (1..=columns.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", "),
+            UPSERT_KEY,
+            columns
+                .iter()
+                .map(|c| format!("\"{0}\" = excluded.\"{0}\"", c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        conn.prepare_cached(&sql)?
+            .execute(params_from_iter(row.values().map(to_sql_value)))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn create_views(conn: &Connection) -> Result<()> {
+    create_table(conn)?;
+    let existing: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('volumes')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for column in VIEW_COLUMNS
+        .iter()
+        .filter(|c| !existing.iter().any(|e| e == *c))
+    {
+        conn.execute_batch(&format!(
+            "ALTER TABLE volumes ADD COLUMN \"{}\" TEXT",
+            column
+        ))?;
+    }
+    for (name, query) in VIEWS {
+        conn.execute_batch(&format!(
+            "DROP VIEW IF EXISTS {name}; CREATE VIEW {name} AS {query};"
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn write_sqlite(volumes: &[Volume], path: &str) -> Result<()> {
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+    upsert_volumes(&tx, volumes)?;
+    create_views(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn volume_count(path: &str) -> Result<usize> {
+    let conn = Connection::open(path)?;
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM volumes", [], |row| row.get(0))?;
+    Ok(count as usize)
+}
+
+fn from_sql_value(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) | ValueRef::Blob(t) => String::from_utf8_lossy(t).into_owned().into(),
+    }
+}
+
+/// Reads back volumes written by `write_sqlite`, ignoring columns `Volume` doesn't know.
+pub fn read_sqlite(path: &str) -> Result<Vec<Volume>> {
+    read_volumes(&Connection::open(path)?)
+}
+
+pub(crate) fn read_volumes(conn: &Connection) -> Result<Vec<Volume>> {
+    let mut stmt = conn.prepare("SELECT * FROM volumes ORDER BY volume_number, rowid")?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    let rows = stmt
+        .query_map([], |row| {
+            let mut map = serde_json::Map::new();
+            // NULL means the row never had the field, which `Volume` reads as `None`
+            for (i, column) in columns.iter().enumerate() {
+                let value = row.get_ref(i)?;
+                if !matches!(value, ValueRef::Null) {
+                    map.insert(column.clone(), from_sql_value(value));
+                }
+            }
+            Ok(map)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut volumes = rows
+        .into_iter()
+        .map(|row| Ok(serde_json::from_value(serde_json::Value::Object(row))?))
+        .collect::<Result<Vec<Volume>>>()?;
+    // Series first, then unnumbered publications last in each; sorted here rather than
+    // in SQL so databases without a `series` column still read
+    volumes
+        .sort_by(|a, b| (&a.series, a.volume_number == 0).cmp(&(&b.series, b.volume_number == 0)));
+    Ok(volumes)
+}
@@ -0,0 +1,135 @@
+use crate::Volume;
+use crate::ownership::OwnedState;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Volume counts for one author.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorCount {
+    pub author: String,
+    pub volumes: usize,
+    pub owned: usize,
+}
+
+/// Summary figures for a set of volumes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub owned: usize,
+    pub missing: usize,
+    /// Authors by number of volumes, most first.
+    pub per_author: Vec<AuthorCount>,
+    /// Volumes with an author that has no Wikipedia link.
+    pub without_wikipedia: Vec<u32>,
+    /// Volumes by decade of LOA publication (e.g. 1990), for volumes with a known year.
+    pub per_decade: BTreeMap<i32, usize>,
+    /// Volumes marked owned with `own add`, by year acquired.
+    pub acquired_per_year: BTreeMap<i32, usize>,
+}
+
+fn year_of(text: &str) -> Option<i32> {
+    text.get(..4)?.parse().ok()
+}
+
+impl Stats {
+    pub fn from_volumes(volumes: &[Volume], owned: &OwnedState) -> Self {
+        let mut per_decade = BTreeMap::new();
+        for year in volumes
+            .iter()
+            .filter_map(|v| v.publication_year.as_deref().and_then(year_of))
+        {
+            *per_decade.entry(year - year.rem_euclid(10)).or_default() += 1;
+        }
+
+        let mut acquired_per_year = BTreeMap::new();
+        for year in owned.acquired.values().filter_map(|date| year_of(date)) {
+            *acquired_per_year.entry(year).or_default() += 1;
+        }
+
+        let mut authors: BTreeMap<&str, AuthorCount> = BTreeMap::new();
+        for volume in volumes.iter().filter(|v| !v.author.is_empty()) {
+            let count = authors
+                .entry(volume.author.as_str())
+                .or_insert_with(|| AuthorCount {
+                    author: volume.author.clone(),
+                    volumes: 0,
+                    owned: 0,
+                });
+            count.volumes += 1;
+            if !volume.own_volume.is_empty() {
+                count.owned += 1;
+            }
+        }
+        let mut per_author: Vec<AuthorCount> = authors.into_values().collect();
+        per_author.sort_by(|a, b| b.volumes.cmp(&a.volumes).then(a.author.cmp(&b.author)));
+
+        let owned_count = volumes.iter().filter(|v| !v.own_volume.is_empty()).count();
+
+        Stats {
+            total: volumes.len(),
+            owned: owned_count,
+            missing: volumes.len() - owned_count,
+            per_author,
+            without_wikipedia: volumes
+                .iter()
+                .filter(|v| !v.author.is_empty() && v.author_wikipedia_link.is_empty())
+                .map(|v| v.volume_number)
+                .collect(),
+            per_decade,
+            acquired_per_year,
+        }
+    }
+
+    pub fn owned_percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.owned as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    /// A shields.io endpoint badge (https://shields.io/badges/endpoint-badge) showing
+    /// collection progress.
+    pub fn badge(&self) -> serde_json::Value {
+        let color = match self.owned_percent() {
+            p if p >= 100.0 => "brightgreen",
+            p if p >= 75.0 => "green",
+            p if p >= 50.0 => "yellowgreen",
+            p if p >= 25.0 => "yellow",
+            _ => "orange",
+        };
+
+        json!({
+            "schemaVersion": 1,
+            "label": "Library of America",
+            "message": format!("owned {}/{}", self.owned, self.total),
+            "color": color,
+        })
+    }
+}
+
+/// Expands a sparse histogram into consecutive buckets `step` apart, zero-filling gaps.
+pub fn fill_gaps(counts: &BTreeMap<i32, usize>, step: i32) -> Vec<(i32, usize)> {
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Vec::new();
+    };
+    (first..=last)
+        .step_by(step as usize)
+        .map(|bucket| (bucket, counts.get(&bucket).copied().unwrap_or(0)))
+        .collect()
+}
+
+/// Renders counts as a row of unicode block characters scaled to the largest value.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| match v {
+            0 => ' ',
+            _ => SPARK_BARS[((v * SPARK_BARS.len()).div_ceil(max) - 1).min(SPARK_BARS.len() - 1)],
+        })
+        .collect()
+}
@@ -0,0 +1,183 @@
+use crate::Volume;
+use crate::scrape::{STATUS_DELISTED, STATUS_FORTHCOMING, ScrapeOptions};
+use crate::sqlite::{create_views, read_volumes, upsert_volumes};
+use crate::volume::dedupe;
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where `sync` keeps its database unless overridden with `--store`.
+pub fn default_store_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("loa-scraper")
+        .join("store.db")
+}
+
+/// What changed in the store during one `sync`. Volumes are listed by `Volume::key`,
+/// since numbers alone repeat across series and are 0 for every unnumbered title.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+    /// Stored volumes in the synced range that were not on the collection page. They
+    /// stay in the store with `status` set to `delisted`.
+    pub missing: Vec<String>,
+    /// The part of `missing` delisted by this sync rather than an earlier one.
+    pub delisted: Vec<String>,
+    /// Volumes whose list price differs from the stored one.
+    pub repriced: Vec<PriceChange>,
+}
+
+/// A volume's list price before and after a `sync`, in dollars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceChange {
+    pub volume: Volume,
+    pub old_price: String,
+    pub new_price: String,
+}
+
+/// The local database of every volume ever scraped, with when each was first and
+/// last seen. It uses the same `volumes` table and views as `--format sqlite`.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open store {}", path.display()))?;
+        create_views(&conn)?;
+        Ok(Store { conn })
+    }
+
+    pub fn volumes(&self) -> Result<Vec<Volume>> {
+        read_volumes(&self.conn)
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM volumes", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Upserts freshly scraped volumes, stamping them as seen at `seen_at`, and marks
+    /// stored volumes in range that weren't scraped as delisted. Syncing the same
+    /// volumes again reports them unchanged and adds no rows.
+    pub fn sync(
+        &mut self,
+        volumes: &[Volume],
+        options: &ScrapeOptions,
+        seen_at: &str,
+    ) -> Result<SyncReport> {
+        let stored = self.volumes()?;
+        let existing: HashMap<String, &Volume> = stored.iter().map(|v| (v.key(), v)).collect();
+        let volumes = &dedupe(volumes);
+        let mut report = SyncReport::default();
+        let mut stamped = Vec::with_capacity(volumes.len());
+
+        for volume in volumes {
+            let mut volume = volume.clone();
+            let previous = existing.get(&volume.key()).copied();
+            match previous {
+                Some(previous) => {
+                    // Back on the collection page after being delisted
+                    if previous.status.as_deref() == Some(STATUS_DELISTED) {
+                        volume.status.get_or_insert_with(String::new);
+                        volume.delisted_on = Some(String::new());
+                    }
+                    if let (Some(old), Some(new)) = (&previous.price_usd, &volume.price_usd)
+                        && !old.is_empty()
+                        && !new.is_empty()
+                        && old != new
+                    {
+                        report.repriced.push(PriceChange {
+                            volume: volume.clone(),
+                            old_price: old.clone(),
+                            new_price: new.clone(),
+                        });
+                        volume.previous_price_usd = Some(old.clone());
+                    }
+                    if differs(previous, &volume)? {
+                        report.changed.push(volume.key());
+                    } else {
+                        report.unchanged += 1;
+                    }
+                    volume.first_seen = previous
+                        .first_seen
+                        .clone()
+                        .or_else(|| Some(seen_at.to_string()));
+                }
+                None => {
+                    report.added.push(volume.key());
+                    volume.first_seen = Some(seen_at.to_string());
+                }
+            }
+            volume.last_seen = Some(seen_at.to_string());
+            stamped.push(volume);
+        }
+
+        // Gone from the listing: keep the row, with its ownership, as a tombstone.
+        // Forthcoming titles are only expected on the page with --include-forthcoming.
+        let fresh: HashSet<String> = volumes.iter().map(Volume::key).collect();
+        for previous in &stored {
+            let number = previous.volume_number;
+            let in_range = if number == 0 {
+                options.include_unnumbered
+            } else {
+                options.includes(number)
+            };
+            if !options.includes_series(&previous.series)
+                || !in_range
+                || fresh.contains(&previous.key())
+                || (!options.include_forthcoming
+                    && previous.status.as_deref() == Some(STATUS_FORTHCOMING))
+            {
+                continue;
+            }
+            report.missing.push(previous.key());
+            if previous.status.as_deref() != Some(STATUS_DELISTED) {
+                report.delisted.push(previous.key());
+                stamped.push(Volume {
+                    status: Some(STATUS_DELISTED.to_string()),
+                    delisted_on: Some(seen_at.to_string()),
+                    ..previous.clone()
+                });
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        upsert_volumes(&tx, &stamped)?;
+        create_views(&tx)?;
+        tx.commit()?;
+
+        Ok(report)
+    }
+}
+
+/// Whether any field the fresh scrape carries differs from the stored row. Fields the
+/// scrape didn't fetch (e.g. `isbn` without `--details`) don't count as changes.
+fn differs(stored: &Volume, fresh: &Volume) -> Result<bool> {
+    let stored = serde_json::to_value(stored)?;
+    let fresh = serde_json::to_value(fresh)?;
+    let (Some(stored), Some(fresh)) = (stored.as_object(), fresh.as_object()) else {
+        return Ok(true);
+    };
+
+    Ok(fresh
+        .iter()
+        .filter(|(field, _)| {
+            !matches!(
+                field.as_str(),
+                "first_seen" | "last_seen" | "previous_price_usd"
+            )
+        })
+        .any(|(field, value)| stored.get(field) != Some(value)))
+}
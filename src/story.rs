@@ -0,0 +1,133 @@
+use crate::Volume;
+use crate::http::Client;
+use crate::scrape::absolute_url;
+use crate::volume::normalize_title;
+use anyhow::{Result, anyhow};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+pub const STORY_OF_THE_WEEK_URL: &str = "https://storyoftheweek.loa.org/";
+
+/// One story from the Story of the Week archive.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Story {
+    pub title: String,
+    pub author: String,
+    /// Title of the LOA volume the story was taken from, and its page.
+    pub source_volume: String,
+    pub source_link: String,
+    pub link: String,
+}
+
+fn selector(css: &str) -> Result<Selector> {
+    Selector::parse(css).map_err(|e| anyhow!("CSS selector error: {:?}", e))
+}
+
+fn text_of(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a link points at an LOA book page, i.e. the story's source volume.
+fn is_book_link(href: &str) -> bool {
+    absolute_url(href).starts_with("https://www.loa.org/books/")
+}
+
+/// Parses one archive page into stories and the link to the next (older) page.
+pub fn parse_archive(html: &Html, page_url: &str) -> Result<(Vec<Story>, Option<String>)> {
+    let entry_selector = selector("article, .post, .story")?;
+    let title_selector = selector(".post-title a, .entry-title a, h2 a, h3 a")?;
+    let author_selector = selector(".author, .byline, .post-author, [rel=author]")?;
+    let link_selector = selector("a[href]")?;
+    let next_selector = selector("a.blog-pager-older-link, a[rel=next], a.next")?;
+    let base = Url::parse(page_url)?;
+    let resolve = |href: &str| {
+        base.join(href)
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| href.to_string())
+    };
+
+    let mut stories = Vec::new();
+    for entry in html.select(&entry_selector) {
+        let Some(title) = entry.select(&title_selector).next() else {
+            continue;
+        };
+        let author = entry
+            .select(&author_selector)
+            .next()
+            .map(text_of)
+            .unwrap_or_default();
+        let source = entry
+            .select(&link_selector)
+            .find(|a| a.value().attr("href").is_some_and(is_book_link));
+
+        stories.push(Story {
+            title: text_of(title),
+            author: author
+                .strip_prefix("by ")
+                .or_else(|| author.strip_prefix("By "))
+                .unwrap_or(&author)
+                .to_string(),
+            source_volume: source.map(text_of).unwrap_or_default(),
+            source_link: source
+                .and_then(|a| a.value().attr("href"))
+                .map(absolute_url)
+                .unwrap_or_default(),
+            link: title.value().attr("href").map(resolve).unwrap_or_default(),
+        });
+    }
+
+    let next = html
+        .select(&next_selector)
+        .next()
+        .and_then(|a| a.value().attr("href"))
+        .map(resolve);
+    Ok((stories, next))
+}
+
+/// Fetches up to `pages` archive pages, newest first.
+pub async fn scrape_stories(client: &Client, pages: usize) -> Result<Vec<Story>> {
+    let mut stories = Vec::new();
+    let mut url = Some(STORY_OF_THE_WEEK_URL.to_string());
+    for _ in 0..pages {
+        let Some(page_url) = url.take() else {
+            break;
+        };
+        client.check_budget()?;
+        let response = client.get(&page_url).send().await?;
+        let (found, next) = parse_archive(&Html::parse_document(&response.text()), &page_url)?;
+        client.check_refused()?;
+        stories.extend(found);
+        url = next;
+    }
+    Ok(stories)
+}
+
+/// Finds the volume a story came from: by its source link when the dataset has the
+/// same detail page, otherwise by the source title.
+pub fn source_volume<'a>(story: &Story, volumes: &'a [Volume]) -> Option<&'a Volume> {
+    if !story.source_link.is_empty()
+        && let Some(volume) = volumes
+            .iter()
+            .find(|v| absolute_url(&v.loa_detail_link) == story.source_link)
+    {
+        return Some(volume);
+    }
+
+    let source = normalize_title(&story.source_volume);
+    if source.is_empty() {
+        return None;
+    }
+    volumes.iter().find(|v| {
+        [&v.title, &v.original_volume_name]
+            .iter()
+            .map(|name| normalize_title(name))
+            .any(|name| !name.is_empty() && (name == source || source.contains(&name)))
+    })
+}
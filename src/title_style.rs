@@ -0,0 +1,136 @@
+use crate::Volume;
+use clap::ValueEnum;
+
+/// How titles are capitalized on export. LOA's pages mix styles, so a catalog with a
+/// house style can have them rewritten consistently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TitleStyle {
+    /// Leave titles exactly as LOA wrote them.
+    #[default]
+    AsScraped,
+    /// Chicago Manual of Style headline case.
+    Chicago,
+    /// Only the first word, the first word after a colon, and proper names capitalized.
+    Sentence,
+}
+
+/// Words Chicago lowercases unless they start or end the title (or follow a colon):
+/// articles, coordinating conjunctions, prepositions, and "to" and "as".
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "but", "for", "or", "nor", "yet", "so", "to", "as", "at", "by", "in",
+    "of", "off", "on", "per", "up", "via", "from", "into", "onto", "upon", "with", "about",
+    "above", "across", "after", "against", "along", "among", "around", "before", "behind", "below",
+    "beneath", "beside", "between", "beyond", "during", "except", "inside", "near", "outside",
+    "over", "past", "since", "through", "toward", "towards", "under", "until", "within", "without",
+];
+
+/// Whether a word carries capitals a case change would destroy: acronyms, initials,
+/// Roman numerals, and names like "McCarthy".
+fn is_styled(word: &str) -> bool {
+    word.chars().skip(1).any(char::is_uppercase)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Splits off leading and trailing punctuation, so "(Selected" and "Poems:" case their
+/// letters alone.
+fn split_punctuation(word: &str) -> (&str, &str, &str) {
+    let start = word
+        .find(|c: char| c.is_alphanumeric())
+        .unwrap_or(word.len());
+    let end = word
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + word[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(start);
+    (&word[..start], &word[start..end], &word[end..])
+}
+
+fn chicago(title: &str) -> String {
+    let words: Vec<&str> = title.split(' ').collect();
+    let last = words.iter().rposition(|w| !w.is_empty());
+    let mut starts_phrase = true;
+    let mut out = Vec::with_capacity(words.len());
+    for (i, word) in words.iter().enumerate() {
+        let (lead, core, trail) = split_punctuation(word);
+        let cased = if is_styled(core) {
+            core.to_string()
+        } else {
+            core.split('-')
+                .enumerate()
+                .map(|(j, part)| {
+                    let lower = part.to_lowercase();
+                    let edge = (starts_phrase && j == 0) || Some(i) == last;
+                    if !edge && MINOR_WORDS.contains(&lower.as_str()) {
+                        lower
+                    } else {
+                        capitalize(&lower)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("-")
+        };
+        out.push(format!("{}{}{}", lead, cased, trail));
+        if !core.is_empty() {
+            starts_phrase = trail.ends_with([':', '?', '!', '.', '—']);
+        }
+    }
+    out.join(" ")
+}
+
+/// Sentence case can't tell a proper noun from any other word, so words from the
+/// volume's author name keep their capital; LOA titles usually lead with it.
+fn sentence(title: &str, proper: &[String]) -> String {
+    let mut starts_phrase = true;
+    let mut out = Vec::new();
+    for word in title.split(' ') {
+        let (lead, core, trail) = split_punctuation(word);
+        let keep = is_styled(core) || proper.iter().any(|name| name == core) || core == "I";
+        let cased = if keep {
+            core.to_string()
+        } else if starts_phrase {
+            capitalize(&core.to_lowercase())
+        } else {
+            core.to_lowercase()
+        };
+        out.push(format!("{}{}{}", lead, cased, trail));
+        if !core.is_empty() {
+            starts_phrase = trail.ends_with([':', '?', '!', '.', '—']);
+        }
+    }
+    out.join(" ")
+}
+
+impl TitleStyle {
+    /// Restyles one title. `author` supplies the proper names sentence case keeps.
+    pub fn apply(self, title: &str, author: &str) -> String {
+        match self {
+            TitleStyle::AsScraped => title.to_string(),
+            TitleStyle::Chicago => chicago(title),
+            TitleStyle::Sentence => {
+                let proper: Vec<String> = author
+                    .split(|c: char| !c.is_alphanumeric() && c != '\'')
+                    .filter(|name| name.chars().next().is_some_and(char::is_uppercase))
+                    .map(str::to_string)
+                    .collect();
+                sentence(title, &proper)
+            }
+        }
+    }
+}
+
+/// Rewrites the title fields (`title` and `original_volume_name`) in the given style.
+pub fn restyle(volumes: &mut [Volume], style: TitleStyle) {
+    if style == TitleStyle::AsScraped {
+        return;
+    }
+    for volume in volumes {
+        volume.title = style.apply(&volume.title, &volume.author);
+        volume.original_volume_name = style.apply(&volume.original_volume_name, &volume.author);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::http::Client;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+
+pub const RELEASES_URL: &str = "https://api.github.com/repos/nfachan/loa-scraper/releases/latest";
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// The parts of a GitHub release the update check needs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+impl Release {
+    /// The version the tag names, without a leading `v`.
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub fn is_newer(&self) -> bool {
+        is_newer(self.version(), CURRENT_VERSION)
+    }
+
+    /// The prebuilt binary for this platform, named after its OS and architecture
+    /// (e.g. `loa-scraper-x86_64-linux`). Archives and checksums are skipped, since
+    /// only a bare binary can replace the running one.
+    pub fn platform_asset(&self) -> Option<&Asset> {
+        let os_names: &[&str] = match std::env::consts::OS {
+            "macos" => &["macos", "darwin", "apple"],
+            "windows" => &["windows", "msvc"],
+            os => &[os],
+        };
+        self.assets.iter().find(|asset| {
+            let name = asset.name.to_lowercase();
+            name.contains(std::env::consts::ARCH)
+                && os_names.iter().any(|os| name.contains(os))
+                && ![".tar.gz", ".tgz", ".zip", ".sha256", ".sig", ".asc"]
+                    .iter()
+                    .any(|ext| name.ends_with(ext))
+        })
+    }
+}
+
+fn numeric_parts(version: &str) -> Vec<u64> {
+    let mut parts: Vec<u64> = version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    // "1.2" and "1.2.0" are the same release
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    parts
+}
+
+/// Compares dotted versions numerically, ignoring pre-release and build suffixes.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    numeric_parts(latest) > numeric_parts(current)
+}
+
+fn user_agent() -> String {
+    format!(
+        "LOA-Scraper/{} (https://github.com/nfachan/loa-scraper)",
+        CURRENT_VERSION
+    )
+}
+
+/// Fetches the latest published release from GitHub.
+pub async fn latest_release(client: &Client) -> Result<Release> {
+    let response = client
+        .get(RELEASES_URL)
+        .header("User-Agent", &user_agent())
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("GitHub releases returned {}", response.status()));
+    }
+    response.json()
+}
+
+/// Downloads a release asset's bytes.
+pub async fn download(client: &Client, asset: &Asset) -> Result<Vec<u8>> {
+    let response = client
+        .get(&asset.browser_download_url)
+        .header("User-Agent", &user_agent())
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Downloading {} returned {}",
+            asset.name,
+            response.status()
+        ));
+    }
+    Ok(response.bytes().to_vec())
+}
@@ -0,0 +1,39 @@
+use crate::http::Client;
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+const AUTOSUGGEST_URL: &str = "https://viaf.org/viaf/AutoSuggest";
+
+/// Finds the VIAF cluster ID for a personal name via AutoSuggest. Returns an empty ID
+/// when there is no match, and an error only when VIAF couldn't be reached.
+pub async fn find_viaf_id(client: &Client, author: &str) -> Result<String> {
+    let response = client
+        .get(AUTOSUGGEST_URL)
+        .query(&[("query", author)])
+        .header(
+            "User-Agent",
+            "LOA-Scraper/1.0 (https://github.com/example/loa-scraper)",
+        )
+        .send()
+        .await?;
+    if response.status().is_server_error() {
+        return Err(anyhow!("VIAF returned {}", response.status()));
+    }
+
+    // AutoSuggest answers `{"result": null}` when nothing matches
+    let json: Value = response.json().unwrap_or_default();
+    let id = json
+        .get("result")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .find(|result| result.get("nametype").and_then(Value::as_str) == Some("personal"))
+        .and_then(|result| result.get("viafid"))
+        .and_then(|id| {
+            id.as_str()
+                .map(str::to_string)
+                .or(id.as_u64().map(|n| n.to_string()))
+        })
+        .unwrap_or_default();
+    Ok(id)
+}
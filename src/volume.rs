@@ -0,0 +1,484 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// The column SQLite sinks key rows on, holding `Volume::key`. SQLite and the sync
+/// store upsert on it and file outputs keep one row per key, so re-running a command
+/// with the same inputs never duplicates a volume.
+pub const UPSERT_KEY: &str = "row_key";
+
+/// The columns `Volume::key` is built from, which can't be redacted.
+pub const KEY_COLUMNS: &[&str] = &["series", "volume_number", "loa_detail_link"];
+
+/// Keeps a column that is present but empty as `Some("")` ("looked up, nothing found"),
+/// since CSV reports empty cells as missing. Absent columns still default to `None`.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(Some(
+        Option::<String>::deserialize(deserializer)?.unwrap_or_default(),
+    ))
+}
+
+/// Writes the 0 that marks an unnumbered publication as a blank cell (`null` in JSON).
+fn blank_if_unnumbered<S: Serializer>(number: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+    match number {
+        0 => serializer.serialize_none(),
+        n => serializer.serialize_some(n),
+    }
+}
+
+fn unnumbered_if_blank<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(Option::<u32>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Volume {
+    /// 0 for special publications outside the numbered series, written as blank.
+    #[serde(
+        default,
+        serialize_with = "blank_if_unnumbered",
+        deserialize_with = "unnumbered_if_blank"
+    )]
+    pub volume_number: u32,
+    pub title: String,
+    pub author: String,
+    pub author_wikipedia_link: String,
+    /// Wikidata QID (e.g. `Q4985`) of the author, when one could be matched.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub author_wikidata_id: Option<String>,
+    /// VIAF cluster ID of the author; steadier than the Wikipedia link for deduplicating.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub author_viaf_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub author_birth_date: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub author_death_date: Option<String>,
+    /// Empty when the author data was fetched this run; "cached" when it came from an
+    /// earlier run because the lookup services were unreachable; "unavailable" when
+    /// neither worked.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub enrichment_status: Option<String>,
+    /// `published` or `forthcoming` with `--include-forthcoming`; `delisted` in the sync
+    /// store once a volume drops off the collection page.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub status: Option<String>,
+    /// Expected release date of a forthcoming volume; only present with
+    /// `--include-forthcoming`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub release_date: Option<String>,
+    /// When `sync` first found the volume gone from the collection page; only present
+    /// from the store.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub delisted_on: Option<String>,
+    /// `numbered` or what an unnumbered book is (e.g. `special edition`); only present
+    /// with `--include-unnumbered`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub publication_type: Option<String>,
+    /// `aps` for American Poets Project volumes, which have their own numbering; empty
+    /// for the numbered collection.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub series: String,
+    pub loa_detail_link: String,
+    /// Wayback Machine snapshot of a retired detail page; only present with `--details`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub archived_detail_link: Option<String>,
+    pub original_volume_name: String,
+    pub own_volume: String,
+    pub cover_image_link: String,
+    /// Local file the cover was downloaded to; only present with `--covers`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub cover_path: Option<String>,
+    /// ISBN-13 from the volume's detail page; only present with `--details`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub isbn: Option<String>,
+    /// Year the LOA edition was published, from the detail page; only present with `--details`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub publication_year: Option<String>,
+    /// List and member prices in dollars (e.g. `45.00`) and stock status (e.g. `in stock`,
+    /// `out of stock`) from the detail page or its store listing; only present with
+    /// `--details`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub price_usd: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub member_price_usd: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub availability: Option<String>,
+    /// "yes" if LOA still sells the volume, "no" if it's out of print or its page was
+    /// retired, empty when unknown; only present with `--details`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub in_print: Option<String>,
+    /// Collection-page markup the row was parsed from; only present with `--debug-raw`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub raw_listing_html: Option<String>,
+    /// OpenLibrary IDs (e.g. `OL45804W`, `OL7353617M`); only present with `--enrich openlibrary`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub openlibrary_work_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub openlibrary_edition_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub openlibrary_cover_url: Option<String>,
+    /// OpenLibrary average rating (out of 5) and how many ratings it's based on; only
+    /// present with `--enrich ratings`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub rating_average: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub ratings_count: Option<String>,
+    /// OCLC number from WorldCat; only present with `--enrich worldcat`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub oclc_number: Option<String>,
+    /// Library of Congress control number and its `lccn.loc.gov` permalink; only
+    /// present with `--enrich loc`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub lccn: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub loc_permalink: Option<String>,
+    /// When `sync` first saw the volume on the collection page; only present from the store.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub first_seen: Option<String>,
+    /// When `sync` last saw the volume on the collection page; only present from the store.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub last_seen: Option<String>,
+    /// The list price before the last change `sync` saw; only present from the store.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub previous_price_usd: Option<String>,
+}
+
+/// The years in a title, e.g. 1930 and 1942 in "Novels 1930–1942".
+fn title_years(title: &str) -> Vec<i32> {
+    title
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|run| run.len() == 4)
+        .filter_map(|run| run.parse().ok())
+        .filter(|year| (1500..=2100).contains(year))
+        .collect()
+}
+
+fn year(date: Option<&str>) -> Option<i32> {
+    date?.get(..4)?.parse().ok()
+}
+
+impl Volume {
+    /// The first and last years a title names, e.g. 1930 and 1942 for "Novels 1930–1942".
+    pub fn title_period(&self) -> Option<(i32, i32)> {
+        let years = title_years(&self.title);
+        Some((*years.iter().min()?, *years.iter().max()?))
+    }
+
+    /// The first and last years of the writing a volume collects: the years its title
+    /// spans, otherwise its author's lifetime.
+    pub fn period(&self) -> Option<(i32, i32)> {
+        if let Some(period) = self.title_period() {
+            return Some(period);
+        }
+        let born = year(self.author_birth_date.as_deref())?;
+        let died = year(self.author_death_date.as_deref())?;
+        Some((born, died))
+    }
+
+    /// The row's identity across every sink: `loa-12` or `aps-3` for numbered volumes,
+    /// and the detail link for unnumbered publications.
+    pub fn key(&self) -> String {
+        if self.volume_number == 0 {
+            return self.loa_detail_link.clone();
+        }
+        let series = if self.series.is_empty() {
+            "loa"
+        } else {
+            &self.series
+        };
+        format!("{}-{}", series, self.volume_number)
+    }
+}
+
+/// Collapses volumes that share a key, keeping the last one (the row an upsert would
+/// leave behind) where the key first appeared.
+pub fn dedupe(volumes: &[Volume]) -> Vec<Volume> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut unique: Vec<Volume> = Vec::with_capacity(volumes.len());
+    for volume in volumes {
+        match positions.get(&volume.key()) {
+            Some(&pos) => unique[pos] = volume.clone(),
+            None => {
+                positions.insert(volume.key(), unique.len());
+                unique.push(volume.clone());
+            }
+        }
+    }
+    unique
+}
+
+/// A volume as the database sinks store it: column name to value.
+pub(crate) type Row = serde_json::Map<String, serde_json::Value>;
+
+/// The rows the database sinks upsert: one per key (see [`dedupe`]), each with its
+/// `UPSERT_KEY` column, and a sample row with a value for every column any of them
+/// carries, non-null where one has it, to build the table's schema from. Rows can
+/// differ in which optional fields they carry.
+pub(crate) fn upsert_rows(volumes: &[Volume]) -> Result<(Vec<Row>, Row)> {
+    let rows = dedupe(volumes)
+        .iter()
+        .map(|v| match serde_json::to_value(v)? {
+            serde_json::Value::Object(mut map) => {
+                map.insert(UPSERT_KEY.to_string(), v.key().into());
+                Ok(map)
+            }
+            _ => Err(anyhow!("Volume did not serialize to an object")),
+        })
+        .collect::<Result<Vec<Row>>>()?;
+
+    let mut sample = Row::new();
+    for row in &rows {
+        for (column, value) in row {
+            if !sample.contains_key(column) || sample[column].is_null() {
+                sample.insert(column.clone(), value.clone());
+            }
+        }
+    }
+    Ok((rows, sample))
+}
+
+/// The author side of a volume, for tools that deduplicate or link authors.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Author {
+    pub name: String,
+    pub wikipedia_link: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub wikidata_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub viaf_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub birth_date: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub death_date: Option<String>,
+}
+
+impl Author {
+    /// `None` for anthologies and other volumes without a named author.
+    pub fn from_volume(volume: &Volume) -> Option<Self> {
+        if volume.author.is_empty() {
+            return None;
+        }
+        Some(Author {
+            name: volume.author.clone(),
+            wikipedia_link: volume.author_wikipedia_link.clone(),
+            wikidata_id: volume.author_wikidata_id.clone(),
+            viaf_id: volume.author_viaf_id.clone(),
+            birth_date: volume.author_birth_date.clone(),
+            death_date: volume.author_death_date.clone(),
+        })
+    }
+}
+
+/// Lowercases a title and drops its punctuation, so "Typee;" and "typee" compare equal.
+pub(crate) fn normalize_title(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The bibliographic side of a volume: what it is and the catalog IDs it's known by.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Work {
+    pub volume_number: u32,
+    pub title: String,
+    pub author: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub isbn: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub publication_year: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub openlibrary_work_id: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub oclc_number: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub lccn: Option<String>,
+}
+
+impl Work {
+    pub fn from_volume(volume: &Volume) -> Self {
+        Work {
+            volume_number: volume.volume_number,
+            title: volume.title.clone(),
+            author: volume.author.clone(),
+            isbn: volume.isbn.clone(),
+            publication_year: volume.publication_year.clone(),
+            openlibrary_work_id: volume.openlibrary_work_id.clone(),
+            oclc_number: volume.oclc_number.clone(),
+            lccn: volume.lccn.clone(),
+        }
+    }
+}
+
+/// Whether one volume is owned, and since when if `own add` recorded it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ownership {
+    pub volume_number: u32,
+    pub owned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquired: Option<String>,
+}
+
+impl Ownership {
+    pub fn from_volume(volume: &Volume) -> Self {
+        Ownership {
+            volume_number: volume.volume_number,
+            owned: !volume.own_volume.is_empty(),
+            acquired: None,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::http::Client;
+use serde_json::Value;
+
+const AVAILABILITY_API: &str = "https://archive.org/wayback/available";
+
+/// Asks the Internet Archive for the snapshot of `url` closest to now, returning its
+/// Wayback Machine URL. Lookup failures are treated as "no snapshot".
+pub async fn closest_snapshot(client: &Client, url: &str) -> Option<String> {
+    let response = client
+        .get(AVAILABILITY_API)
+        .query(&[("url", url)])
+        .send()
+        .await
+        .ok()?;
+    let json: Value = response.json().ok()?;
+    let closest = json.pointer("/archived_snapshots/closest")?;
+
+    if closest.get("available").and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    closest
+        .get("url")
+        .and_then(Value::as_str)
+        .map(|snapshot| snapshot.replacen("http://", "https://", 1))
+}
@@ -0,0 +1,87 @@
+use crate::Volume;
+use crate::http::Client;
+use anyhow::{Result, anyhow};
+use reqwest::Method;
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+
+/// Attempts per volume before giving up on it.
+const ATTEMPTS: u32 = 3;
+
+/// How many volumes reached the webhook, and the last error for those that didn't.
+#[derive(Debug, Default)]
+pub struct WebhookReport {
+    pub sent: usize,
+    pub failed: usize,
+    pub last_error: Option<String>,
+}
+
+/// POSTs each volume as JSON to a URL from a background task, in the order they were
+/// emitted, so the scrape never waits on the receiving end. Failed posts are retried
+/// with backoff; volumes that still fail are counted rather than stopping the run.
+pub struct WebhookEmitter {
+    sender: UnboundedSender<Volume>,
+    task: JoinHandle<WebhookReport>,
+}
+
+async fn post(client: &Client, url: &str, volume: &Volume) -> Result<()> {
+    let mut delay = Duration::from_secs(1);
+    let mut attempt = 1;
+    loop {
+        let error = match client.request(Method::POST, url).json(volume).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            // A 4xx other than 429 won't go away on retry
+            Ok(response)
+                if response.status().is_client_error()
+                    && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                return Err(anyhow!("webhook returned {}", response.status()));
+            }
+            Ok(response) => anyhow!("webhook returned {}", response.status()),
+            Err(e) => e,
+        };
+        if attempt == ATTEMPTS {
+            return Err(error);
+        }
+        attempt += 1;
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+impl WebhookEmitter {
+    /// Starts the background task; must be called inside a tokio runtime.
+    pub fn start(url: &str) -> Result<Self> {
+        reqwest::Url::parse(url).map_err(|e| anyhow!("Bad --emit-webhook URL {}: {}", url, e))?;
+        let url = url.to_string();
+        let (sender, mut receiver) = unbounded_channel::<Volume>();
+        let task = tokio::spawn(async move {
+            // Its own client, so webhook posts don't count against --max-requests
+            let client = Client::new();
+            let mut report = WebhookReport::default();
+            while let Some(volume) = receiver.recv().await {
+                match post(&client, &url, &volume).await {
+                    Ok(()) => report.sent += 1,
+                    Err(e) => {
+                        report.failed += 1;
+                        report.last_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            report
+        });
+        Ok(WebhookEmitter { sender, task })
+    }
+
+    pub fn emit(&self, volume: &Volume) {
+        // The task only stops once the sender is dropped, so this can't fail
+        let _ = self.sender.send(volume.clone());
+    }
+
+    /// Waits for every emitted volume to be posted.
+    pub async fn finish(self) -> Result<WebhookReport> {
+        drop(self.sender);
+        Ok(self.task.await?)
+    }
+}
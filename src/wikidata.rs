@@ -0,0 +1,239 @@
+use crate::http::Client;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+const SPARQL_ENDPOINT: &str = "https://query.wikidata.org/sparql";
+/// Authors per query; keeps the VALUES clause well under the endpoint's URL limits.
+pub const CHUNK_SIZE: usize = 50;
+
+/// What Wikidata knows about an author, matched by exact English label.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorInfo {
+    pub qid: String,
+    pub birth_date: String,
+    pub death_date: String,
+    pub wikipedia_link: String,
+    /// VIAF cluster ID, from Wikidata or else VIAF's own AutoSuggest.
+    #[serde(default)]
+    pub viaf_id: String,
+}
+
+fn sparql_string(text: &str) -> String {
+    format!("\"{}\"@en", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn query_for(names: &[String]) -> String {
+    let values: Vec<String> = names.iter().map(|name| sparql_string(name)).collect();
+    format!(
+        r#"SELECT ?name ?item ?birth ?death ?viaf ?article ?sitelinks ?description WHERE {{
+  VALUES ?name {{ {} }}
+  ?item rdfs:label ?name ;
+        wdt:P31 wd:Q5 ;
+        wikibase:sitelinks ?sitelinks .
+  OPTIONAL {{ ?item wdt:P569 ?birth . }}
+  OPTIONAL {{ ?item wdt:P570 ?death . }}
+  OPTIONAL {{ ?item wdt:P214 ?viaf . }}
+  OPTIONAL {{ ?article schema:about ?item ;
+                      schema:isPartOf <https://en.wikipedia.org/> . }}
+  OPTIONAL {{ ?item schema:description ?description . FILTER(lang(?description) = "en") }}
+}}"#,
+        values.join(" ")
+    )
+}
+
+fn binding<'a>(row: &'a Value, name: &str) -> &'a str {
+    row.get(name)
+        .and_then(|b| b.get("value"))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+}
+
+/// Wikidata dates come back as `1819-08-01T00:00:00Z`; keep the date part.
+fn date_part(value: &str) -> String {
+    value.split('T').next().unwrap_or("").to_string()
+}
+
+async fn query_chunk(client: &Client, names: &[String]) -> Option<Vec<Value>> {
+    run_query(client, query_for(names)).await
+}
+
+async fn run_query(client: &Client, query: String) -> Option<Vec<Value>> {
+    let response = client
+        .get(SPARQL_ENDPOINT)
+        .query(&[("query", query), ("format", "json".to_string())])
+        .header("Accept", "application/sparql-results+json")
+        .header(
+            "User-Agent",
+            "LOA-Scraper/1.0 (https://github.com/example/loa-scraper)",
+        )
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let json: Value = response.json().ok()?;
+    json.pointer("/results/bindings")?.as_array().cloned()
+}
+
+/// A runner-up with at least 1/`AMBIGUITY_RATIO` of the leader's sitelinks makes a
+/// name ambiguous; below that the leader is taken to be the well-known one.
+const AMBIGUITY_RATIO: u64 = 4;
+
+/// One of several people a name matched, with Wikidata's short description of them.
+#[derive(Debug, Clone, Default)]
+pub struct Candidate {
+    pub info: AuthorInfo,
+    pub description: String,
+    pub sitelinks: u64,
+}
+
+/// The outcome of resolving a batch of authors.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorResolution {
+    pub found: HashMap<String, AuthorInfo>,
+    /// Names whose query failed, as opposed to names Wikidata has no match for.
+    pub unreachable: HashSet<String>,
+    /// Every person matched by names that matched several comparably notable ones,
+    /// most sitelinks first. `found` holds the first of them.
+    pub ambiguous: HashMap<String, Vec<Candidate>>,
+}
+
+/// Resolves many authors with a few batched SPARQL queries. When a name matches several
+/// people, the one with the most sitelinks wins.
+pub async fn resolve_authors(client: &Client, names: &[String]) -> AuthorResolution {
+    // Every person each name matched, by QID
+    let mut matches: HashMap<String, HashMap<String, Candidate>> = HashMap::new();
+    let mut unreachable = HashSet::new();
+
+    for chunk in names.chunks(CHUNK_SIZE) {
+        let Some(rows) = query_chunk(client, chunk).await else {
+            unreachable.extend(chunk.iter().cloned());
+            continue;
+        };
+
+        for row in rows {
+            let name = binding(&row, "name").to_string();
+            let sitelinks = binding(&row, "sitelinks").parse().unwrap_or(0);
+            let info = AuthorInfo {
+                qid: binding(&row, "item")
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or("")
+                    .to_string(),
+                birth_date: date_part(binding(&row, "birth")),
+                death_date: date_part(binding(&row, "death")),
+                wikipedia_link: binding(&row, "article").to_string(),
+                viaf_id: binding(&row, "viaf").to_string(),
+            };
+
+            // A person with several dates or articles comes back in several rows
+            matches
+                .entry(name)
+                .or_default()
+                .entry(info.qid.clone())
+                .or_insert_with(|| Candidate {
+                    info,
+                    description: binding(&row, "description").to_string(),
+                    sitelinks,
+                });
+        }
+    }
+
+    let mut resolution = AuthorResolution {
+        unreachable,
+        ..Default::default()
+    };
+    for (name, people) in matches {
+        let mut candidates: Vec<Candidate> = people.into_values().collect();
+        candidates.sort_by(|a, b| {
+            b.sitelinks
+                .cmp(&a.sitelinks)
+                .then_with(|| a.info.qid.cmp(&b.info.qid))
+        });
+        resolution
+            .found
+            .insert(name.clone(), candidates[0].info.clone());
+        if candidates.len() > 1
+            && candidates[1].sitelinks * AMBIGUITY_RATIO >= candidates[0].sitelinks
+        {
+            resolution.ambiguous.insert(name, candidates);
+        }
+    }
+    resolution
+}
+
+/// A work Wikidata lists as one of an author's notable works (P800).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotableWork {
+    pub qid: String,
+    pub title: String,
+    /// Year of first publication, when Wikidata has one.
+    pub year: Option<i32>,
+}
+
+/// An author and their notable works, earliest first.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuthorWorks {
+    pub qid: String,
+    pub works: Vec<NotableWork>,
+}
+
+fn works_query(author: &str) -> String {
+    format!(
+        r#"SELECT ?author ?work ?title ?published WHERE {{
+  {{
+    SELECT ?author WHERE {{
+      ?author rdfs:label {} ;
+              wdt:P31 wd:Q5 ;
+              wikibase:sitelinks ?sitelinks .
+    }} ORDER BY DESC(?sitelinks) LIMIT 1
+  }}
+  ?author wdt:P800 ?work .
+  ?work rdfs:label ?title .
+  FILTER(LANG(?title) = "en")
+  OPTIONAL {{ ?work wdt:P577 ?published . }}
+}}"#,
+        sparql_string(author)
+    )
+}
+
+fn qid(uri: &str) -> String {
+    uri.rsplit('/').next().unwrap_or("").to_string()
+}
+
+/// The notable works Wikidata lists for an author, matched by exact English label
+/// (the person with the most sitelinks wins). `Ok(None)` when the author isn't found
+/// or has no notable works; an error when Wikidata can't be reached.
+pub async fn notable_works(client: &Client, author: &str) -> Result<Option<AuthorWorks>> {
+    let rows = run_query(client, works_query(author))
+        .await
+        .ok_or_else(|| anyhow!("Wikidata query for {} failed", author))?;
+
+    let mut found = AuthorWorks::default();
+    for row in rows {
+        found.qid = qid(binding(&row, "author"));
+        let year = binding(&row, "published")
+            .get(..4)
+            .and_then(|year| year.parse().ok());
+        let work = qid(binding(&row, "work"));
+        // A work with several publication dates comes back once per date
+        match found.works.iter_mut().find(|w| w.qid == work) {
+            Some(existing) => existing.year = existing.year.into_iter().chain(year).min(),
+            None => found.works.push(NotableWork {
+                qid: work,
+                title: binding(&row, "title").to_string(),
+                year,
+            }),
+        }
+    }
+    if found.works.is_empty() {
+        return Ok(None);
+    }
+    found.works.sort_by(|a, b| {
+        (a.year.is_none(), a.year, &a.title).cmp(&(b.year.is_none(), b.year, &b.title))
+    });
+    Ok(Some(found))
+}
@@ -0,0 +1,90 @@
+use crate::http::Client;
+use anyhow::{Result, anyhow};
+
+/// How many articles a search offers when the best match is in doubt.
+pub const SEARCH_CANDIDATES: usize = 5;
+
+/// An English Wikipedia article a search turned up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Article {
+    pub title: String,
+    /// Usually empty: OpenSearch only returns descriptions for some wikis.
+    pub description: String,
+    pub url: String,
+}
+
+/// Finds the English Wikipedia article for an author via OpenSearch. Returns an empty
+/// link when there is no match, and an error only when Wikipedia couldn't be reached.
+pub async fn get_wikipedia_link(client: &Client, author: &str) -> Result<String> {
+    Ok(search_wikipedia(client, author, 1)
+        .await?
+        .into_iter()
+        .next()
+        .map(|article| article.url)
+        .unwrap_or_default())
+}
+
+/// Up to `limit` articles matching an author's name, best first, via OpenSearch. Errors
+/// only when Wikipedia couldn't be reached.
+pub async fn search_wikipedia(client: &Client, author: &str, limit: usize) -> Result<Vec<Article>> {
+    // Skip if no author or if it's not a real author name
+    if author.is_empty() || author == "Unknown" {
+        return Ok(Vec::new());
+    }
+
+    let search_url = format!(
+        "https://en.wikipedia.org/w/api.php?action=opensearch&search={}&limit={}&format=json",
+        urlencoding::encode(author),
+        limit
+    );
+
+    let response = client
+        .get(&search_url)
+        .header(
+            "User-Agent",
+            "LOA-Scraper/1.0 (https://github.com/example/loa-scraper)",
+        )
+        .send()
+        .await?;
+    if response.status().is_server_error() {
+        return Err(anyhow!("Wikipedia returned {}", response.status()));
+    }
+
+    // OpenSearch API returns: [query, [titles], [descriptions], [urls]]. Anything
+    // else is likely an error page, which counts as no match.
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&response.text()) else {
+        return Ok(Vec::new());
+    };
+    let column = |i: usize| -> Vec<String> {
+        json.get(i)
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|v| v.as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let (titles, descriptions, urls) = (column(1), column(2), column(3));
+    Ok(urls
+        .into_iter()
+        .enumerate()
+        .filter(|(_, url)| !url.is_empty())
+        .map(|(i, url)| Article {
+            title: titles.get(i).cloned().unwrap_or_default(),
+            description: descriptions.get(i).cloned().unwrap_or_default(),
+            url,
+        })
+        .collect())
+}
+
+/// Whether the best search result is plainly the author's own article: its title is
+/// their name, give or take case and a parenthetical like "(writer)". Anything else,
+/// such as a disambiguation page or a namesake's article, is a guess.
+pub fn is_confident_match(author: &str, articles: &[Article]) -> bool {
+    articles.first().is_some_and(|article| {
+        let title = article.title.split(" (").next().unwrap_or_default();
+        !article.title.contains("(disambiguation)") && title.eq_ignore_ascii_case(author.trim())
+    })
+}
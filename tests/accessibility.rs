@@ -0,0 +1,336 @@
+//! Generated HTML must work from the keyboard and with a screen reader. A small
+//! WCAG-oriented checker covers what can be judged from the markup and stylesheet.
+
+use loa_scraper::Volume;
+use loa_scraper::output::{
+    OutputFormat, OutputOptions, write_timeline_html, write_volumes, write_volumes_with,
+};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashSet;
+use std::fs;
+
+/// WCAG 2.1 AA minimum contrast for normal-size text.
+const MIN_CONTRAST: f64 = 4.5;
+
+fn select<'a>(document: &'a Html, selector: &str) -> Vec<ElementRef<'a>> {
+    document
+        .select(&Selector::parse(selector).unwrap())
+        .collect()
+}
+
+fn text(element: &ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
+fn hex_color(value: &str) -> Option<[f64; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    let hex: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?].map(|c| c as f64 / 255.0))
+}
+
+fn luminance([r, g, b]: [f64; 3]) -> f64 {
+    let linear = |c: f64| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+fn contrast(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+struct CssRule {
+    /// The enclosing at-rule, e.g. `@media (prefers-color-scheme: dark)`, or empty.
+    context: String,
+    selector: String,
+    declarations: Vec<(String, String)>,
+}
+
+impl CssRule {
+    fn color(&self, property: &str) -> Option<[f64; 3]> {
+        self.declarations
+            .iter()
+            .find(|(p, _)| p == property)
+            .and_then(|(_, v)| hex_color(v.split_whitespace().next()?))
+    }
+}
+
+/// A stylesheet's rules, one level of `@media` deep.
+fn css_rules(css: &str) -> Vec<CssRule> {
+    let mut rules = Vec::new();
+    let mut context = String::new();
+    let mut rest = css;
+    while let Some(open) = rest.find('{') {
+        let selector = rest[..open].trim().to_string();
+        if selector.starts_with('@') {
+            context = selector;
+            rest = &rest[open + 1..];
+            continue;
+        }
+        let close = rest[open..].find('}').unwrap() + open;
+        let declarations = rest[open + 1..close]
+            .split(';')
+            .filter_map(|d| d.split_once(':'))
+            .map(|(p, v)| (p.trim().to_string(), v.trim().to_string()))
+            .collect();
+        rules.push(CssRule {
+            context: context.clone(),
+            selector,
+            declarations,
+        });
+        rest = &rest[close + 1..];
+        // The end of an @media block
+        let trimmed = rest.trim_start();
+        if let Some(after) = trimmed.strip_prefix('}') {
+            context.clear();
+            rest = after;
+        }
+    }
+    rules
+}
+
+/// Text colors against their background (the rule's own, else the page's) in the
+/// default and dark themes.
+fn contrast_problems(css: &str) -> Vec<String> {
+    let rules = css_rules(css);
+    let page_background = |context: &str| {
+        rules
+            .iter()
+            .find(|r| r.context == context && r.selector == "body")
+            .and_then(|r| r.color("background"))
+    };
+    let mut problems = Vec::new();
+    for rule in &rules {
+        let Some(color) = rule.color("color") else {
+            continue;
+        };
+        let background = rule
+            .color("background")
+            .or_else(|| rule.color("background-color"))
+            .or_else(|| page_background(&rule.context))
+            .or_else(|| page_background(""))
+            .unwrap();
+        let ratio = contrast(color, background);
+        if ratio < MIN_CONTRAST {
+            problems.push(format!(
+                "{} {} has contrast {:.1}:1",
+                rule.context, rule.selector, ratio
+            ));
+        }
+    }
+    problems
+}
+
+/// What keeps a page from being navigable by keyboard and screen reader.
+fn accessibility_problems(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let mut problems = Vec::new();
+
+    if select(&document, "html[lang]").is_empty() {
+        problems.push("<html> has no lang".to_string());
+    }
+    if select(&document, "title")
+        .iter()
+        .all(|t| text(t).is_empty())
+    {
+        problems.push("page has no title".to_string());
+    }
+    if select(&document, "main").len() != 1 {
+        problems.push("page needs exactly one <main> landmark".to_string());
+    }
+    if select(&document, "h1").len() != 1 {
+        problems.push("page needs exactly one <h1>".to_string());
+    }
+
+    for image in select(&document, "img") {
+        if image.attr("alt").is_none_or(|alt| alt.trim().is_empty()) {
+            problems.push(format!("image {:?} has no alt text", image.attr("src")));
+        }
+    }
+    for table in select(&document, "table") {
+        if table
+            .select(&Selector::parse("caption").unwrap())
+            .next()
+            .is_none()
+        {
+            problems.push("table has no caption".to_string());
+        }
+    }
+    for header in select(&document, "th") {
+        if header.attr("scope").is_none() {
+            problems.push(format!("header {:?} has no scope", text(&header)));
+        }
+        if header.attr("data-sort").is_some()
+            && header
+                .select(&Selector::parse("button").unwrap())
+                .next()
+                .is_none()
+        {
+            problems.push(format!(
+                "sortable header {:?} can't be reached by keyboard",
+                text(&header)
+            ));
+        }
+    }
+
+    let labelled: HashSet<&str> = select(&document, "label[for]")
+        .iter()
+        .filter_map(|l| l.attr("for"))
+        .collect();
+    for input in select(&document, "input, select, textarea") {
+        let in_label = input
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().name() == "label");
+        let named = in_label
+            || input.attr("aria-label").is_some()
+            || input.attr("aria-labelledby").is_some()
+            || input.attr("id").is_some_and(|id| labelled.contains(id));
+        if !named {
+            problems.push(format!("input {:?} has no label", input.attr("id")));
+        }
+    }
+    for link in select(&document, "a") {
+        if text(&link).is_empty() && link.attr("aria-label").is_none() {
+            problems.push(format!("link {:?} has no text", link.attr("href")));
+        }
+    }
+    for element in select(&document, "[tabindex]") {
+        if element
+            .attr("tabindex")
+            .and_then(|t| t.parse::<i32>().ok())
+            .is_some_and(|t| t > 0)
+        {
+            problems.push("positive tabindex breaks the reading order".to_string());
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for element in select(&document, "[id]") {
+        let id = element.attr("id").unwrap_or_default();
+        if !ids.insert(id) {
+            problems.push(format!("id {:?} is used twice", id));
+        }
+    }
+    for reference in select(&document, "[aria-labelledby]") {
+        let target = reference.attr("aria-labelledby").unwrap_or_default();
+        if select(&document, &format!("#{}", target)).is_empty() {
+            problems.push(format!("aria-labelledby points at missing #{}", target));
+        }
+    }
+
+    for style in select(&document, "style") {
+        problems.extend(contrast_problems(&text(&style)));
+    }
+    problems
+}
+
+fn volumes() -> Vec<Volume> {
+    vec![
+        Volume {
+            volume_number: 1,
+            title: "Typee, Omoo, Mardi".into(),
+            author: "Herman Melville".into(),
+            author_wikipedia_link: "https://en.wikipedia.org/wiki/Herman_Melville".into(),
+            author_birth_date: Some("1819-08-01".into()),
+            author_death_date: Some("1891-09-28".into()),
+            loa_detail_link: "/books/1-typee-omoo-mardi".into(),
+            own_volume: "yes".into(),
+            cover_image_link: "https://www.loa.org/media/1.jpg".into(),
+            ..Default::default()
+        },
+        Volume {
+            volume_number: 2,
+            title: "Novels 1930–1942 <& \"Stories\">".into(),
+            author: "Henry Green".into(),
+            loa_detail_link: "/books/2-novels".into(),
+            cover_image_link: "https://www.loa.org/media/2.jpg".into(),
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn html_report_is_accessible() {
+    let path = std::env::temp_dir().join(format!("loa-a11y-{}.html", std::process::id()));
+    write_volumes(&volumes(), OutputFormat::Html, Some(path.to_str().unwrap())).unwrap();
+    let html = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(accessibility_problems(&html), Vec::<String>::new());
+    assert!(html.contains("alt=\"Cover of Typee, Omoo, Mardi\""));
+}
+
+#[test]
+fn print_optimized_report_is_accessible() {
+    let path = std::env::temp_dir().join(format!("loa-a11y-print-{}.html", std::process::id()));
+    let options = OutputOptions {
+        print_optimized: true,
+        ..Default::default()
+    };
+    write_volumes_with(
+        &volumes(),
+        OutputFormat::Html,
+        Some(path.to_str().unwrap()),
+        &options,
+    )
+    .unwrap();
+    let html = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(accessibility_problems(&html), Vec::<String>::new());
+    assert!(html.contains("thead { display: table-header-group; }"));
+}
+
+#[test]
+fn timeline_is_accessible() {
+    let mut html = Vec::new();
+    write_timeline_html(&volumes(), &mut html).unwrap();
+    let html = String::from_utf8(html).unwrap();
+
+    assert_eq!(accessibility_problems(&html), Vec::<String>::new());
+}
+
+#[test]
+fn checker_flags_inaccessible_markup() {
+    let html = r#"<!DOCTYPE html><html><head><title></title>
+<style>body { color: #222; background: #fff; } .faint { color: #aaa; }</style></head>
+<body><h1>A</h1><h1>B</h1>
+<input id="q"><a href="/x"></a><img src="c.jpg">
+<table><tr><th data-sort="text">Title</th></tr></table>
+<span tabindex="2" id="s"></span><span id="s"></span></body></html>"#;
+
+    let problems = accessibility_problems(html);
+    for expected in [
+        "<html> has no lang",
+        "page has no title",
+        "page needs exactly one <main> landmark",
+        "page needs exactly one <h1>",
+        "image Some(\"c.jpg\") has no alt text",
+        "table has no caption",
+        "header \"Title\" has no scope",
+        "sortable header \"Title\" can't be reached by keyboard",
+        "input Some(\"q\") has no label",
+        "link Some(\"/x\") has no text",
+        "positive tabindex breaks the reading order",
+        "id \"s\" is used twice",
+        " .faint has contrast 2.3:1",
+    ] {
+        assert!(
+            problems.iter().any(|p| p == expected),
+            "missing {:?} in {:?}",
+            expected,
+            problems
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! The REST API `serve` exposes, answered without a socket.
+
+use hyper::{Method, StatusCode};
+use loa_scraper::Volume;
+use loa_scraper::api::respond;
+use serde_json::Value;
+
+fn volume(volume_number: u32, title: &str, author: &str) -> Volume {
+    Volume {
+        volume_number,
+        title: title.into(),
+        author: author.into(),
+        loa_detail_link: format!("/books/{}", volume_number),
+        ..Default::default()
+    }
+}
+
+fn volumes() -> Vec<Volume> {
+    vec![
+        Volume {
+            own_volume: "yes".into(),
+            publication_year: Some("1982".into()),
+            ..volume(1, "Typee, Omoo, Mardi", "Herman Melville")
+        },
+        volume(9, "Redburn, White-Jacket, Moby-Dick", "Herman Melville"),
+        volume(40, "Novels 1901–1910", "Henry James"),
+        Volume {
+            series: "aps".into(),
+            status: Some("forthcoming".into()),
+            ..volume(3, "Selected Poems", "Edna St. Vincent Millay")
+        },
+    ]
+}
+
+fn get(path: &str, query: Option<&str>) -> (StatusCode, Value) {
+    respond(&volumes(), &Method::GET, path, query)
+}
+
+fn titles(page: &Value) -> Vec<&str> {
+    page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["title"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn volumes_are_filtered_and_paged() {
+    let (status, page) = get("/volumes", None);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 4);
+
+    let (_, page) = get("/volumes", Some("author=MELVILLE&limit=1&offset=1"));
+    assert_eq!(page["total"], 2);
+    assert_eq!(titles(&page), ["Redburn, White-Jacket, Moby-Dick"]);
+
+    let (_, page) = get("/volumes", Some("owned=true"));
+    assert_eq!(titles(&page), ["Typee, Omoo, Mardi"]);
+    let (_, page) = get("/volumes", Some("series=loa&status=published&q=novels"));
+    assert_eq!(titles(&page), ["Novels 1901–1910"]);
+    let (_, page) = get("/volumes", Some("status=forthcoming"));
+    assert_eq!(titles(&page), ["Selected Poems"]);
+    let (_, page) = get("/volumes", Some("year=1982"));
+    assert_eq!(titles(&page), ["Typee, Omoo, Mardi"]);
+}
+
+#[test]
+fn single_volumes_are_found_by_number_or_key() {
+    let (status, volume) = get("/volumes/9", None);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(volume["title"], "Redburn, White-Jacket, Moby-Dick");
+
+    let (_, volume) = get("/volumes/aps-3", None);
+    assert_eq!(volume["title"], "Selected Poems");
+
+    // The main series' volume 3 doesn't exist, whatever other series have
+    let (status, error) = get("/volumes/3", None);
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["error"], "No volume 3");
+}
+
+#[test]
+fn authors_are_indexed_from_the_matching_volumes() {
+    let (_, page) = get("/authors", None);
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["items"][0]["name"], "Herman Melville");
+    assert_eq!(page["items"][0]["volume_numbers"], "1; 9");
+
+    let (_, page) = get("/authors", Some("series=aps"));
+    assert_eq!(page["items"][0]["name"], "Edna St. Vincent Millay");
+    assert_eq!(page["items"][0]["volume_numbers"], "APS 3");
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let (status, error) = get("/volumes", Some("auther=James"));
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error["error"].as_str().unwrap().contains("\"auther\""));
+
+    assert_eq!(
+        get("/volumes", Some("owned=maybe")).0,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(get("/volumes", Some("limit=-1")).0, StatusCode::BAD_REQUEST);
+    assert_eq!(get("/books", None).0, StatusCode::NOT_FOUND);
+    assert_eq!(
+        respond(&volumes(), &Method::DELETE, "/volumes/1", None).0,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+}
+
+#[cfg(feature = "graphql")]
+#[tokio::test]
+async fn graphql_follows_authors_to_their_volumes() {
+    use loa_scraper::graphql::{execute, schema};
+
+    let query = r#"{
+        author(name: "herman melville") { name volumes { key owned } }
+        volume(key: "aps-3") { volumeNumber series author { name } }
+        works(title: "novels") { title volume { key } }
+        volumes(filter: { owned: false, series: "loa" }, limit: 1) { title }
+    }"#;
+    let response = execute(&schema(), query.into(), volumes()).await;
+    assert!(response.is_ok(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+
+    assert_eq!(data["author"]["name"], "Herman Melville");
+    assert_eq!(
+        data["author"]["volumes"],
+        serde_json::json!([{ "key": "loa-1", "owned": true }, { "key": "loa-9", "owned": false }])
+    );
+    assert_eq!(data["volume"]["volumeNumber"], 3);
+    assert_eq!(data["volume"]["series"], "aps");
+    assert_eq!(data["volume"]["author"]["name"], "Edna St. Vincent Millay");
+    assert_eq!(data["works"][0]["volume"]["key"], "loa-40");
+    assert_eq!(
+        data["volumes"],
+        serde_json::json!([{ "title": "Redburn, White-Jacket, Moby-Dick" }])
+    );
+}
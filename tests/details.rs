@@ -0,0 +1,28 @@
+//! Detail-page fields must be found wherever they sit in the page text.
+
+use loa_scraper::details::{find_labeled_price, find_publication_year};
+
+#[test]
+fn prices_are_found_after_non_ascii_text() {
+    // "İ" lowercases to three bytes, so offsets into a lowercased copy drift
+    let text = "Orhan Pamuk (İstanbul) Price—$40.00 Member Price: $32.00";
+    assert_eq!(find_labeled_price(text, "price"), Some("40.00".into()));
+    assert_eq!(
+        find_labeled_price(text, "member price"),
+        Some("32.00".into())
+    );
+    assert_eq!(
+        find_labeled_price("KELVIN \u{212A} PRICE: $12.50", "price"),
+        Some("12.50".into())
+    );
+}
+
+#[test]
+fn publication_years_are_found_after_non_ascii_text() {
+    let text = "Orhan Pamuk (İstanbul) Published—March 1982";
+    assert_eq!(find_publication_year(text), Some("1982".into()));
+    assert_eq!(
+        find_publication_year("\u{212A}elvin Published: 2004"),
+        Some("2004".into())
+    );
+}
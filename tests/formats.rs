@@ -0,0 +1,170 @@
+//! Export formats must keep every volume distinct, whatever series it's from and
+//! whether or not it's numbered.
+
+use loa_scraper::Volume;
+use loa_scraper::output::{OutputFormat, write_volumes};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("loa-formats-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn volume(volume_number: u32, title: &str, link: &str) -> Volume {
+    Volume {
+        volume_number,
+        title: title.into(),
+        author: "Herman Melville".into(),
+        loa_detail_link: link.into(),
+        original_volume_name: format!("Herman Melville: {}", title),
+        ..Default::default()
+    }
+}
+
+/// LOA and APS volumes sharing a number, and two unnumbered publications.
+fn volumes() -> Vec<Volume> {
+    vec![
+        volume(1, "Typee, Omoo, Mardi", "/books/1-typee-omoo-mardi"),
+        Volume {
+            series: "aps".into(),
+            ..volume(1, "Selected Poems", "/books/aps-1-selected-poems")
+        },
+        volume(0, "Moby-Dick", "/books/moby-dick-paperback"),
+        volume(0, "Billy Budd", "/books/billy-budd-paperback"),
+    ]
+}
+
+fn write(format: OutputFormat, name: &str) -> String {
+    write_these(&volumes(), format, name)
+}
+
+fn write_these(volumes: &[Volume], format: OutputFormat, name: &str) -> String {
+    let path = temp_path(name);
+    write_volumes(volumes, format, Some(path.to_str().unwrap())).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    text
+}
+
+/// The values between `open` and the next `close` on each line that has them.
+fn values<'a>(text: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+    text.lines()
+        .filter_map(|line| line.split_once(open))
+        .filter_map(|(_, rest)| rest.split_once(close))
+        .map(|(value, _)| value)
+        .collect()
+}
+
+fn assert_distinct(ids: &[&str], what: &str) {
+    let unique: HashSet<&&str> = ids.iter().collect();
+    assert_eq!(unique.len(), ids.len(), "duplicate {}: {:?}", what, ids);
+}
+
+#[test]
+fn graph_nodes_are_distinct_across_series() {
+    let graphml = write(OutputFormat::Graphml, "graph.graphml");
+    let ids = values(&graphml, "<node id=\"", "\"");
+    assert_eq!(ids.len(), volumes().len() + 1);
+    assert_distinct(&ids, "GraphML node ids");
+
+    let dot = write(OutputFormat::Dot, "graph.dot");
+    let ids = values(&dot, "  \"", "\" [label");
+    assert_eq!(ids.len(), volumes().len() + 1);
+    assert_distinct(&ids, "DOT node ids");
+}
+
+#[test]
+fn opds_entries_are_distinct_across_series() {
+    let feed = write(OutputFormat::Opds, "feed.xml");
+    let ids = values(&feed, "<id>urn:loa:volume:", "</id>");
+    assert_eq!(ids.len(), volumes().len());
+    assert_distinct(&ids, "OPDS entry ids");
+    assert_eq!(
+        values(&feed, "<summary>", "</summary>"),
+        [
+            "Library of America volume 1",
+            "American Poets Project volume 1",
+            "Published by Library of America",
+            "Published by Library of America",
+        ]
+    );
+}
+
+#[test]
+fn unnumbered_publications_have_no_series_number() {
+    let unnumbered = &volumes()[2..];
+    for (format, name, numbered) in [
+        (
+            OutputFormat::CslJson,
+            "unnumbered.csl.json",
+            "collection-number",
+        ),
+        (OutputFormat::Ris, "unnumbered.ris", "SV  -"),
+        (OutputFormat::Jsonld, "unnumbered.jsonld", "\"position\""),
+        (OutputFormat::Librarything, "unnumbered-lt.csv", " ; "),
+        (OutputFormat::Marcxml, "unnumbered.xml", "code=\"v\""),
+        (OutputFormat::Turtle, "unnumbered.ttl", "schema:position"),
+    ] {
+        let text = write_these(unnumbered, format, name);
+        assert!(
+            !text.contains(numbered),
+            "{:?} numbered an unnumbered publication:\n{}",
+            format,
+            text
+        );
+    }
+
+    let marc = write_these(unnumbered, OutputFormat::Marcxml, "unnumbered-001.xml");
+    let control = values(&marc, "<controlfield tag=\"001\">", "</controlfield>");
+    assert_distinct(&control, "MARC control numbers");
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn empty_scrapes_convert_to_an_empty_arrow_batch() {
+    use loa_scraper::arrow::to_record_batch;
+
+    let empty = to_record_batch(&[]).unwrap();
+    assert_eq!(empty.num_rows(), 0);
+    let full = to_record_batch(&volumes()).unwrap();
+    assert_eq!(full.num_rows(), volumes().len());
+    for column in ["volume_number", "title", "author", "loa_detail_link"] {
+        assert!(
+            empty.schema().column_with_name(column).is_some(),
+            "empty batch has no {} column",
+            column
+        );
+    }
+}
+
+#[test]
+fn unnumbered_publications_get_their_own_cover_files() {
+    use loa_scraper::covers::cover_file;
+    use std::path::Path;
+
+    let dir = Path::new("covers");
+    let unnumbered = &volumes()[2..];
+    let files: Vec<_> = unnumbered.iter().map(|v| cover_file(dir, v)).collect();
+    assert_ne!(files[0], files[1]);
+    assert_eq!(
+        cover_file(dir, &volumes()[0]),
+        dir.join("001.jpg"),
+        "numbered covers kept their names"
+    );
+}
+
+#[test]
+fn series_sharing_a_number_get_their_own_cover_files() {
+    use loa_scraper::covers::cover_file;
+    use std::path::Path;
+
+    let dir = Path::new("covers");
+    assert_ne!(
+        cover_file(dir, &volumes()[0]),
+        cover_file(dir, &volumes()[1]),
+        "LOA and APS volume 1 share a cover file"
+    );
+}
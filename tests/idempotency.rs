@@ -0,0 +1,332 @@
+//! Re-running a command with the same inputs must leave every sink as it was.
+
+use loa_scraper::Volume;
+use loa_scraper::input::read_volumes;
+use loa_scraper::output::{OutputFormat, write_volumes};
+use loa_scraper::scrape::ScrapeOptions;
+use loa_scraper::store::Store;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("loa-idempotency-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn volume(volume_number: u32, title: &str) -> Volume {
+    Volume {
+        volume_number,
+        title: title.into(),
+        author: "Herman Melville".into(),
+        loa_detail_link: format!("/books/{}", volume_number),
+        original_volume_name: format!("Herman Melville: {}", title),
+        ..Default::default()
+    }
+}
+
+fn volumes() -> Vec<Volume> {
+    vec![
+        volume(1, "Typee, Omoo, Mardi"),
+        volume(9, "Redburn, White-Jacket, Moby-Dick"),
+    ]
+}
+
+#[test]
+fn file_outputs_are_identical_when_rewritten() {
+    for (format, name) in [
+        (OutputFormat::Csv, "twice.csv"),
+        (OutputFormat::Json, "twice.json"),
+    ] {
+        let path = temp_path(name);
+        let path_str = path.to_str().unwrap();
+
+        write_volumes(&volumes(), format, Some(path_str)).unwrap();
+        let first = fs::read(&path).unwrap();
+        write_volumes(&volumes(), format, Some(path_str)).unwrap();
+        let second = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(first, second, "{:?} changed on rewrite", format);
+    }
+}
+
+#[test]
+fn sqlite_upserts_instead_of_appending() {
+    let path = temp_path("twice.db");
+    let path_str = path.to_str().unwrap();
+
+    write_volumes(&volumes(), OutputFormat::Sqlite, Some(path_str)).unwrap();
+    write_volumes(&volumes(), OutputFormat::Sqlite, Some(path_str)).unwrap();
+    let read = read_volumes(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(read, volumes());
+}
+
+#[test]
+fn duplicate_volume_numbers_collapse_to_the_last_row() {
+    let mut scraped = volumes();
+    scraped.push(volume(1, "Typee, Omoo, Mardi (revised)"));
+    let expected = vec![
+        volume(1, "Typee, Omoo, Mardi (revised)"),
+        volume(9, "Redburn, White-Jacket, Moby-Dick"),
+    ];
+
+    for (format, name) in [
+        (OutputFormat::Csv, "duplicates.csv"),
+        (OutputFormat::Json, "duplicates.json"),
+        (OutputFormat::Sqlite, "duplicates.db"),
+    ] {
+        let path = temp_path(name);
+        let path_str = path.to_str().unwrap();
+
+        write_volumes(&scraped, format, Some(path_str)).unwrap();
+        let read = read_volumes(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, expected, "{:?} kept a duplicate row", format);
+    }
+}
+
+#[test]
+fn store_sync_twice_adds_nothing_the_second_time() {
+    let path = temp_path("store.db");
+    let options = ScrapeOptions::default();
+    let mut scraped = volumes();
+    scraped.push(volume(9, "Redburn, White-Jacket, Moby-Dick"));
+
+    let mut store = Store::open(&path).unwrap();
+    let first = store
+        .sync(&scraped, &options, "2024-01-01T00:00:00Z")
+        .unwrap();
+    let second = store
+        .sync(&scraped, &options, "2024-01-02T00:00:00Z")
+        .unwrap();
+    let count = store.count().unwrap();
+    let stored = store.volumes().unwrap();
+    drop(store);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(first.added, ["loa-1", "loa-9"]);
+    assert!(second.added.is_empty());
+    assert!(second.changed.is_empty());
+    assert_eq!(second.unchanged, 2);
+    assert_eq!(count, 2);
+    assert!(
+        stored
+            .iter()
+            .all(|v| v.first_seen.as_deref() == Some("2024-01-01T00:00:00Z")
+                && v.last_seen.as_deref() == Some("2024-01-02T00:00:00Z"))
+    );
+}
+
+#[test]
+fn series_number_their_volumes_separately() {
+    let mut scraped = volumes();
+    scraped.push(Volume {
+        series: "aps".into(),
+        ..volume(1, "Edna St. Vincent Millay: Selected Poems")
+    });
+
+    for (format, name) in [
+        (OutputFormat::Csv, "series.csv"),
+        (OutputFormat::Sqlite, "series.db"),
+    ] {
+        let path = temp_path(name);
+        let path_str = path.to_str().unwrap();
+
+        write_volumes(&scraped, format, Some(path_str)).unwrap();
+        write_volumes(&scraped, format, Some(path_str)).unwrap();
+        let read = read_volumes(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            read, scraped,
+            "{:?} merged volumes from different series",
+            format
+        );
+    }
+}
+
+#[test]
+fn sync_reports_volumes_by_key() {
+    let path = temp_path("keys.db");
+    let mut scraped = volumes();
+    scraped.push(Volume {
+        series: "aps".into(),
+        ..volume(1, "Edna St. Vincent Millay: Selected Poems")
+    });
+    scraped.push(Volume {
+        loa_detail_link: "/books/boxed-set".into(),
+        ..volume(0, "The Civil War Told by Those Who Lived It")
+    });
+    let options = ScrapeOptions {
+        include_unnumbered: true,
+        ..ScrapeOptions::default()
+    };
+
+    let mut store = Store::open(&path).unwrap();
+    let report = store
+        .sync(&scraped, &options, "2024-01-01T00:00:00Z")
+        .unwrap();
+    drop(store);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        report.added,
+        ["loa-1", "loa-9", "aps-1", "/books/boxed-set"]
+    );
+}
+
+#[test]
+fn delisted_volumes_stay_in_the_store() {
+    let path = temp_path("delisted.db");
+    let options = ScrapeOptions::default();
+    let mut owned = volumes();
+    owned[1].own_volume = "yes".into();
+
+    let mut store = Store::open(&path).unwrap();
+    store
+        .sync(&owned, &options, "2024-01-01T00:00:00Z")
+        .unwrap();
+    let first = store
+        .sync(&volumes()[..1], &options, "2024-02-01T00:00:00Z")
+        .unwrap();
+    let second = store
+        .sync(&volumes()[..1], &options, "2024-03-01T00:00:00Z")
+        .unwrap();
+    let stored = store.volumes().unwrap();
+    drop(store);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(first.missing, ["loa-9"]);
+    assert_eq!(second.missing, ["loa-9"]);
+    assert_eq!(first.delisted, ["loa-9"]);
+    assert!(second.delisted.is_empty());
+    let delisted = &stored[1];
+    assert_eq!(delisted.status.as_deref(), Some("delisted"));
+    assert_eq!(
+        delisted.delisted_on.as_deref(),
+        Some("2024-02-01T00:00:00Z")
+    );
+    assert_eq!(delisted.own_volume, "yes");
+}
+
+#[test]
+fn unnumbered_publications_are_kept_apart_by_detail_link() {
+    let mut scraped: Vec<Volume> = volumes()
+        .into_iter()
+        .map(|v| Volume {
+            publication_type: Some("numbered".into()),
+            ..v
+        })
+        .collect();
+    for (link, title) in [
+        (
+            "/books/poems-of-emily-dickinson",
+            "Poems of Emily Dickinson",
+        ),
+        ("/books/moby-dick-paperback", "Moby-Dick"),
+    ] {
+        scraped.push(Volume {
+            loa_detail_link: link.into(),
+            publication_type: Some("special edition".into()),
+            ..volume(0, title)
+        });
+    }
+
+    for (format, name) in [
+        (OutputFormat::Csv, "unnumbered.csv"),
+        (OutputFormat::Json, "unnumbered.json"),
+        (OutputFormat::Sqlite, "unnumbered.db"),
+    ] {
+        let path = temp_path(name);
+        let path_str = path.to_str().unwrap();
+
+        write_volumes(&scraped, format, Some(path_str)).unwrap();
+        write_volumes(&scraped, format, Some(path_str)).unwrap();
+        let read = read_volumes(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, scraped, "{:?} merged unnumbered publications", format);
+    }
+}
+
+#[test]
+fn store_views_list_forthcoming_titles_and_price_drops() {
+    let path = temp_path("views.db");
+    let options = ScrapeOptions {
+        include_forthcoming: true,
+        ..ScrapeOptions::default()
+    };
+    let priced = |price: &str| -> Vec<Volume> {
+        let mut scraped = volumes();
+        scraped[0].price_usd = Some(price.into());
+        scraped[1].price_usd = Some("40.00".into());
+        scraped.push(Volume {
+            status: Some("forthcoming".into()),
+            release_date: Some("2025-03-04".into()),
+            ..volume(400, "Collected Stories")
+        });
+        scraped
+    };
+
+    let mut store = Store::open(&path).unwrap();
+    store
+        .sync(&priced("45.00"), &options, "2024-01-01T00:00:00Z")
+        .unwrap();
+    store
+        .sync(&priced("35.00"), &options, "2024-02-01T00:00:00Z")
+        .unwrap();
+    // An unchanged price keeps the drop on record
+    store
+        .sync(&priced("35.00"), &options, "2024-03-01T00:00:00Z")
+        .unwrap();
+    drop(store);
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    let forthcoming: Vec<u32> = conn
+        .prepare("SELECT volume_number FROM forthcoming")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let drops: Vec<(u32, String, String, f64)> = conn
+        .prepare(
+            "SELECT volume_number, previous_price_usd, price_usd, price_drop_usd FROM price_drops",
+        )
+        .unwrap()
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    drop(conn);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(forthcoming, vec![400]);
+    assert_eq!(drops, vec![(1, "45.00".into(), "35.00".into(), 10.0)]);
+}
+
+#[test]
+fn report_views_exist_before_any_volume_has_their_columns() {
+    let path = temp_path("bare-views.db");
+    write_volumes(&volumes(), OutputFormat::Sqlite, path.to_str()).unwrap();
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    for view in ["forthcoming", "price_drops"] {
+        let count: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", view), [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0, "{} listed a plain volume", view);
+    }
+    drop(conn);
+    assert_eq!(read_volumes(&path).unwrap(), volumes());
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,68 @@
+//! loa.org's robots.txt must be honored, and an unreadable one must not lift its limits.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use loa_scraper::http::{Backend, Client, Request, Response};
+use loa_scraper::robots::Disallowed;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+
+/// Serves robots.txt with `robots` (or fails to connect when it's `None`), and an
+/// empty page for everything else.
+struct Site {
+    robots: Option<(StatusCode, &'static str)>,
+}
+
+#[async_trait]
+impl Backend for Site {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let (status, body) = if request.url.path() == "/robots.txt" {
+            self.robots.ok_or_else(|| anyhow!("connection refused"))?
+        } else {
+            (StatusCode::OK, "")
+        };
+        Ok(Response::new(status, HeaderMap::new(), body.into()))
+    }
+}
+
+async fn fetch(robots: Option<(StatusCode, &'static str)>, url: &str) -> Result<Response> {
+    Client::with_backend(Site { robots }).get(url).send().await
+}
+
+fn refusal(result: Result<Response>) -> Disallowed {
+    result
+        .expect_err("request was allowed")
+        .downcast::<Disallowed>()
+        .expect("request failed for another reason")
+}
+
+#[tokio::test]
+async fn disallowed_paths_are_refused() {
+    let robots = Some((StatusCode::OK, "User-agent: *\nDisallow: /account/\n"));
+    assert!(fetch(robots, "https://www.loa.org/books/").await.is_ok());
+    let refused = refusal(fetch(robots, "https://www.loa.org/account/login").await);
+    assert!(refused.unreadable.is_none());
+}
+
+#[tokio::test]
+async fn a_missing_robots_txt_allows_everything() {
+    let robots = Some((StatusCode::NOT_FOUND, "Not found"));
+    assert!(fetch(robots, "https://www.loa.org/books/").await.is_ok());
+}
+
+#[tokio::test]
+async fn an_unreadable_robots_txt_disallows_everything() {
+    for robots in [
+        Some((StatusCode::SERVICE_UNAVAILABLE, "Down for maintenance")),
+        None,
+    ] {
+        let refused = refusal(fetch(robots, "https://www.loa.org/books/").await);
+        assert!(refused.unreadable.is_some(), "{:?}", robots);
+    }
+    // Other hosts don't consult loa.org's robots.txt
+    assert!(
+        fetch(None, "https://en.wikipedia.org/wiki/Herman_Melville")
+            .await
+            .is_ok()
+    );
+}
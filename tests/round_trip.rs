@@ -0,0 +1,168 @@
+//! Files written by one subcommand must read back identically in every other.
+
+use loa_scraper::input::{read_csv, read_volumes};
+use loa_scraper::output::{OutputFormat, write_csv_records, write_volumes};
+use loa_scraper::ownership::OwnedState;
+use loa_scraper::{Author, Ownership, Volume, Work};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("loa-round-trip-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// A plain scrape: only the always-present columns.
+fn minimal_volumes() -> Vec<Volume> {
+    vec![
+        Volume {
+            volume_number: 1,
+            title: "Typee, Omoo, Mardi".into(),
+            author: "Herman Melville".into(),
+            author_wikipedia_link: "https://en.wikipedia.org/wiki/Herman_Melville".into(),
+            loa_detail_link: "/books/1-typee-omoo-mardi".into(),
+            original_volume_name: "Herman Melville: Typee, Omoo, Mardi".into(),
+            own_volume: "yes".into(),
+            cover_image_link: "https://www.loa.org/media/1.jpg".into(),
+            ..Default::default()
+        },
+        Volume {
+            volume_number: 3,
+            title: "American Poetry: The Nineteenth Century, \"Vol. 1\"".into(),
+            original_volume_name: "American Poetry, with a comma".into(),
+            ..Default::default()
+        },
+    ]
+}
+
+/// A fully enriched scrape, including fields looked up but found empty.
+fn enriched_volumes() -> Vec<Volume> {
+    minimal_volumes()
+        .into_iter()
+        .map(|volume| {
+            let found = volume.volume_number == 1;
+            let text = |value: &str| {
+                Some(if found {
+                    value.to_string()
+                } else {
+                    String::new()
+                })
+            };
+            Volume {
+                author_wikidata_id: text("Q4985"),
+                author_viaf_id: text("27068555"),
+                author_birth_date: text("1819-08-01"),
+                author_death_date: text("1891-09-28"),
+                enrichment_status: Some(String::new()),
+                status: text("published"),
+                release_date: text("1982-05-01"),
+                // Already in series order, which is how a database reads back
+                series: if found { "aps" } else { "loa" }.into(),
+                delisted_on: text("2024-09-01"),
+                publication_type: text("Boxed Set"),
+                archived_detail_link: Some(String::new()),
+                cover_path: text("covers/001.jpg"),
+                isbn: text("9780940450004"),
+                publication_year: text("1982"),
+                price_usd: text("45.00"),
+                member_price_usd: text("36.00"),
+                availability: text("In stock"),
+                in_print: text("true"),
+                raw_listing_html: text("<li class=\"content-listing\">\n</li>"),
+                openlibrary_work_id: text("OL45804W"),
+                openlibrary_edition_id: text("OL7353617M"),
+                openlibrary_cover_url: text("https://covers.openlibrary.org/b/id/1-L.jpg"),
+                rating_average: text("4.12"),
+                ratings_count: text("17"),
+                oclc_number: text("8667498"),
+                lccn: text("82009980"),
+                loc_permalink: text("https://lccn.loc.gov/82009980"),
+                first_seen: text("2024-01-01T00:00:00Z"),
+                last_seen: text("2024-06-01T00:00:00Z"),
+                previous_price_usd: text("40.00"),
+                ..volume
+            }
+        })
+        .collect()
+}
+
+fn round_trip_volumes(volumes: &[Volume], format: OutputFormat, extension: &str) {
+    let path = temp_path(&format!("{:?}-{}.{}", format, volumes.len(), extension));
+    write_volumes(volumes, format, Some(&path.to_string_lossy())).unwrap();
+    let read = read_volumes(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(read, volumes, "{:?} round trip", format);
+}
+
+fn round_trip_csv<T: Serialize + DeserializeOwned + PartialEq + Debug>(name: &str, records: &[T]) {
+    let path = temp_path(&format!("{}.csv", name));
+    write_csv_records(records, &mut File::create(&path).unwrap()).unwrap();
+    let read: Vec<T> = read_csv(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(read, records, "{} CSV round trip", name);
+}
+
+fn round_trip_json<T: Serialize + DeserializeOwned + PartialEq + Debug>(name: &str, records: &[T]) {
+    let json = serde_json::to_string(records).unwrap();
+    let read: Vec<T> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, records, "{} JSON round trip", name);
+}
+
+#[test]
+fn volumes_round_trip_through_csv() {
+    round_trip_volumes(&minimal_volumes(), OutputFormat::Csv, "csv");
+    round_trip_volumes(&enriched_volumes(), OutputFormat::Csv, "csv");
+}
+
+#[test]
+fn volumes_round_trip_through_json() {
+    round_trip_volumes(&minimal_volumes(), OutputFormat::Json, "json");
+    round_trip_volumes(&enriched_volumes(), OutputFormat::Json, "json");
+}
+
+#[test]
+fn volumes_round_trip_through_sqlite() {
+    round_trip_volumes(&minimal_volumes(), OutputFormat::Sqlite, "db");
+    round_trip_volumes(&enriched_volumes(), OutputFormat::Sqlite, "db");
+}
+
+#[test]
+fn volumes_round_trip_through_protobuf() {
+    round_trip_volumes(&minimal_volumes(), OutputFormat::Protobuf, "pb");
+    round_trip_volumes(&enriched_volumes(), OutputFormat::Protobuf, "pb");
+}
+
+#[test]
+fn sqlite_keeps_fields_only_some_volumes_carry() {
+    // The sync store mixes rows written with and without --details
+    let mut volumes = minimal_volumes();
+    volumes[0].isbn = Some("9780940450004".into());
+    round_trip_volumes(&volumes, OutputFormat::Sqlite, "db");
+}
+
+#[test]
+fn model_types_round_trip() {
+    let volumes = enriched_volumes();
+    let authors: Vec<Author> = volumes.iter().filter_map(Author::from_volume).collect();
+    let works: Vec<Work> = volumes.iter().map(Work::from_volume).collect();
+
+    let mut state = OwnedState::default();
+    state.add(1, "2024-03-01");
+    let ownership: Vec<Ownership> = volumes
+        .iter()
+        .map(|v| state.ownership(v.volume_number))
+        .collect();
+
+    round_trip_csv("authors", &authors);
+    round_trip_json("authors", &authors);
+    round_trip_csv("works", &works);
+    round_trip_json("works", &works);
+    round_trip_json("ownership", &ownership);
+
+    let owned: Vec<Ownership> = ownership.into_iter().filter(|o| o.owned).collect();
+    round_trip_csv("ownership", &owned);
+}
@@ -0,0 +1,107 @@
+//! The listing checks must flag a changed collection page, and only that.
+
+use loa_scraper::sanity::{SanityWarning, check_listings};
+use loa_scraper::scrape::VolumeData;
+
+fn listing(volume_number: u32, series: &str, link: &str) -> VolumeData {
+    VolumeData {
+        volume_number,
+        title: format!("Volume {}", volume_number),
+        author: String::new(),
+        loa_detail_link: link.into(),
+        original_volume_name: format!("Volume {}", volume_number),
+        cover_image_link: String::new(),
+        status: String::new(),
+        release_date: String::new(),
+        series: series.into(),
+        publication_type: String::new(),
+        raw_listing_html: String::new(),
+    }
+}
+
+#[test]
+fn unnumbered_publications_and_other_series_are_not_duplicates() {
+    let listings = vec![
+        listing(1, "", "/books/1"),
+        listing(1, "aps", "/books/aps-1"),
+        listing(0, "", "/books/moby-dick-paperback"),
+        listing(0, "", "/books/billy-budd-paperback"),
+    ];
+    assert_eq!(check_listings(&listings, None), vec![]);
+
+    let mut repeated = listings;
+    repeated.push(listing(1, "", "/books/1-again"));
+    assert_eq!(
+        check_listings(&repeated, None),
+        vec![SanityWarning::DuplicateNumbers(vec![1])]
+    );
+}
+
+#[test]
+fn only_listed_volumes_in_range_are_compared() {
+    use loa_scraper::Volume;
+    use loa_scraper::sanity::comparable_count;
+    use loa_scraper::scrape::ScrapeOptions;
+
+    let volume = |volume_number: u32, series: &str, status: Option<&str>| Volume {
+        volume_number,
+        series: series.into(),
+        status: status.map(str::to_string),
+        loa_detail_link: format!("/books/{}-{}", series, volume_number),
+        ..Default::default()
+    };
+    let stored = vec![
+        volume(1, "", None),
+        volume(2, "", Some("published")),
+        volume(3, "", Some("delisted")),
+        volume(4, "", Some("forthcoming")),
+        volume(0, "", None),
+        volume(1, "aps", None),
+        volume(50, "", None),
+    ];
+
+    assert_eq!(comparable_count(&stored, &ScrapeOptions::default()), 3);
+    let narrower = ScrapeOptions {
+        end: Some(10),
+        ..ScrapeOptions::default()
+    };
+    assert_eq!(comparable_count(&stored, &narrower), 2);
+}
+
+#[test]
+fn existing_outputs_count_only_comparable_volumes() {
+    use loa_scraper::Volume;
+    use loa_scraper::output::{OutputFormat, existing_volume_count, write_volumes};
+    use loa_scraper::scrape::ScrapeOptions;
+
+    let volume = |volume_number: u32, series: &str, status: Option<&str>| Volume {
+        volume_number,
+        title: format!("Volume {}", volume_number),
+        series: series.into(),
+        status: status.map(str::to_string),
+        loa_detail_link: format!("/books/{}-{}", series, volume_number),
+        ..Default::default()
+    };
+    let written = vec![
+        volume(1, "", Some("published")),
+        volume(2, "", Some("published")),
+        volume(3, "", Some("forthcoming")),
+        volume(0, "", None),
+        volume(1, "aps", None),
+    ];
+    let options = ScrapeOptions::default();
+
+    for (format, name) in [
+        (OutputFormat::Csv, "count.csv"),
+        (OutputFormat::Json, "count.json"),
+        (OutputFormat::Sqlite, "count.db"),
+    ] {
+        let path = std::env::temp_dir().join(format!("loa-sanity-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let path_str = path.to_str().unwrap();
+        write_volumes(&written, format, Some(path_str)).unwrap();
+        let count = existing_volume_count(path_str, format, &options);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(count, Some(2), "{:?} counted other volumes", format);
+    }
+}
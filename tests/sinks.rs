@@ -0,0 +1,90 @@
+//! Outputs that do async work must be writable from sync code and from inside either
+//! flavor of Tokio runtime, as library callers may use any of them.
+
+use loa_scraper::Volume;
+use loa_scraper::output::{OutputFormat, OutputOptions, write_volumes_with};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("loa-sinks-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn volumes() -> Vec<Volume> {
+    vec![Volume {
+        volume_number: 1,
+        title: "Typee, Omoo, Mardi".into(),
+        author: "Herman Melville".into(),
+        loa_detail_link: "/books/1-typee-omoo-mardi".into(),
+        original_volume_name: "Herman Melville: Typee, Omoo, Mardi".into(),
+        ..Default::default()
+    }]
+}
+
+fn write_self_contained_html(name: &str) {
+    let path = temp_path(name);
+    let options = OutputOptions {
+        self_contained: true,
+        ..OutputOptions::default()
+    };
+    write_volumes_with(&volumes(), OutputFormat::Html, path.to_str(), &options).unwrap();
+    let html = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(html.contains("Typee, Omoo, Mardi"));
+}
+
+#[test]
+fn self_contained_html_is_written_without_a_runtime() {
+    write_self_contained_html("no-runtime.html");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn self_contained_html_is_written_on_a_current_thread_runtime() {
+    write_self_contained_html("current-thread.html");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn self_contained_html_is_written_on_a_multi_thread_runtime() {
+    write_self_contained_html("multi-thread.html");
+}
+
+#[test]
+fn self_contained_html_inlines_only_downloaded_covers() {
+    let cover = temp_path("001.png");
+    fs::write(&cover, b"not really a png").unwrap();
+    let mut volumes = vec![volumes()[0].clone(), volumes()[0].clone()];
+    volumes[0].cover_image_link = "https://www.loa.org/media/1.png".into();
+    volumes[0].cover_path = Some(cover.to_string_lossy().into_owned());
+    volumes[1].volume_number = 2;
+    volumes[1].cover_image_link = "https://www.loa.org/media/2.jpg".into();
+
+    let path = temp_path("covers.html");
+    let options = OutputOptions {
+        self_contained: true,
+        ..OutputOptions::default()
+    };
+    write_volumes_with(&volumes, OutputFormat::Html, path.to_str(), &options).unwrap();
+    let html = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&cover).unwrap();
+
+    assert!(html.contains("data:image/png;base64,bm90IHJlYWxseSBhIHBuZw=="));
+    // Not downloaded, so left out rather than fetched or linked
+    assert!(!html.contains("loa.org/media"));
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test(flavor = "current_thread")]
+async fn postgres_reports_errors_on_a_current_thread_runtime() {
+    use loa_scraper::output::write_volumes;
+
+    // Nothing listens on port 1, so the write fails rather than panicking
+    let result = write_volumes(
+        &volumes(),
+        OutputFormat::Csv,
+        Some("postgres://loa@127.0.0.1:1/loa"),
+    );
+    assert!(result.is_err());
+}